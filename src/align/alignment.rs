@@ -0,0 +1,114 @@
+use crate::alphabet::Alphabet;
+use crate::distance::{EditOp, EditScript};
+use crate::seq::Seq;
+
+use super::{AlignmentMode, Cigar};
+
+/// Columns per block in [`Alignment::pretty`].
+const PRETTY_WIDTH: usize = 60;
+
+/// The result of aligning `x` against `y`.
+///
+/// Coordinates are 0-based and half-open; the CIGAR covers exactly
+/// `x[x_start..x_end]` and `y[y_start..y_end]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    pub score: i32,
+    pub x_start: usize,
+    pub x_end: usize,
+    pub y_start: usize,
+    pub y_end: usize,
+    pub cigar: EditScript,
+    pub mode: AlignmentMode,
+}
+
+impl Alignment {
+    /// Returns the number of alignment columns.
+    pub fn len(&self) -> usize {
+        self.cigar.ops().iter().map(|(_, n)| n).sum()
+    }
+
+    /// Checks if no symbols were aligned.
+    pub fn is_empty(&self) -> bool {
+        self.cigar.ops().is_empty()
+    }
+
+    /// Returns the alignment columns as a CIGAR, writing matches as `=` and
+    /// mismatches as `X`. Use [`Cigar::from_alignment`] for soft clips.
+    pub fn to_cigar(&self) -> Cigar {
+        Cigar::from(&self.cigar)
+    }
+
+    /// Returns the fraction of alignment columns that are matches.
+    pub fn identity(&self) -> f64 {
+        let matches = self.cigar.iter().filter(|&op| op == EditOp::Match).count();
+        match self.len() {
+            0 => 0.0,
+            len => matches as f64 / len as f64,
+        }
+    }
+
+    /// Renders the alignment in blocks of three lines: `x`, a match line
+    /// (`|` match, `.` mismatch) and `y`, with 1-based start and end
+    /// coordinates around each row.
+    pub fn pretty<A: Alphabet>(&self, x: &Seq<A>, y: &Seq<A>) -> String {
+        let (mut x_row, mut marks, mut y_row) = (Vec::new(), Vec::new(), Vec::new());
+        let (mut i, mut j) = (self.x_start, self.y_start);
+        for op in self.cigar.iter() {
+            let (a, b, mark) = match op {
+                EditOp::Match | EditOp::Mismatch => {
+                    let mark = if op == EditOp::Match { b'|' } else { b'.' };
+                    i += 1;
+                    j += 1;
+                    (A::to_byte(x.get(i - 1)), A::to_byte(y.get(j - 1)), mark)
+                }
+                EditOp::Insertion => {
+                    i += 1;
+                    (A::to_byte(x.get(i - 1)), b'-', b' ')
+                }
+                EditOp::Deletion => {
+                    j += 1;
+                    (b'-', A::to_byte(y.get(j - 1)), b' ')
+                }
+            };
+            x_row.push(a);
+            marks.push(mark);
+            y_row.push(b);
+        }
+
+        // Width of the coordinate column
+        let pad = self.x_end.max(self.y_end).to_string().len();
+        let mut out = String::new();
+        let (mut x_pos, mut y_pos) = (self.x_start, self.y_start);
+        for start in (0..x_row.len()).step_by(PRETTY_WIDTH) {
+            let end = (start + PRETTY_WIDTH).min(x_row.len());
+            let x_block = &x_row[start..end];
+            let y_block = &y_row[start..end];
+            let x_next = x_pos + x_block.iter().filter(|&&c| c != b'-').count();
+            let y_next = y_pos + y_block.iter().filter(|&&c| c != b'-').count();
+
+            if start > 0 {
+                out.push('\n');
+            }
+            out.push_str(&format!(
+                "x {:>pad$} {} {}\n",
+                x_pos + 1,
+                String::from_utf8_lossy(x_block),
+                x_next
+            ));
+            out.push_str(&format!(
+                "  {:>pad$} {}\n",
+                "",
+                String::from_utf8_lossy(&marks[start..end])
+            ));
+            out.push_str(&format!(
+                "y {:>pad$} {} {}\n",
+                y_pos + 1,
+                String::from_utf8_lossy(y_block),
+                y_next
+            ));
+            (x_pos, y_pos) = (x_next, y_next);
+        }
+        out
+    }
+}
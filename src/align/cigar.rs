@@ -0,0 +1,320 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::alphabet::Alphabet;
+use crate::distance::{EditOp, EditScript};
+use crate::seq::Seq;
+
+use super::Alignment;
+
+/// A CIGAR operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CigarOp {
+    /// Alignment match, either equal or not (`M`).
+    Match,
+    /// Insertion to the reference (`I`).
+    Insertion,
+    /// Deletion from the reference (`D`).
+    Deletion,
+    /// Skipped reference region, such as an intron (`N`).
+    Skip,
+    /// Clipped bases present in SEQ (`S`).
+    SoftClip,
+    /// Clipped bases absent from SEQ (`H`).
+    HardClip,
+    /// Silent deletion from a padded reference (`P`).
+    Padding,
+    /// Sequence match (`=`).
+    Equal,
+    /// Sequence mismatch (`X`).
+    Mismatch,
+}
+
+impl CigarOp {
+    /// Operations in the order of their BAM codes.
+    pub const ALL: [CigarOp; 9] = [
+        CigarOp::Match,
+        CigarOp::Insertion,
+        CigarOp::Deletion,
+        CigarOp::Skip,
+        CigarOp::SoftClip,
+        CigarOp::HardClip,
+        CigarOp::Padding,
+        CigarOp::Equal,
+        CigarOp::Mismatch,
+    ];
+
+    pub fn from_char(c: char) -> Option<Self> {
+        let index = "MIDNSHP=X".find(c)?;
+        Some(Self::ALL[index])
+    }
+
+    pub fn to_char(self) -> char {
+        b"MIDNSHP=X"[self as usize] as char
+    }
+
+    /// Checks if the operation steps along the query (SEQ).
+    pub fn consumes_query(self) -> bool {
+        matches!(
+            self,
+            CigarOp::Match
+                | CigarOp::Insertion
+                | CigarOp::SoftClip
+                | CigarOp::Equal
+                | CigarOp::Mismatch
+        )
+    }
+
+    /// Checks if the operation steps along the reference.
+    pub fn consumes_reference(self) -> bool {
+        matches!(
+            self,
+            CigarOp::Match | CigarOp::Deletion | CigarOp::Skip | CigarOp::Equal | CigarOp::Mismatch
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CigarError {
+    /// An empty string; a missing CIGAR is written `*`.
+    Empty,
+    /// A character that is not an operation.
+    InvalidOperation(char),
+    /// An operation without a preceding length.
+    MissingLength(char),
+    /// Digits after the last operation.
+    TrailingLength,
+    /// A length that does not fit 32 bits.
+    LengthOverflow,
+}
+
+impl fmt::Display for CigarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CigarError::Empty => write!(f, "empty CIGAR"),
+            CigarError::InvalidOperation(c) => write!(f, "invalid CIGAR operation {c}"),
+            CigarError::MissingLength(c) => write!(f, "CIGAR operation {c} has no length"),
+            CigarError::TrailingLength => write!(f, "CIGAR ends with a length"),
+            CigarError::LengthOverflow => write!(f, "CIGAR length out of range"),
+        }
+    }
+}
+
+impl std::error::Error for CigarError {}
+
+/// Run-length encoded alignment of a query against a reference, as used by
+/// SAM and BAM.
+///
+/// Query positions count every base of SEQ, soft clips included; reference
+/// positions are offsets from the first aligned reference base.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Cigar {
+    ops: Vec<(CigarOp, u32)>,
+}
+
+impl Cigar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the runs as `(op, length)` pairs.
+    pub fn ops(&self) -> &[(CigarOp, u32)] {
+        &self.ops
+    }
+
+    /// Checks if there are no runs, printed as `*`.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Appends `len` operations, extending the last run if it is the same
+    /// operation.
+    pub fn push(&mut self, op: CigarOp, len: u32) {
+        if len == 0 {
+            return;
+        }
+        match self.ops.last_mut() {
+            Some((last, n)) if *last == op => *n += len,
+            _ => self.ops.push((op, len)),
+        }
+    }
+
+    /// Returns an iterator over the individual (unrolled) operations.
+    pub fn iter(&self) -> impl Iterator<Item = CigarOp> + '_ {
+        self.ops
+            .iter()
+            .flat_map(|&(op, n)| std::iter::repeat_n(op, n as usize))
+    }
+
+    /// Returns the number of query bases, which is the length of SEQ.
+    pub fn query_len(&self) -> usize {
+        self.span(CigarOp::consumes_query)
+    }
+
+    /// Returns the number of reference bases covered.
+    pub fn reference_len(&self) -> usize {
+        self.span(CigarOp::consumes_reference)
+    }
+
+    fn span(&self, consumes: fn(CigarOp) -> bool) -> usize {
+        self.ops
+            .iter()
+            .filter(|(op, _)| consumes(*op))
+            .map(|&(_, n)| n as usize)
+            .sum()
+    }
+
+    /// Returns the reference position a query position is aligned to, or
+    /// `None` for inserted and clipped bases.
+    pub fn reference_pos(&self, query_pos: usize) -> Option<usize> {
+        let (mut query, mut reference) = (0, 0);
+        for &(op, n) in &self.ops {
+            let n = n as usize;
+            if op.consumes_query() {
+                if query_pos < query + n {
+                    return op
+                        .consumes_reference()
+                        .then(|| reference + query_pos - query);
+                }
+                query += n;
+            }
+            if op.consumes_reference() {
+                reference += n;
+            }
+        }
+        None
+    }
+
+    /// Returns the query and reference positions of every operation that
+    /// consumes either; the side an operation skips is `None`.
+    pub fn aligned_pairs(&self) -> impl Iterator<Item = (Option<usize>, Option<usize>)> + '_ {
+        let (mut query, mut reference) = (0, 0);
+        self.iter()
+            .filter(|op| op.consumes_query() || op.consumes_reference())
+            .map(move |op| {
+                let q = op.consumes_query().then(|| {
+                    query += 1;
+                    query - 1
+                });
+                let r = op.consumes_reference().then(|| {
+                    reference += 1;
+                    reference - 1
+                });
+                (q, r)
+            })
+    }
+
+    /// Returns the CIGAR with `=` and `X` runs merged into `M`.
+    pub fn collapse_matches(&self) -> Cigar {
+        let mut cigar = Cigar::new();
+        for &(op, n) in &self.ops {
+            let op = match op {
+                CigarOp::Equal | CigarOp::Mismatch => CigarOp::Match,
+                op => op,
+            };
+            cigar.push(op, n);
+        }
+        cigar
+    }
+
+    /// Builds the CIGAR of an alignment of query `x` against reference `y`,
+    /// comparing the aligned symbols for `=` and `X` and soft clipping the
+    /// unaligned ends of `x`.
+    ///
+    /// The alignment starts at `alignment.y_start` on the reference.
+    pub fn from_alignment<A: Alphabet>(alignment: &Alignment, x: &Seq<A>, y: &Seq<A>) -> Self {
+        let mut cigar = Cigar::new();
+        cigar.push(CigarOp::SoftClip, alignment.x_start as u32);
+        let (mut i, mut j) = (alignment.x_start, alignment.y_start);
+        for op in alignment.cigar.iter() {
+            match op {
+                EditOp::Match | EditOp::Mismatch => {
+                    let op = if x.get_bits(i) == y.get_bits(j) {
+                        CigarOp::Equal
+                    } else {
+                        CigarOp::Mismatch
+                    };
+                    cigar.push(op, 1);
+                    i += 1;
+                    j += 1;
+                }
+                EditOp::Insertion => {
+                    cigar.push(CigarOp::Insertion, 1);
+                    i += 1;
+                }
+                EditOp::Deletion => {
+                    cigar.push(CigarOp::Deletion, 1);
+                    j += 1;
+                }
+            }
+        }
+        cigar.push(CigarOp::SoftClip, (x.len() - alignment.x_end) as u32);
+        cigar
+    }
+}
+
+impl From<Vec<(CigarOp, u32)>> for Cigar {
+    /// Keeps the runs as given, even adjacent ones of the same operation.
+    fn from(ops: Vec<(CigarOp, u32)>) -> Self {
+        Self { ops }
+    }
+}
+
+impl From<&EditScript> for Cigar {
+    fn from(script: &EditScript) -> Self {
+        let mut cigar = Cigar::new();
+        for &(op, n) in script.ops() {
+            let op = match op {
+                EditOp::Match => CigarOp::Equal,
+                EditOp::Mismatch => CigarOp::Mismatch,
+                EditOp::Insertion => CigarOp::Insertion,
+                EditOp::Deletion => CigarOp::Deletion,
+            };
+            cigar.push(op, n as u32);
+        }
+        cigar
+    }
+}
+
+impl FromStr for Cigar {
+    type Err = CigarError;
+
+    /// Parses runs of `<length><op>`, or `*` for no CIGAR.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut ops = Vec::new();
+        match text {
+            "*" => return Ok(Self { ops }),
+            "" => return Err(CigarError::Empty),
+            _ => {}
+        }
+        let mut len: Option<u32> = None;
+        for c in text.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                let value = len
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(digit));
+                len = Some(value.ok_or(CigarError::LengthOverflow)?);
+                continue;
+            }
+            let op = CigarOp::from_char(c).ok_or(CigarError::InvalidOperation(c))?;
+            ops.push((op, len.take().ok_or(CigarError::MissingLength(c))?));
+        }
+        match len {
+            Some(_) => Err(CigarError::TrailingLength),
+            None => Ok(Self { ops }),
+        }
+    }
+}
+
+impl fmt::Display for Cigar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ops.is_empty() {
+            return write!(f, "*");
+        }
+        for (op, n) in &self.ops {
+            write!(f, "{n}{}", op.to_char())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::alphabet::{Alphabet, Nucleotide, AA20};
+
+/// Scores for aligning one symbol of alphabet `A` against another.
+///
+/// Tying the matrix to an alphabet keeps e.g. a BLOSUM table from being
+/// used on DNA.
+pub trait SubstitutionMatrix<A: Alphabet> {
+    /// Returns the score of aligning two packed symbols.
+    fn score_bits(&self, a: u8, b: u8) -> i32;
+
+    /// Returns the score of aligning two symbols.
+    fn score(&self, a: A::Elements, b: A::Elements) -> i32 {
+        self.score_bits(a.into(), b.into())
+    }
+}
+
+impl<A: Alphabet, M: SubstitutionMatrix<A>> SubstitutionMatrix<A> for &M {
+    #[inline]
+    fn score_bits(&self, a: u8, b: u8) -> i32 {
+        (*self).score_bits(a, b)
+    }
+}
+
+/// One score for identical symbols and one for all others, for any alphabet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchMismatch {
+    pub match_score: i32,
+    pub mismatch_score: i32,
+}
+
+impl MatchMismatch {
+    pub fn new(match_score: i32, mismatch_score: i32) -> Self {
+        Self {
+            match_score,
+            mismatch_score,
+        }
+    }
+}
+
+impl<A: Alphabet> SubstitutionMatrix<A> for MatchMismatch {
+    #[inline]
+    fn score_bits(&self, a: u8, b: u8) -> i32 {
+        if a == b {
+            self.match_score
+        } else {
+            self.mismatch_score
+        }
+    }
+}
+
+/// A 20x20 amino acid matrix indexed by `AminoAcid` discriminants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProteinMatrix {
+    scores: [[i8; 20]; 20],
+}
+
+impl ProteinMatrix {
+    pub const fn new(scores: [[i8; 20]; 20]) -> Self {
+        Self { scores }
+    }
+
+    pub fn scores(&self) -> &[[i8; 20]; 20] {
+        &self.scores
+    }
+}
+
+impl SubstitutionMatrix<AA20> for ProteinMatrix {
+    #[inline]
+    fn score_bits(&self, a: u8, b: u8) -> i32 {
+        self.scores[a as usize][b as usize] as i32
+    }
+}
+
+impl FromStr for ProteinMatrix {
+    type Err = MatrixError;
+
+    /// Parses an NCBI matrix file; ambiguity codes and `*` are ignored.
+    fn from_str(s: &str) -> Result<Self, MatrixError> {
+        const SYMBOLS: [&[u8]; 20] = [
+            b"A", b"C", b"D", b"E", b"F", b"G", b"H", b"I", b"K", b"L", b"M", b"N", b"P", b"Q",
+            b"R", b"S", b"T", b"V", b"W", b"Y",
+        ];
+        parse_ncbi(s, &SYMBOLS).map(Self::new)
+    }
+}
+
+/// A 5x5 nucleotide matrix indexed by `Nucleotide` discriminants.
+///
+/// Works with both `Nuc4` and `Nuc5`; the `N` row is only reachable from
+/// the latter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NucleotideMatrix {
+    scores: [[i8; 5]; 5],
+}
+
+impl NucleotideMatrix {
+    pub const fn new(scores: [[i8; 5]; 5]) -> Self {
+        Self { scores }
+    }
+
+    pub fn scores(&self) -> &[[i8; 5]; 5] {
+        &self.scores
+    }
+}
+
+impl<A: Alphabet<Elements = Nucleotide>> SubstitutionMatrix<A> for NucleotideMatrix {
+    #[inline]
+    fn score_bits(&self, a: u8, b: u8) -> i32 {
+        self.scores[a as usize][b as usize] as i32
+    }
+}
+
+impl FromStr for NucleotideMatrix {
+    type Err = MatrixError;
+
+    /// Parses an NCBI matrix file such as `NUC.4.4`, using the `A`, `C`, `G`,
+    /// `T` (or `U`) and `N` rows.
+    fn from_str(s: &str) -> Result<Self, MatrixError> {
+        const SYMBOLS: [&[u8]; 5] = [b"A", b"C", b"G", b"TU", b"N"];
+        parse_ncbi(s, &SYMBOLS).map(Self::new)
+    }
+}
+
+// -- NCBI format -------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixError {
+    MissingHeader,
+    InvalidScore { line: usize },
+    RowLength { line: usize },
+    MissingSymbol(char),
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatrixError::MissingHeader => write!(f, "matrix has no column header"),
+            MatrixError::InvalidScore { line } => write!(f, "invalid score on line {line}"),
+            MatrixError::RowLength { line } => {
+                write!(f, "row on line {line} does not match the header")
+            }
+            MatrixError::MissingSymbol(c) => write!(f, "matrix has no entry for {c}"),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+/// Parses the whitespace separated NCBI layout: `#` comments, a header of
+/// column symbols and one labelled row per symbol.
+///
+/// `symbols[i]` lists the accepted labels for output index `i`, in order of
+/// preference.
+fn parse_ncbi<const N: usize>(
+    text: &str,
+    symbols: &[&[u8]; N],
+) -> Result<[[i8; N]; N], MatrixError> {
+    let mut header: Option<Vec<u8>> = None;
+    let mut rows: Vec<(u8, Vec<i8>)> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some(columns) = &header else {
+            header = Some(line.split_whitespace().map(|t| t.as_bytes()[0]).collect());
+            continue;
+        };
+
+        let mut fields = line.split_whitespace();
+        let label = fields.next().unwrap().as_bytes()[0];
+        let scores = fields
+            .map(|t| t.parse::<i8>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| MatrixError::InvalidScore { line: number + 1 })?;
+        if scores.len() != columns.len() {
+            return Err(MatrixError::RowLength { line: number + 1 });
+        }
+        rows.push((label.to_ascii_uppercase(), scores));
+    }
+
+    let header = header.ok_or(MatrixError::MissingHeader)?;
+    let find = |labels: &[u8], candidates: &[u8]| {
+        candidates
+            .iter()
+            .find_map(|c| labels.iter().position(|l| l.to_ascii_uppercase() == *c))
+            .ok_or(MatrixError::MissingSymbol(candidates[0] as char))
+    };
+
+    let labels: Vec<u8> = rows.iter().map(|(label, _)| *label).collect();
+    let mut matrix = [[0i8; N]; N];
+    for (i, row_symbol) in symbols.iter().enumerate() {
+        let row = &rows[find(&labels, row_symbol)?].1;
+        for (j, col_symbol) in symbols.iter().enumerate() {
+            matrix[i][j] = row[find(&header, col_symbol)?];
+        }
+    }
+    Ok(matrix)
+}
@@ -0,0 +1,13 @@
+mod alignment;
+mod cigar;
+mod matrix;
+mod pairwise;
+mod striped;
+mod tables;
+
+pub use alignment::*;
+pub use cigar::*;
+pub use matrix::*;
+pub use pairwise::*;
+pub use striped::*;
+pub use tables::*;
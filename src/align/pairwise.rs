@@ -0,0 +1,258 @@
+use crate::alphabet::Alphabet;
+use crate::distance::EditOp;
+use crate::seq::Seq;
+
+use super::{Alignment, MatchMismatch, SubstitutionMatrix};
+
+/// Score of unreachable DP cells; low enough to never win, high enough to
+/// not overflow when penalties are added.
+const NEG_INF: i32 = i32::MIN / 4;
+
+/// A substitution matrix plus affine gap penalties.
+///
+/// A gap of length `n` scores `gap_open + n * gap_extend`, so penalties are
+/// usually negative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scoring<M = MatchMismatch> {
+    pub matrix: M,
+    pub gap_open: i32,
+    pub gap_extend: i32,
+}
+
+impl Scoring<MatchMismatch> {
+    pub fn new(match_score: i32, mismatch_score: i32, gap_open: i32, gap_extend: i32) -> Self {
+        Self::with_matrix(
+            MatchMismatch::new(match_score, mismatch_score),
+            gap_open,
+            gap_extend,
+        )
+    }
+}
+
+impl<M> Scoring<M> {
+    pub fn with_matrix(matrix: M, gap_open: i32, gap_extend: i32) -> Self {
+        Self {
+            matrix,
+            gap_open,
+            gap_extend,
+        }
+    }
+
+    /// Returns the score of a gap of `len` symbols.
+    pub fn gap(&self, len: usize) -> i32 {
+        if len == 0 {
+            0
+        } else {
+            self.gap_open + len as i32 * self.gap_extend
+        }
+    }
+}
+
+impl Default for Scoring<MatchMismatch> {
+    /// Match 1, mismatch -1, gap open -5, gap extend -1.
+    fn default() -> Self {
+        Self::new(1, -1, -5, -1)
+    }
+}
+
+/// Which parts of the two sequences have to take part in the alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlignmentMode {
+    /// Needleman-Wunsch: both sequences end to end.
+    Global,
+    /// Smith-Waterman: the best scoring pair of substrings.
+    Local,
+    /// The first sequence end to end, anywhere within the second one.
+    SemiGlobal,
+    /// A suffix of one sequence against a prefix of the other; end gaps are free.
+    Overlap,
+}
+
+/// Pairwise aligner with affine gap penalties (Gotoh).
+///
+/// Runs in `O(nm)` time and memory; the full DP matrices are kept for the
+/// traceback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aligner<M = MatchMismatch> {
+    mode: AlignmentMode,
+    scoring: Scoring<M>,
+}
+
+/// DP state a traceback step is in.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Best score over all three matrices.
+    Best,
+    /// Inside a run of insertions (symbols only in `x`).
+    Insert,
+    /// Inside a run of deletions (symbols only in `y`).
+    Delete,
+}
+
+impl<M> Aligner<M> {
+    pub fn new(mode: AlignmentMode, scoring: Scoring<M>) -> Self {
+        Self { mode, scoring }
+    }
+
+    pub fn global(scoring: Scoring<M>) -> Self {
+        Self::new(AlignmentMode::Global, scoring)
+    }
+
+    pub fn local(scoring: Scoring<M>) -> Self {
+        Self::new(AlignmentMode::Local, scoring)
+    }
+
+    pub fn semi_global(scoring: Scoring<M>) -> Self {
+        Self::new(AlignmentMode::SemiGlobal, scoring)
+    }
+
+    pub fn overlap(scoring: Scoring<M>) -> Self {
+        Self::new(AlignmentMode::Overlap, scoring)
+    }
+
+    pub fn mode(&self) -> AlignmentMode {
+        self.mode
+    }
+
+    pub fn scoring(&self) -> &Scoring<M> {
+        &self.scoring
+    }
+
+    /// Aligns `x` against `y`.
+    ///
+    /// Insertions in the resulting CIGAR are symbols of `x` missing from `y`,
+    /// deletions the other way round. Among equally good alignments, gaps
+    /// are placed as far left as possible.
+    pub fn align<A: Alphabet>(&self, x: &Seq<A>, y: &Seq<A>) -> Alignment
+    where
+        M: SubstitutionMatrix<A>,
+    {
+        let (m, n) = (x.len(), y.len());
+        let cols = n + 1;
+        let sc = &self.scoring;
+        let local = self.mode == AlignmentMode::Local;
+        // Whether leading gaps in `y` (row 0) and in `x` (column 0) are free
+        let free_y = self.mode != AlignmentMode::Global;
+        let free_x = matches!(self.mode, AlignmentMode::Local | AlignmentMode::Overlap);
+
+        // `best` is the overall score, `ins`/`del` end in a gap
+        let mut best = vec![NEG_INF; (m + 1) * cols];
+        let mut ins = vec![NEG_INF; (m + 1) * cols];
+        let mut del = vec![NEG_INF; (m + 1) * cols];
+
+        best[0] = 0;
+        for j in 1..=n {
+            best[j] = if free_y { 0 } else { sc.gap(j) };
+            del[j] = best[j];
+        }
+        for i in 1..=m {
+            best[i * cols] = if free_x { 0 } else { sc.gap(i) };
+            ins[i * cols] = best[i * cols];
+        }
+
+        let mut local_max = (0, 0, 0);
+        for i in 1..=m {
+            let symbol = x.get_bits(i - 1);
+            for j in 1..=n {
+                let cell = i * cols + j;
+                ins[cell] = (best[cell - cols] + sc.gap_open + sc.gap_extend)
+                    .max(ins[cell - cols] + sc.gap_extend);
+                del[cell] = (best[cell - 1] + sc.gap_open + sc.gap_extend)
+                    .max(del[cell - 1] + sc.gap_extend);
+                let diagonal =
+                    best[cell - cols - 1] + sc.matrix.score_bits(symbol, y.get_bits(j - 1));
+
+                let mut score = diagonal.max(ins[cell]).max(del[cell]);
+                if local {
+                    score = score.max(0);
+                    if score > local_max.0 {
+                        local_max = (score, i, j);
+                    }
+                }
+                best[cell] = score;
+            }
+        }
+
+        // Pick the cell the alignment ends in
+        let (score, mut i, mut j) = match self.mode {
+            AlignmentMode::Global => (best[m * cols + n], m, n),
+            AlignmentMode::Local => local_max,
+            AlignmentMode::SemiGlobal => (0..=n)
+                .map(|j| (best[m * cols + j], m, j))
+                .max_by_key(|&(s, _, j)| (s, std::cmp::Reverse(j)))
+                .unwrap(),
+            AlignmentMode::Overlap => (0..=n)
+                .map(|j| (best[m * cols + j], m, j))
+                .chain((0..=m).map(|i| (best[i * cols + n], i, n)))
+                .max_by_key(|&(s, i, j)| (s, std::cmp::Reverse(i + j)))
+                .unwrap(),
+        };
+        let (x_end, y_end) = (i, j);
+
+        let mut ops = Vec::new();
+        let mut state = State::Best;
+        loop {
+            let cell = i * cols + j;
+            if local && state == State::Best && best[cell] == 0 {
+                break;
+            }
+            if i == 0 {
+                if !free_y {
+                    ops.extend(std::iter::repeat_n(EditOp::Deletion, j));
+                    j = 0;
+                }
+                break;
+            }
+            if j == 0 {
+                if !free_x {
+                    ops.extend(std::iter::repeat_n(EditOp::Insertion, i));
+                    i = 0;
+                }
+                break;
+            }
+
+            match state {
+                State::Best => {
+                    let (a, b) = (x.get_bits(i - 1), y.get_bits(j - 1));
+                    if best[cell] == best[cell - cols - 1] + sc.matrix.score_bits(a, b) {
+                        ops.push(if a == b {
+                            EditOp::Match
+                        } else {
+                            EditOp::Mismatch
+                        });
+                        i -= 1;
+                        j -= 1;
+                    } else if best[cell] == ins[cell] {
+                        state = State::Insert;
+                    } else {
+                        state = State::Delete;
+                    }
+                }
+                State::Insert => {
+                    ops.push(EditOp::Insertion);
+                    if ins[cell] == best[cell - cols] + sc.gap_open + sc.gap_extend {
+                        state = State::Best;
+                    }
+                    i -= 1;
+                }
+                State::Delete => {
+                    ops.push(EditOp::Deletion);
+                    if del[cell] == best[cell - 1] + sc.gap_open + sc.gap_extend {
+                        state = State::Best;
+                    }
+                    j -= 1;
+                }
+            }
+        }
+
+        Alignment {
+            score,
+            x_start: i,
+            x_end,
+            y_start: j,
+            y_end,
+            cigar: ops.into_iter().rev().collect(),
+            mode: self.mode,
+        }
+    }
+}
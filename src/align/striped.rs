@@ -0,0 +1,425 @@
+use std::simd::prelude::*;
+
+use crate::alphabet::Alphabet;
+use crate::seq::Seq;
+
+use super::{Aligner, Alignment, AlignmentMode, Scoring, SubstitutionMatrix};
+
+/// Best local alignment score and where it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalHit {
+    pub score: i32,
+    /// Exclusive end of the alignment in the query.
+    pub query_end: usize,
+    /// Exclusive end of the alignment in the target.
+    pub target_end: usize,
+}
+
+/// A query prepared for striped Smith-Waterman (Farrar, 2007).
+///
+/// The query is laid out in stripes so that one vector holds cells that do
+/// not depend on each other; scores for every target symbol are precomputed.
+/// Alignments first run in 16 saturating 8-bit lanes, are retried in 8
+/// 16-bit lanes if the score may have saturated, and fall back to the
+/// scalar [`Aligner`] beyond that.
+#[derive(Debug, Clone)]
+pub struct QueryProfile<A: Alphabet> {
+    query: Seq<A>,
+    /// Substitution scores, indexed by `query symbol * SIZE + target symbol`.
+    table: Table,
+    gap_open: i32,
+    gap_extend: i32,
+    bytes: Option<Profile<Bytes>>,
+    words: Option<Profile<Words>>,
+}
+
+impl<A: Alphabet> QueryProfile<A> {
+    /// Builds the striped profiles for `query`.
+    ///
+    /// Panics if the gap penalties are positive.
+    pub fn new<M: SubstitutionMatrix<A>>(query: &Seq<A>, scoring: &Scoring<M>) -> Self {
+        assert!(
+            scoring.gap_open <= 0 && scoring.gap_extend <= 0,
+            "gap penalties must not be positive"
+        );
+        let size = A::SIZE as usize;
+        let mut scores = Vec::with_capacity(size * size);
+        for a in 0..A::SIZE {
+            for b in 0..A::SIZE {
+                scores.push(scoring.matrix.score_bits(a, b));
+            }
+        }
+        let table = Table { size, scores };
+
+        Self {
+            bytes: Profile::new(query, &table, scoring),
+            words: Profile::new(query, &table, scoring),
+            query: query.clone(),
+            table,
+            gap_open: scoring.gap_open,
+            gap_extend: scoring.gap_extend,
+        }
+    }
+
+    pub fn query(&self) -> &Seq<A> {
+        &self.query
+    }
+
+    /// Returns the best local alignment score against `target` (score-only
+    /// mode).
+    ///
+    /// Ties resolve to the smallest target end, then the smallest query end.
+    pub fn score(&self, target: &Seq<A>) -> LocalHit {
+        if let Some(hit) = self.bytes.as_ref().and_then(|p| p.align(target)) {
+            return hit;
+        }
+        if let Some(hit) = self.words.as_ref().and_then(|p| p.align(target)) {
+            return hit;
+        }
+        let alignment = self.scalar(AlignmentMode::Local, &self.query, target);
+        LocalHit {
+            score: alignment.score,
+            query_end: alignment.x_end,
+            target_end: alignment.y_end,
+        }
+    }
+
+    /// Returns the best local alignment against `target` including its
+    /// CIGAR (traceback mode).
+    ///
+    /// The start is found with a second striped pass over the reversed
+    /// prefixes, so the quadratic traceback only covers the aligned region.
+    pub fn align(&self, target: &Seq<A>) -> Alignment {
+        let hit = self.score(target);
+        if hit.score <= 0 {
+            return self.scalar(AlignmentMode::Local, &Seq::new(0), &Seq::new(0));
+        }
+
+        let query = reversed(&self.query.slice(..hit.query_end));
+        let target_prefix = reversed(&target.slice(..hit.target_end));
+        let scoring = Scoring::with_matrix(&self.table, self.gap_open, self.gap_extend);
+        let back = QueryProfile::new(&query, &scoring).score(&target_prefix);
+        let (query_start, target_start) = (
+            hit.query_end - back.query_end,
+            hit.target_end - back.target_end,
+        );
+
+        let region = self.scalar(
+            AlignmentMode::Global,
+            &self.query.slice(query_start..hit.query_end),
+            &target.slice(target_start..hit.target_end),
+        );
+        if back.score == hit.score && region.score == hit.score {
+            return Alignment {
+                x_start: query_start,
+                x_end: hit.query_end,
+                y_start: target_start,
+                y_end: hit.target_end,
+                mode: AlignmentMode::Local,
+                ..region
+            };
+        }
+
+        // The reverse pass picked a different optimum; align the full prefixes
+        self.scalar(
+            AlignmentMode::Local,
+            &self.query.slice(..hit.query_end),
+            &target.slice(..hit.target_end),
+        )
+    }
+
+    fn scalar(&self, mode: AlignmentMode, x: &Seq<A>, y: &Seq<A>) -> Alignment {
+        let scoring = Scoring::with_matrix(&self.table, self.gap_open, self.gap_extend);
+        Aligner::new(mode, scoring).align(x, y)
+    }
+}
+
+/// Returns `seq` with its symbols in reverse order.
+fn reversed<A: Alphabet>(seq: &Seq<A>) -> Seq<A> {
+    let mut out = Seq::new(seq.len());
+    for i in 0..seq.len() {
+        out.init_with(seq.len() - 1 - i, seq.get_bits(i));
+    }
+    out
+}
+
+/// Dense copy of a substitution matrix over packed symbols.
+#[derive(Debug, Clone)]
+struct Table {
+    size: usize,
+    scores: Vec<i32>,
+}
+
+impl<A: Alphabet> SubstitutionMatrix<A> for Table {
+    #[inline]
+    fn score_bits(&self, a: u8, b: u8) -> i32 {
+        self.scores[a as usize * self.size + b as usize]
+    }
+}
+
+// -- Lanes -------------------------------------------------------------------
+
+/// A vector of score cells with saturating arithmetic.
+trait Lanes: Copy + PartialEq + std::fmt::Debug {
+    const LEN: usize;
+    const MIN: i32;
+    const MAX: i32;
+
+    fn splat(value: i32) -> Self;
+    fn from_fn(f: impl FnMut(usize) -> i32) -> Self;
+    fn lane(self, k: usize) -> i32;
+    fn max(self, other: Self) -> Self;
+    fn saturating_sub(self, other: Self) -> Self;
+
+    /// Adds a (biased) profile score to diagonal cells, clamping at zero.
+    fn add_score(self, score: Self, bias: Self) -> Self;
+
+    /// Moves every lane up by one, shifting in zero.
+    fn shift(self) -> Self;
+
+    fn any_gt(self, other: Self) -> bool;
+    fn reduce_max(self) -> i32;
+}
+
+/// 16 unsigned 8-bit lanes.
+type Bytes = Simd<u8, 16>;
+
+/// 8 signed 16-bit lanes.
+type Words = Simd<i16, 8>;
+
+impl Lanes for Bytes {
+    const LEN: usize = 16;
+    const MIN: i32 = 0;
+    const MAX: i32 = u8::MAX as i32;
+
+    fn splat(value: i32) -> Self {
+        Simd::splat(value as u8)
+    }
+
+    fn from_fn(mut f: impl FnMut(usize) -> i32) -> Self {
+        Simd::from_array(std::array::from_fn(|k| f(k) as u8))
+    }
+
+    fn lane(self, k: usize) -> i32 {
+        self[k] as i32
+    }
+
+    #[inline(always)]
+    fn max(self, other: Self) -> Self {
+        self.simd_max(other)
+    }
+
+    #[inline(always)]
+    fn saturating_sub(self, other: Self) -> Self {
+        SimdUint::saturating_sub(self, other)
+    }
+
+    #[inline(always)]
+    fn add_score(self, score: Self, bias: Self) -> Self {
+        SimdUint::saturating_sub(SimdUint::saturating_add(self, score), bias)
+    }
+
+    #[inline(always)]
+    fn shift(self) -> Self {
+        self.shift_elements_right::<1>(0)
+    }
+
+    #[inline(always)]
+    fn any_gt(self, other: Self) -> bool {
+        self.simd_gt(other).any()
+    }
+
+    fn reduce_max(self) -> i32 {
+        SimdUint::reduce_max(self) as i32
+    }
+}
+
+impl Lanes for Words {
+    const LEN: usize = 8;
+    const MIN: i32 = i16::MIN as i32;
+    const MAX: i32 = i16::MAX as i32;
+
+    fn splat(value: i32) -> Self {
+        Simd::splat(value as i16)
+    }
+
+    fn from_fn(mut f: impl FnMut(usize) -> i32) -> Self {
+        Simd::from_array(std::array::from_fn(|k| f(k) as i16))
+    }
+
+    fn lane(self, k: usize) -> i32 {
+        self[k] as i32
+    }
+
+    #[inline(always)]
+    fn max(self, other: Self) -> Self {
+        self.simd_max(other)
+    }
+
+    #[inline(always)]
+    fn saturating_sub(self, other: Self) -> Self {
+        SimdInt::saturating_sub(self, other)
+    }
+
+    #[inline(always)]
+    fn add_score(self, score: Self, _bias: Self) -> Self {
+        SimdInt::saturating_add(self, score).simd_max(Simd::splat(0))
+    }
+
+    #[inline(always)]
+    fn shift(self) -> Self {
+        self.shift_elements_right::<1>(0)
+    }
+
+    #[inline(always)]
+    fn any_gt(self, other: Self) -> bool {
+        self.simd_gt(other).any()
+    }
+
+    fn reduce_max(self) -> i32 {
+        SimdInt::reduce_max(self) as i32
+    }
+}
+
+// -- Striped kernel ----------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct Profile<V> {
+    /// Number of vectors per stripe; query position `k * seg_len + i` lives
+    /// in lane `k` of vector `i`.
+    seg_len: usize,
+    query_len: usize,
+    /// Scores (plus `bias`) indexed by `target symbol * seg_len + i`.
+    vectors: Vec<V>,
+    bias: V,
+    gap_open: V,
+    gap_extend: V,
+    /// Scores at or above this may have saturated.
+    limit: i32,
+}
+
+impl<V: Lanes> Profile<V> {
+    /// Returns `None` if the scoring scheme does not fit the lane type.
+    fn new<A: Alphabet, M: SubstitutionMatrix<A>>(
+        query: &Seq<A>,
+        table: &Table,
+        scoring: &Scoring<M>,
+    ) -> Option<Self> {
+        let lowest = table.scores.iter().copied().min().unwrap_or(0).min(0);
+        let highest = table.scores.iter().copied().max().unwrap_or(0).max(0);
+        // Unsigned lanes store scores shifted up by `bias`
+        let bias = if V::MIN == 0 { -lowest } else { 0 };
+        let gap_open = -(scoring.gap_open + scoring.gap_extend);
+        let gap_extend = -scoring.gap_extend;
+        if lowest + bias < V::MIN || highest + bias > V::MAX || gap_open > V::MAX {
+            return None;
+        }
+
+        let seg_len = query.len().div_ceil(V::LEN);
+        let mut vectors = Vec::with_capacity(A::SIZE as usize * seg_len);
+        for symbol in 0..A::SIZE {
+            for i in 0..seg_len {
+                vectors.push(V::from_fn(|k| {
+                    let pos = k * seg_len + i;
+                    // Padding past the query end scores as low as possible
+                    let score = if pos < query.len() {
+                        table.scores[query.get_bits(pos) as usize * table.size + symbol as usize]
+                    } else {
+                        lowest
+                    };
+                    score + bias
+                }));
+            }
+        }
+
+        Some(Self {
+            seg_len,
+            query_len: query.len(),
+            vectors,
+            bias: V::splat(bias),
+            gap_open: V::splat(gap_open),
+            gap_extend: V::splat(gap_extend),
+            limit: V::MAX - highest - bias,
+        })
+    }
+
+    /// Runs the striped recurrence, returning `None` on possible saturation.
+    fn align<A: Alphabet>(&self, target: &Seq<A>) -> Option<LocalHit> {
+        let seg_len = self.seg_len;
+        let mut hit = LocalHit {
+            score: 0,
+            query_end: 0,
+            target_end: 0,
+        };
+        if seg_len == 0 {
+            return Some(hit);
+        }
+
+        let zero = V::splat(0);
+        let mut h_load = vec![zero; seg_len];
+        let mut h_store = vec![zero; seg_len];
+        let mut e = vec![zero; seg_len];
+
+        for j in 0..target.len() {
+            let symbol = target.get_bits(j) as usize;
+            let profile = &self.vectors[symbol * seg_len..(symbol + 1) * seg_len];
+            let mut column_max = zero;
+            let mut f = zero;
+            let mut h = h_store[seg_len - 1].shift();
+            std::mem::swap(&mut h_load, &mut h_store);
+
+            for i in 0..seg_len {
+                h = h.add_score(profile[i], self.bias);
+                column_max = column_max.max(h);
+                h = h.max(e[i]).max(f);
+                h_store[i] = h;
+
+                let opened = h.saturating_sub(self.gap_open);
+                e[i] = e[i].saturating_sub(self.gap_extend).max(opened);
+                f = f.saturating_sub(self.gap_extend).max(opened);
+                h = h_load[i];
+            }
+
+            // Lazy F loop: carry vertical gaps across stripe boundaries until
+            // they no longer change any cell
+            'lazy: for _ in 0..V::LEN {
+                f = f.shift();
+                for i in 0..seg_len {
+                    if !f.any_gt(h_store[i].saturating_sub(self.gap_open)) {
+                        break 'lazy;
+                    }
+                    h_store[i] = h_store[i].max(f);
+                    e[i] = e[i].max(h_store[i].saturating_sub(self.gap_open));
+                    f = f.saturating_sub(self.gap_extend);
+                }
+            }
+
+            let column_best = column_max.reduce_max();
+            if column_best > hit.score {
+                if column_best >= self.limit {
+                    return None;
+                }
+                hit = LocalHit {
+                    score: column_best,
+                    query_end: self.end_in_column(&h_store, column_best),
+                    target_end: j + 1,
+                };
+            }
+        }
+        Some(hit)
+    }
+
+    /// Finds the smallest query position holding `score` in a column.
+    fn end_in_column(&self, column: &[V], score: i32) -> usize {
+        let mut end = usize::MAX;
+        for (i, vector) in column.iter().enumerate() {
+            for k in 0..V::LEN {
+                let pos = k * self.seg_len + i;
+                if vector.lane(k) == score && pos < self.query_len {
+                    end = end.min(pos + 1);
+                }
+            }
+        }
+        end
+    }
+}
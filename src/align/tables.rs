@@ -0,0 +1,217 @@
+use super::{NucleotideMatrix, ProteinMatrix};
+
+// Protein tables are transcribed from the NCBI BLAST matrix files, with rows
+// and columns reordered to the `AminoAcid` discriminants:
+//
+//    A   C   D   E   F   G   H   I   K   L   M   N   P   Q   R   S   T   V   W   Y
+
+/// BLOSUM45, for distantly related proteins.
+#[rustfmt::skip]
+pub const BLOSUM45: ProteinMatrix = ProteinMatrix::new([
+    [ 5, -1, -2, -1, -2,  0, -2, -1, -1, -1, -1, -1, -1, -1, -2,  1,  0,  0, -2, -2],
+    [-1, 12, -3, -3, -2, -3, -3, -3, -3, -2, -2, -2, -4, -3, -3, -1, -1, -1, -5, -3],
+    [-2, -3,  7,  2, -4, -1,  0, -4,  0, -3, -3,  2, -1,  0, -1,  0, -1, -3, -4, -2],
+    [-1, -3,  2,  6, -3, -2,  0, -3,  1, -2, -2,  0,  0,  2,  0,  0, -1, -3, -3, -2],
+    [-2, -2, -4, -3,  8, -3, -2,  0, -3,  1,  0, -2, -3, -4, -2, -2, -1,  0,  1,  3],
+    [ 0, -3, -1, -2, -3,  7, -2, -4, -2, -3, -2,  0, -2, -2, -2,  0, -2, -3, -2, -3],
+    [-2, -3,  0,  0, -2, -2, 10, -3, -1, -2,  0,  1, -2,  1,  0, -1, -2, -3, -3,  2],
+    [-1, -3, -4, -3,  0, -4, -3,  5, -3,  2,  2, -2, -2, -2, -3, -2, -1,  3, -2,  0],
+    [-1, -3,  0,  1, -3, -2, -1, -3,  5, -3, -1,  0, -1,  1,  3, -1, -1, -2, -2, -1],
+    [-1, -2, -3, -2,  1, -3, -2,  2, -3,  5,  2, -3, -3, -2, -2, -3, -1,  1, -2,  0],
+    [-1, -2, -3, -2,  0, -2,  0,  2, -1,  2,  6, -2, -2,  0, -1, -2, -1,  1, -2,  0],
+    [-1, -2,  2,  0, -2,  0,  1, -2,  0, -3, -2,  6, -2,  0,  0,  1,  0, -3, -4, -2],
+    [-1, -4, -1,  0, -3, -2, -2, -2, -1, -3, -2, -2,  9, -1, -2, -1, -1, -3, -3, -3],
+    [-1, -3,  0,  2, -4, -2,  1, -2,  1, -2,  0,  0, -1,  6,  1,  0, -1, -3, -2, -1],
+    [-2, -3, -1,  0, -2, -2,  0, -3,  3, -2, -1,  0, -2,  1,  7, -1, -1, -2, -2, -1],
+    [ 1, -1,  0,  0, -2,  0, -1, -2, -1, -3, -2,  1, -1,  0, -1,  4,  2, -1, -4, -2],
+    [ 0, -1, -1, -1, -1, -2, -2, -1, -1, -1, -1,  0, -1, -1, -1,  2,  5,  0, -3, -1],
+    [ 0, -1, -3, -3,  0, -3, -3,  3, -2,  1,  1, -3, -3, -3, -2, -1,  0,  5, -3, -1],
+    [-2, -5, -4, -3,  1, -2, -3, -2, -2, -2, -2, -4, -3, -2, -2, -4, -3, -3, 15,  3],
+    [-2, -3, -2, -2,  3, -3,  2,  0, -1,  0,  0, -2, -3, -1, -1, -2, -1, -1,  3,  8],
+]);
+
+/// BLOSUM50.
+#[rustfmt::skip]
+pub const BLOSUM50: ProteinMatrix = ProteinMatrix::new([
+    [ 5, -1, -2, -1, -3,  0, -2, -1, -1, -2, -1, -1, -1, -1, -2,  1,  0,  0, -3, -2],
+    [-1, 13, -4, -3, -2, -3, -3, -2, -3, -2, -2, -2, -4, -3, -4, -1, -1, -1, -5, -3],
+    [-2, -4,  8,  2, -5, -1, -1, -4, -1, -4, -4,  2, -1,  0, -2,  0, -1, -4, -5, -3],
+    [-1, -3,  2,  6, -3, -3,  0, -4,  1, -3, -2,  0, -1,  2,  0, -1, -1, -3, -3, -2],
+    [-3, -2, -5, -3,  8, -4, -1,  0, -4,  1,  0, -4, -4, -4, -3, -3, -2, -1,  1,  4],
+    [ 0, -3, -1, -3, -4,  8, -2, -4, -2, -4, -3,  0, -2, -2, -3,  0, -2, -4, -3, -3],
+    [-2, -3, -1,  0, -1, -2, 10, -4,  0, -3, -1,  1, -2,  1,  0, -1, -2, -4, -3,  2],
+    [-1, -2, -4, -4,  0, -4, -4,  5, -3,  2,  2, -3, -3, -3, -4, -3, -1,  4, -3, -1],
+    [-1, -3, -1,  1, -4, -2,  0, -3,  6, -3, -2,  0, -1,  2,  3,  0, -1, -3, -3, -2],
+    [-2, -2, -4, -3,  1, -4, -3,  2, -3,  5,  3, -4, -4, -2, -3, -3, -1,  1, -2, -1],
+    [-1, -2, -4, -2,  0, -3, -1,  2, -2,  3,  7, -2, -3,  0, -2, -2, -1,  1, -1,  0],
+    [-1, -2,  2,  0, -4,  0,  1, -3,  0, -4, -2,  7, -2,  0, -1,  1,  0, -3, -4, -2],
+    [-1, -4, -1, -1, -4, -2, -2, -3, -1, -4, -3, -2, 10, -1, -3, -1, -1, -3, -4, -3],
+    [-1, -3,  0,  2, -4, -2,  1, -3,  2, -2,  0,  0, -1,  7,  1,  0, -1, -3, -1, -1],
+    [-2, -4, -2,  0, -3, -3,  0, -4,  3, -3, -2, -1, -3,  1,  7, -1, -1, -3, -3, -1],
+    [ 1, -1,  0, -1, -3,  0, -1, -3,  0, -3, -2,  1, -1,  0, -1,  5,  2, -2, -4, -2],
+    [ 0, -1, -1, -1, -2, -2, -2, -1, -1, -1, -1,  0, -1, -1, -1,  2,  5,  0, -3, -2],
+    [ 0, -1, -4, -3, -1, -4, -4,  4, -3,  1,  1, -3, -3, -3, -3, -2,  0,  5, -3, -1],
+    [-3, -5, -5, -3,  1, -3, -3, -3, -3, -2, -1, -4, -4, -1, -3, -4, -3, -3, 15,  2],
+    [-2, -3, -3, -2,  4, -3,  2, -1, -2, -1,  0, -2, -3, -1, -1, -2, -2, -1,  2,  8],
+]);
+
+/// BLOSUM62, the BLASTP default.
+#[rustfmt::skip]
+pub const BLOSUM62: ProteinMatrix = ProteinMatrix::new([
+    [ 4,  0, -2, -1, -2,  0, -2, -1, -1, -1, -1, -2, -1, -1, -1,  1,  0,  0, -3, -2],
+    [ 0,  9, -3, -4, -2, -3, -3, -1, -3, -1, -1, -3, -3, -3, -3, -1, -1, -1, -2, -2],
+    [-2, -3,  6,  2, -3, -1, -1, -3, -1, -4, -3,  1, -1,  0, -2,  0, -1, -3, -4, -3],
+    [-1, -4,  2,  5, -3, -2,  0, -3,  1, -3, -2,  0, -1,  2,  0,  0, -1, -2, -3, -2],
+    [-2, -2, -3, -3,  6, -3, -1,  0, -3,  0,  0, -3, -4, -3, -3, -2, -2, -1,  1,  3],
+    [ 0, -3, -1, -2, -3,  6, -2, -4, -2, -4, -3,  0, -2, -2, -2,  0, -2, -3, -2, -3],
+    [-2, -3, -1,  0, -1, -2,  8, -3, -1, -3, -2,  1, -2,  0,  0, -1, -2, -3, -2,  2],
+    [-1, -1, -3, -3,  0, -4, -3,  4, -3,  2,  1, -3, -3, -3, -3, -2, -1,  3, -3, -1],
+    [-1, -3, -1,  1, -3, -2, -1, -3,  5, -2, -1,  0, -1,  1,  2,  0, -1, -2, -3, -2],
+    [-1, -1, -4, -3,  0, -4, -3,  2, -2,  4,  2, -3, -3, -2, -2, -2, -1,  1, -2, -1],
+    [-1, -1, -3, -2,  0, -3, -2,  1, -1,  2,  5, -2, -2,  0, -1, -1, -1,  1, -1, -1],
+    [-2, -3,  1,  0, -3,  0,  1, -3,  0, -3, -2,  6, -2,  0,  0,  1,  0, -3, -4, -2],
+    [-1, -3, -1, -1, -4, -2, -2, -3, -1, -3, -2, -2,  7, -1, -2, -1, -1, -2, -4, -3],
+    [-1, -3,  0,  2, -3, -2,  0, -3,  1, -2,  0,  0, -1,  5,  1,  0, -1, -2, -2, -1],
+    [-1, -3, -2,  0, -3, -2,  0, -3,  2, -2, -1,  0, -2,  1,  5, -1, -1, -3, -3, -2],
+    [ 1, -1,  0,  0, -2,  0, -1, -2,  0, -2, -1,  1, -1,  0, -1,  4,  1, -2, -3, -2],
+    [ 0, -1, -1, -1, -2, -2, -2, -1, -1, -1, -1,  0, -1, -1, -1,  1,  5,  0, -2, -2],
+    [ 0, -1, -3, -2, -1, -3, -3,  3, -2,  1,  1, -3, -2, -2, -3, -2,  0,  4, -3, -1],
+    [-3, -2, -4, -3,  1, -2, -2, -3, -3, -2, -1, -4, -4, -2, -3, -3, -2, -3, 11,  2],
+    [-2, -2, -3, -2,  3, -3,  2, -1, -2, -1, -1, -2, -3, -1, -2, -2, -2, -1,  2,  7],
+]);
+
+/// BLOSUM80.
+#[rustfmt::skip]
+pub const BLOSUM80: ProteinMatrix = ProteinMatrix::new([
+    [ 7, -1, -3, -2, -4,  0, -3, -3, -1, -3, -2, -3, -1, -2, -3,  2,  0, -1, -5, -4],
+    [-1, 13, -7, -7, -4, -6, -7, -2, -6, -3, -3, -5, -6, -5, -6, -2, -2, -2, -5, -5],
+    [-3, -7, 10,  2, -6, -3, -2, -7, -2, -7, -6,  2, -3, -1, -3, -1, -2, -6, -8, -6],
+    [-2, -7,  2,  8, -6, -4,  0, -6,  1, -6, -4, -1, -2,  3, -1, -1, -2, -4, -6, -5],
+    [-4, -4, -6, -6, 10, -6, -2, -1, -5,  0,  0, -6, -6, -5, -5, -4, -4, -2,  0,  4],
+    [ 0, -6, -3, -4, -6,  9, -4, -7, -3, -7, -5, -1, -5, -4, -4, -1, -3, -6, -6, -6],
+    [-3, -7, -2,  0, -2, -4, 12, -6, -1, -5, -4,  1, -4,  1,  0, -2, -3, -5, -4,  3],
+    [-3, -2, -7, -6, -1, -7, -6,  7, -5,  2,  2, -6, -5, -5, -5, -4, -2,  4, -5, -3],
+    [-1, -6, -2,  1, -5, -3, -1, -5,  8, -4, -3,  0, -2,  2,  3, -1, -1, -4, -6, -4],
+    [-3, -3, -7, -6,  0, -7, -5,  2, -4,  6,  3, -6, -5, -4, -4, -4, -3,  1, -4, -2],
+    [-2, -3, -6, -4,  0, -5, -4,  2, -3,  3,  9, -4, -4, -1, -3, -3, -1,  1, -3, -3],
+    [-3, -5,  2, -1, -6, -1,  1, -6,  0, -6, -4,  9, -4,  0, -1,  1,  0, -5, -7, -4],
+    [-1, -6, -3, -2, -6, -5, -4, -5, -2, -5, -4, -4, 12, -3, -3, -2, -3, -4, -7, -6],
+    [-2, -5, -1,  3, -5, -4,  1, -5,  2, -4, -1,  0, -3,  9,  1, -1, -1, -4, -4, -3],
+    [-3, -6, -3, -1, -5, -4,  0, -5,  3, -4, -3, -1, -3,  1,  9, -2, -2, -4, -5, -4],
+    [ 2, -2, -1, -1, -4, -1, -2, -4, -1, -4, -3,  1, -2, -1, -2,  7,  2, -3, -6, -3],
+    [ 0, -2, -2, -2, -4, -3, -3, -2, -1, -3, -1,  0, -3, -1, -2,  2,  8,  0, -5, -3],
+    [-1, -2, -6, -4, -2, -6, -5,  4, -4,  1,  1, -5, -4, -4, -4, -3,  0,  7, -5, -3],
+    [-5, -5, -8, -6,  0, -6, -4, -5, -6, -4, -3, -7, -7, -4, -5, -6, -5, -5, 16,  3],
+    [-4, -5, -6, -5,  4, -6,  3, -3, -4, -2, -3, -4, -6, -3, -4, -3, -3, -3,  3, 11],
+]);
+
+/// BLOSUM90, for closely related proteins.
+#[rustfmt::skip]
+pub const BLOSUM90: ProteinMatrix = ProteinMatrix::new([
+    [ 5, -1, -3, -1, -3,  0, -2, -2, -1, -2, -2, -2, -1, -1, -2,  1,  0, -1, -4, -3],
+    [-1,  9, -5, -6, -3, -4, -5, -2, -4, -2, -2, -4, -4, -4, -5, -2, -2, -2, -4, -4],
+    [-3, -5,  7,  1, -5, -2, -2, -5, -1, -5, -4,  1, -3, -1, -3, -1, -2, -5, -6, -4],
+    [-1, -6,  1,  6, -5, -3, -1, -4,  0, -4, -3, -1, -2,  2, -1, -1, -1, -3, -5, -4],
+    [-3, -3, -5, -5,  7, -5, -2, -1, -4,  0, -1, -4, -4, -4, -4, -3, -3, -2,  0,  3],
+    [ 0, -4, -2, -3, -5,  6, -3, -5, -2, -5, -4, -1, -3, -3, -3, -1, -3, -5, -4, -5],
+    [-2, -5, -2, -1, -2, -3,  8, -4, -1, -4, -3,  0, -3,  1,  0, -2, -2, -4, -3,  1],
+    [-2, -2, -5, -4, -1, -5, -4,  5, -4,  1,  1, -4, -4, -4, -4, -3, -1,  3, -4, -2],
+    [-1, -4, -1,  0, -4, -2, -1, -4,  6, -3, -2,  0, -2,  1,  2, -1, -1, -3, -5, -3],
+    [-2, -2, -5, -4,  0, -5, -4,  1, -3,  5,  2, -4, -4, -3, -3, -3, -2,  0, -3, -2],
+    [-2, -2, -4, -3, -1, -4, -3,  1, -2,  2,  7, -3, -3,  0, -2, -2, -1,  0, -2, -2],
+    [-2, -4,  1, -1, -4, -1,  0, -4,  0, -4, -3,  7, -3,  0, -1,  0,  0, -4, -5, -3],
+    [-1, -4, -3, -2, -4, -3, -3, -4, -2, -4, -3, -3,  8, -2, -3, -2, -2, -3, -5, -4],
+    [-1, -4, -1,  2, -4, -3,  1, -4,  1, -3,  0,  0, -2,  7,  1, -1, -1, -3, -3, -3],
+    [-2, -5, -3, -1, -4, -3,  0, -4,  2, -3, -2, -1, -3,  1,  6, -1, -2, -3, -4, -3],
+    [ 1, -2, -1, -1, -3, -1, -2, -3, -1, -3, -2,  0, -2, -1, -1,  5,  1, -2, -4, -3],
+    [ 0, -2, -2, -1, -3, -3, -2, -1, -1, -2, -1,  0, -2, -1, -2,  1,  6, -1, -4, -2],
+    [-1, -2, -5, -3, -2, -5, -4,  3, -3,  0,  0, -4, -3, -3, -3, -2, -1,  5, -3, -3],
+    [-4, -4, -6, -5,  0, -4, -3, -4, -5, -3, -2, -5, -5, -3, -4, -4, -4, -3, 11,  2],
+    [-3, -4, -4, -4,  3, -5,  1, -2, -3, -2, -2, -3, -4, -3, -3, -3, -2, -3,  2,  8],
+]);
+
+/// PAM30, for short, highly similar peptides.
+#[rustfmt::skip]
+pub const PAM30: ProteinMatrix = ProteinMatrix::new([
+    [ 6, -6, -3, -2, -8, -2, -7, -5, -7, -6, -5, -4, -2, -4, -7,  0, -1, -2, -13, -8],
+    [-6, 10, -14, -14, -13, -9, -7, -6, -14, -15, -13, -11, -8, -14, -8, -3, -8, -6, -15, -4],
+    [-3, -14,  8,  2, -15, -3, -4, -7, -4, -12, -11,  2, -8, -2, -10, -4, -5, -8, -15, -11],
+    [-2, -14,  2,  8, -14, -4, -5, -5, -4, -9, -7, -2, -5,  1, -9, -4, -6, -6, -17, -8],
+    [-8, -13, -15, -14,  9, -9, -6, -2, -14, -3, -4, -9, -10, -13, -9, -6, -9, -8, -4,  2],
+    [-2, -9, -3, -4, -9,  6, -9, -11, -7, -10, -8, -3, -6, -7, -9, -2, -6, -5, -15, -14],
+    [-7, -7, -4, -5, -6, -9,  9, -9, -6, -6, -10,  0, -4,  1, -2, -6, -7, -6, -7, -3],
+    [-5, -6, -7, -5, -2, -11, -9,  8, -6, -1, -1, -5, -8, -8, -5, -7, -2,  2, -14, -6],
+    [-7, -14, -4, -4, -14, -7, -6, -6,  7, -8, -2, -1, -6, -3,  0, -4, -3, -9, -12, -9],
+    [-6, -15, -12, -9, -3, -10, -6, -1, -8,  7,  1, -7, -7, -5, -8, -8, -7, -2, -6, -7],
+    [-5, -13, -11, -7, -4, -8, -10, -1, -2,  1, 11, -9, -8, -4, -4, -5, -4, -1, -13, -11],
+    [-4, -11,  2, -2, -9, -3,  0, -5, -1, -7, -9,  8, -6, -3, -6,  0, -2, -8, -8, -4],
+    [-2, -8, -8, -5, -10, -6, -4, -8, -6, -7, -8, -6,  8, -3, -4, -2, -4, -6, -14, -13],
+    [-4, -14, -2,  1, -13, -7,  1, -8, -3, -5, -4, -3, -3,  8, -2, -5, -5, -7, -13, -12],
+    [-7, -8, -10, -9, -9, -9, -2, -5,  0, -8, -4, -6, -4, -2,  8, -3, -6, -8, -2, -10],
+    [ 0, -3, -4, -4, -6, -2, -6, -7, -4, -8, -5,  0, -2, -5, -3,  6,  0, -6, -5, -7],
+    [-1, -8, -5, -6, -9, -6, -7, -2, -3, -7, -4, -2, -4, -5, -6,  0,  7, -3, -13, -6],
+    [-2, -6, -8, -6, -8, -5, -6,  2, -9, -2, -1, -8, -6, -7, -8, -6, -3,  7, -15, -7],
+    [-13, -15, -15, -17, -4, -15, -7, -14, -12, -6, -13, -8, -14, -13, -2, -5, -13, -15, 13, -5],
+    [-8, -4, -11, -8,  2, -14, -3, -6, -9, -7, -11, -4, -13, -12, -10, -7, -6, -7, -5, 10],
+]);
+
+/// PAM70.
+#[rustfmt::skip]
+pub const PAM70: ProteinMatrix = ProteinMatrix::new([
+    [ 5, -4, -1, -1, -6,  0, -4, -2, -4, -4, -3, -2,  0, -2, -4,  1,  1, -1, -9, -5],
+    [-4,  9, -9, -9, -8, -6, -5, -4, -9, -10, -9, -7, -5, -9, -5, -1, -5, -4, -11, -2],
+    [-1, -9,  6,  3, -10, -1, -1, -5, -2, -8, -7,  3, -4,  0, -6, -1, -2, -5, -10, -7],
+    [-1, -9,  3,  6, -9, -2, -2, -4, -2, -6, -4,  0, -3,  2, -5, -2, -3, -4, -11, -6],
+    [-6, -8, -10, -9,  8, -7, -4,  0, -9, -1, -2, -6, -7, -9, -7, -4, -6, -5, -2,  4],
+    [ 0, -6, -1, -2, -7,  6, -6, -6, -5, -7, -6, -1, -3, -4, -6,  0, -3, -3, -10, -9],
+    [-4, -5, -1, -2, -4, -6,  8, -6, -3, -4, -6,  1, -2,  2,  0, -3, -4, -4, -5, -1],
+    [-2, -4, -5, -4,  0, -6, -6,  7, -4,  1,  1, -3, -5, -5, -3, -4, -1,  3, -9, -4],
+    [-4, -9, -2, -2, -9, -5, -3, -4,  6, -5,  0,  0, -4, -1,  2, -2, -1, -6, -7, -7],
+    [-4, -10, -8, -6, -1, -7, -4,  1, -5,  6,  2, -5, -5, -3, -6, -6, -4,  0, -4, -4],
+    [-3, -9, -7, -4, -2, -6, -6,  1,  0,  2, 10, -5, -5, -2, -2, -3, -2,  0, -8, -7],
+    [-2, -7,  3,  0, -6, -1,  1, -3,  0, -5, -5,  6, -3, -1, -3,  1,  0, -5, -6, -3],
+    [ 0, -5, -4, -3, -7, -3, -2, -5, -4, -5, -5, -3,  7, -1, -2,  0, -2, -3, -9, -9],
+    [-2, -9,  0,  2, -9, -4,  2, -5, -1, -3, -2, -1, -1,  7,  0, -3, -3, -4, -8, -8],
+    [-4, -5, -6, -5, -7, -6,  0, -3,  2, -6, -2, -3, -2,  0,  8, -1, -4, -5,  0, -7],
+    [ 1, -1, -1, -2, -4,  0, -3, -4, -2, -6, -3,  1,  0, -3, -1,  5,  2, -3, -3, -5],
+    [ 1, -5, -2, -3, -6, -3, -4, -1, -1, -4, -2,  0, -2, -3, -4,  2,  6, -1, -8, -4],
+    [-1, -4, -5, -4, -5, -3, -4,  3, -6,  0,  0, -5, -3, -4, -5, -3, -1,  6, -10, -5],
+    [-9, -11, -10, -11, -2, -10, -5, -9, -7, -4, -8, -6, -9, -8,  0, -3, -8, -10, 13, -3],
+    [-5, -2, -7, -6,  4, -9, -1, -4, -7, -4, -7, -3, -9, -8, -7, -5, -4, -5, -3,  9],
+]);
+
+/// PAM250, for distantly related proteins.
+#[rustfmt::skip]
+pub const PAM250: ProteinMatrix = ProteinMatrix::new([
+    [ 2, -2,  0,  0, -3,  1, -1, -1, -1, -2, -1,  0,  1,  0, -2,  1,  1,  0, -6, -3],
+    [-2, 12, -5, -5, -4, -3, -3, -2, -5, -6, -5, -4, -3, -5, -4,  0, -2, -2, -8,  0],
+    [ 0, -5,  4,  3, -6,  1,  1, -2,  0, -4, -3,  2, -1,  2, -1,  0,  0, -2, -7, -4],
+    [ 0, -5,  3,  4, -5,  0,  1, -2,  0, -3, -2,  1, -1,  2, -1,  0,  0, -2, -7, -4],
+    [-3, -4, -6, -5,  9, -5, -2,  1, -5,  2,  0, -3, -5, -5, -4, -3, -3, -1,  0,  7],
+    [ 1, -3,  1,  0, -5,  5, -2, -3, -2, -4, -3,  0,  0, -1, -3,  1,  0, -1, -7, -5],
+    [-1, -3,  1,  1, -2, -2,  6, -2,  0, -2, -2,  2,  0,  3,  2, -1, -1, -2, -3,  0],
+    [-1, -2, -2, -2,  1, -3, -2,  5, -2,  2,  2, -2, -2, -2, -2, -1,  0,  4, -5, -1],
+    [-1, -5,  0,  0, -5, -2,  0, -2,  5, -3,  0,  1, -1,  1,  3,  0,  0, -2, -3, -4],
+    [-2, -6, -4, -3,  2, -4, -2,  2, -3,  6,  4, -3, -3, -2, -3, -3, -2,  2, -2, -1],
+    [-1, -5, -3, -2,  0, -3, -2,  2,  0,  4,  6, -2, -2, -1,  0, -2, -1,  2, -4, -2],
+    [ 0, -4,  2,  1, -3,  0,  2, -2,  1, -3, -2,  2,  0,  1,  0,  1,  0, -2, -4, -2],
+    [ 1, -3, -1, -1, -5,  0,  0, -2, -1, -3, -2,  0,  6,  0,  0,  1,  0, -1, -6, -5],
+    [ 0, -5,  2,  2, -5, -1,  3, -2,  1, -2, -1,  1,  0,  4,  1, -1, -1, -2, -5, -4],
+    [-2, -4, -1, -1, -4, -3,  2, -2,  3, -3,  0,  0,  0,  1,  6,  0, -1, -2,  2, -4],
+    [ 1,  0,  0,  0, -3,  1, -1, -1,  0, -3, -2,  1,  1, -1,  0,  2,  1, -1, -2, -3],
+    [ 1, -2,  0,  0, -3,  0, -1,  0,  0, -2, -1,  0,  0, -1, -1,  1,  3,  0, -5, -3],
+    [ 0, -2, -2, -2, -1, -1, -2,  4, -2,  2,  2, -2, -1, -2, -2, -1,  0,  4, -6, -2],
+    [-6, -8, -7, -7,  0, -7, -3, -5, -3, -2, -4, -4, -6, -5,  2, -2, -5, -6, 17,  0],
+    [-3,  0, -4, -4,  7, -5,  0, -1, -4, -1, -2, -2, -5, -4, -4, -3, -3, -2,  0, 10],
+]);
+
+/// EDNAFULL (NUC.4.4) restricted to `A`, `C`, `G`, `T` and `N`, indexed by
+/// `Nucleotide` discriminants.
+#[rustfmt::skip]
+pub const EDNAFULL: NucleotideMatrix = NucleotideMatrix::new([
+    [ 5, -4, -4, -4, -2],
+    [-4,  5, -4, -4, -2],
+    [-4, -4,  5, -4, -2],
+    [-4, -4, -4,  5, -2],
+    [-2, -2, -2, -2, -1],
+]);
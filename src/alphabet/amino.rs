@@ -1,4 +1,4 @@
-use super::Alphabet;
+use super::{Alphabet, NamedAlphabet};
 
 /// Typed amino acid symbols
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -128,3 +128,7 @@ impl Alphabet for AA20 {
         AA20_TO_BYTE[e as usize]
     }
 }
+
+impl NamedAlphabet for AA20 {
+    const NAME: &'static str = "AA20";
+}
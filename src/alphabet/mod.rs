@@ -26,6 +26,13 @@ pub trait Alphabet: 'static + Eq {
     fn to_byte(e: Self::Elements) -> u8;
 }
 
+/// Alphabets with a stable name, as required by the binary formats that
+/// record the alphabet in their headers.
+pub trait NamedAlphabet: Alphabet {
+    /// Short name identifying the alphabet, e.g. in file headers
+    const NAME: &'static str;
+}
+
 /// Promotion trait for combining two alphabets.
 ///
 /// Output is the smallest alphabet that can represent all symbols from both.
@@ -1,4 +1,4 @@
-use super::Alphabet;
+use super::{Alphabet, NamedAlphabet};
 
 /// Typed nucleotide symbols (shared by Nuc4 and Nuc5)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,6 +17,26 @@ impl From<Nucleotide> for u8 {
     }
 }
 
+impl Nucleotide {
+    /// Returns the Watson-Crick complement. `N` is its own complement.
+    pub fn complement(self) -> Nucleotide {
+        match self {
+            Nucleotide::A => Nucleotide::T,
+            Nucleotide::C => Nucleotide::G,
+            Nucleotide::G => Nucleotide::C,
+            Nucleotide::T => Nucleotide::A,
+            Nucleotide::N => Nucleotide::N,
+        }
+    }
+}
+
+/// Strand of a double-stranded DNA molecule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strand {
+    Forward,
+    Reverse,
+}
+
 // -- Alphabet structs --------------------------------------------------------
 
 /// 4-symbol DNA alphabet (A, C, G, T)
@@ -57,6 +77,40 @@ const NUC5_BYTE_TO_BITS: [u8; 256] = {
     lut
 };
 
+/// Lookup table: ASCII IUPAC code → set of nucleotides it stands for.
+///
+/// One bit per `Nucleotide` discriminant (A=1, C=2, G=4, T=8); `U` reads as
+/// `T`. 0 = invalid.
+pub const IUPAC_TO_MASK: [u8; 256] = {
+    const CODES: [(u8, u8); 16] = [
+        (b'A', 0b0001),
+        (b'C', 0b0010),
+        (b'G', 0b0100),
+        (b'T', 0b1000),
+        (b'U', 0b1000),
+        (b'R', 0b0101),
+        (b'Y', 0b1010),
+        (b'S', 0b0110),
+        (b'W', 0b1001),
+        (b'K', 0b1100),
+        (b'M', 0b0011),
+        (b'B', 0b1110),
+        (b'D', 0b1101),
+        (b'H', 0b1011),
+        (b'V', 0b0111),
+        (b'N', 0b1111),
+    ];
+    let mut lut = [0u8; 256];
+    let mut i = 0;
+    while i < CODES.len() {
+        let (code, mask) = CODES[i];
+        lut[code as usize] = mask;
+        lut[code.to_ascii_lowercase() as usize] = mask;
+        i += 1;
+    }
+    lut
+};
+
 // -- Alphabet impls ----------------------------------------------------------
 
 const NUC4_ELEMENTS: &[Nucleotide] = &[Nucleotide::A, Nucleotide::C, Nucleotide::G, Nucleotide::T];
@@ -110,6 +164,14 @@ impl Alphabet for Nuc5 {
     }
 }
 
+impl NamedAlphabet for Nuc4 {
+    const NAME: &'static str = "Nuc4";
+}
+
+impl NamedAlphabet for Nuc5 {
+    const NAME: &'static str = "Nuc5";
+}
+
 // -- Promote impls -----------------------------------------------------------
 
 use super::Promote;
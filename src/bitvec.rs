@@ -0,0 +1,362 @@
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// Bits per rank superblock.
+const SUPERBLOCK: usize = 512;
+
+/// Words per rank superblock.
+const WORDS_PER_SUPERBLOCK: usize = SUPERBLOCK / 64;
+
+/// Set bits between select samples.
+const SELECT_SAMPLE: usize = 4096;
+
+/// Growable bit vector used to assemble a [`BitVec`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitVecBuilder {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVecBuilder {
+    /// Creates a builder holding `len` unset bits.
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the builder holds no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Grows or shrinks to `len` bits; new bits are unset.
+    pub fn resize(&mut self, len: usize) {
+        self.words.resize(len.div_ceil(64), 0);
+        if len < self.len && !len.is_multiple_of(64) {
+            *self.words.last_mut().unwrap() &= (1 << (len % 64)) - 1;
+        }
+        self.len = len;
+    }
+
+    /// Appends a bit.
+    pub fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(64) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, bit);
+    }
+
+    /// Sets or clears bit `i`.
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn set(&mut self, i: usize, bit: bool) {
+        assert!(
+            i < self.len,
+            "bit {i} out of bounds for length {}",
+            self.len
+        );
+        let mask = 1 << (i % 64);
+        if bit {
+            self.words[i / 64] |= mask;
+        } else {
+            self.words[i / 64] &= !mask;
+        }
+    }
+
+    /// Returns bit `i`.
+    pub fn get(&self, i: usize) -> bool {
+        assert!(
+            i < self.len,
+            "bit {i} out of bounds for length {}",
+            self.len
+        );
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns the underlying words; bits past `len` are unset.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Freezes the bits and builds the rank/select directory.
+    pub fn build(self) -> BitVec {
+        BitVec::from_words(self.words, self.len)
+    }
+}
+
+/// Immutable bit vector with constant-time rank and fast select.
+///
+/// Ranks are answered from one absolute count per 512-bit superblock plus
+/// seven packed 9-bit counts for the words inside it (rank9). Select
+/// narrows its search with a sample every 4096 set bits, then scans the
+/// superblock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+    /// Pairs of (ones before superblock, packed in-superblock word counts).
+    ranks: Vec<u64>,
+    /// Superblock holding every 4096th set bit.
+    samples: Vec<usize>,
+}
+
+impl BitVec {
+    /// Creates a bit vector of `len` bits with the given positions set.
+    ///
+    /// Panics if a position is out of bounds.
+    pub fn from_positions<I: IntoIterator<Item = usize>>(len: usize, ones: I) -> Self {
+        let mut builder = BitVecBuilder::new(len);
+        for i in ones {
+            builder.set(i, true);
+        }
+        builder.build()
+    }
+
+    /// Creates a bit vector from little-endian words; bit `i` is bit `i % 64`
+    /// of word `i / 64`. Bits past `len` are cleared.
+    ///
+    /// Panics if `words` holds fewer than `len` bits.
+    pub fn from_words(mut words: Vec<u64>, len: usize) -> Self {
+        assert!(words.len() * 64 >= len, "{len} bits do not fit the words");
+        words.truncate(len.div_ceil(64));
+        if !len.is_multiple_of(64) {
+            *words.last_mut().unwrap() &= (1 << (len % 64)) - 1;
+        }
+
+        let mut ranks = Vec::with_capacity(2 * (words.len() / WORDS_PER_SUPERBLOCK + 1));
+        let mut samples = Vec::new();
+        let mut total = 0u64;
+        for (sb, chunk) in words.chunks(WORDS_PER_SUPERBLOCK).enumerate() {
+            let mut packed = 0u64;
+            let mut inner = 0u64;
+            for (j, word) in chunk.iter().enumerate() {
+                if j > 0 {
+                    packed |= inner << (9 * (j - 1));
+                }
+                inner += word.count_ones() as u64;
+            }
+            // Unused slots repeat the superblock total so rank stays valid
+            for j in chunk.len()..WORDS_PER_SUPERBLOCK {
+                packed |= inner << (9 * (j - 1));
+            }
+            while samples.len() * SELECT_SAMPLE < (total + inner) as usize {
+                samples.push(sb);
+            }
+            ranks.push(total);
+            ranks.push(packed);
+            total += inner;
+        }
+        ranks.push(total);
+        ranks.push(0);
+
+        Self {
+            words,
+            len,
+            ranks,
+            samples,
+        }
+    }
+
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the vector holds no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the underlying words.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns bit `i`.
+    ///
+    /// Panics if `i` is out of bounds.
+    #[inline]
+    pub fn get(&self, i: usize) -> bool {
+        assert!(
+            i < self.len,
+            "bit {i} out of bounds for length {}",
+            self.len
+        );
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.ranks[self.ranks.len() - 2] as usize
+    }
+
+    /// Returns the number of unset bits.
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Returns the number of set bits in `0..i`.
+    ///
+    /// Panics if `i > len`.
+    #[inline]
+    pub fn rank1(&self, i: usize) -> usize {
+        assert!(
+            i <= self.len,
+            "rank {i} out of bounds for length {}",
+            self.len
+        );
+        let sb = i / SUPERBLOCK;
+        let word = i / 64;
+        let mut rank = self.ranks[2 * sb] + self.inner_rank(sb, word % WORDS_PER_SUPERBLOCK);
+        if !i.is_multiple_of(64) {
+            rank += (self.words[word] << (64 - i % 64)).count_ones() as u64;
+        }
+        rank as usize
+    }
+
+    /// Returns the number of unset bits in `0..i`.
+    #[inline]
+    pub fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// Returns the position of the `k`-th (0-based) set bit.
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        if k >= self.count_ones() {
+            return None;
+        }
+        // The sampled superblocks bound the search from both sides
+        let low = self.samples[k / SELECT_SAMPLE];
+        let high = self
+            .samples
+            .get(k / SELECT_SAMPLE + 1)
+            .map_or(self.superblocks(), |&sb| sb + 1);
+        let sb = self.superblock_before(low..high, k, |_, ones| ones);
+        Some(self.select_in_superblock(sb, k - self.ranks[2 * sb] as usize, false))
+    }
+
+    /// Returns the position of the `k`-th (0-based) unset bit.
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        if k >= self.count_zeros() {
+            return None;
+        }
+        let sb =
+            self.superblock_before(0..self.superblocks(), k, |sb, ones| sb * SUPERBLOCK - ones);
+        let before = sb * SUPERBLOCK - self.ranks[2 * sb] as usize;
+        Some(self.select_in_superblock(sb, k - before, true))
+    }
+
+    /// Iterates over all bits.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+
+    /// Iterates over the positions of set bits in increasing order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    fn superblocks(&self) -> usize {
+        self.ranks.len() / 2 - 1
+    }
+
+    /// Set bits in the superblock before word `j` of it.
+    #[inline]
+    fn inner_rank(&self, sb: usize, j: usize) -> u64 {
+        if j == 0 {
+            0
+        } else {
+            self.ranks[2 * sb + 1] >> (9 * (j - 1)) & 0x1FF
+        }
+    }
+
+    /// Finds the last superblock in `range` with at most `k` counted bits
+    /// before it; `count(sb, ones_before)` converts set bits to the counted
+    /// kind.
+    fn superblock_before(
+        &self,
+        range: Range<usize>,
+        k: usize,
+        count: impl Fn(usize, usize) -> usize,
+    ) -> usize {
+        let (mut low, mut high) = (range.start, range.end);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if count(mid, self.ranks[2 * mid] as usize) <= k {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    /// Selects the `k`-th set (or unset) bit inside superblock `sb`.
+    fn select_in_superblock(&self, sb: usize, mut k: usize, zeros: bool) -> usize {
+        let first = sb * WORDS_PER_SUPERBLOCK;
+        let last = (first + WORDS_PER_SUPERBLOCK).min(self.words.len());
+        for (w, &word) in self.words[first..last].iter().enumerate() {
+            let word = if zeros { !word } else { word };
+            let count = word.count_ones() as usize;
+            if k < count {
+                return (first + w) * 64 + select_in_word(word, k);
+            }
+            k -= count;
+        }
+        unreachable!("superblock directory out of sync")
+    }
+}
+
+impl Default for BitVec {
+    fn default() -> Self {
+        Self::from_words(Vec::new(), 0)
+    }
+}
+
+impl From<BitVecBuilder> for BitVec {
+    fn from(builder: BitVecBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut builder = BitVecBuilder::default();
+        for bit in iter {
+            builder.push(bit);
+        }
+        builder.build()
+    }
+}
+
+/// Position of the `k`-th set bit of `word`.
+#[inline]
+fn select_in_word(mut word: u64, k: usize) -> usize {
+    for _ in 0..k {
+        word &= word - 1;
+    }
+    word.trailing_zeros() as usize
+}
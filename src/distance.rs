@@ -0,0 +1,384 @@
+use std::fmt;
+
+use crate::alphabet::Alphabet;
+use crate::search::advance_block;
+use crate::seq::Seq;
+
+/// Width of one block of the bit-parallel DP column.
+const WORD: usize = 64;
+
+/// Cost of a cell outside the band.
+const UNREACHABLE: u32 = u32::MAX / 2;
+
+/// Bitmask selecting the lowest bit of every symbol lane in a packed word.
+///
+/// Lanes never straddle bytes, so the per-byte pattern is simply repeated.
+const fn lane_mask(bits: u8, symbols_per_byte: usize) -> u64 {
+    let mut byte = 0u64;
+    let mut lane = 0;
+    while lane < symbols_per_byte {
+        byte |= 1 << (lane * bits as usize);
+        lane += 1;
+    }
+    byte * 0x0101_0101_0101_0101
+}
+
+/// Counts the symbol lanes of `diff` (XOR of two packed words) that are non-zero.
+#[inline(always)]
+fn mismatched_lanes<A: Alphabet>(diff: u64) -> usize {
+    // Fold every bit of a lane down onto its lowest bit, then popcount those
+    let mut folded = diff;
+    let mut shift = 1;
+    while shift < A::BITS {
+        folded |= diff >> shift;
+        shift += 1;
+    }
+    (folded & lane_mask(A::BITS, Seq::<A>::SYMBOLS_PER_BYTE)).count_ones() as usize
+}
+
+impl<A: Alphabet> Seq<A> {
+    /// Counts the positions at which two equal-length sequences differ.
+    ///
+    /// Works on the packed representation, comparing 64 bits at a time.
+    /// Panics if the lengths differ.
+    pub fn hamming(&self, other: &Self) -> usize {
+        self.hamming_within(other, usize::MAX)
+            .expect("unbounded distance always fits")
+    }
+
+    /// Like [`Seq::hamming`], but stops early once the distance exceeds `max`.
+    ///
+    /// Returns `None` if the distance is greater than `max`.
+    pub fn hamming_bounded(&self, other: &Self, max: usize) -> Option<usize> {
+        self.hamming_within(other, max)
+    }
+
+    fn hamming_within(&self, other: &Self, max: usize) -> Option<usize> {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "hamming distance requires sequences of equal length"
+        );
+
+        // Whole bytes are compared packed; the partially filled last byte per symbol
+        let full_bytes = self.len() / Self::SYMBOLS_PER_BYTE;
+        let (lhs, lhs_rest) = self.data[..full_bytes].as_chunks::<8>();
+        let (rhs, rhs_rest) = other.data[..full_bytes].as_chunks::<8>();
+        let mut distance = 0;
+
+        for (a, b) in lhs.iter().zip(rhs) {
+            let diff = u64::from_ne_bytes(*a) ^ u64::from_ne_bytes(*b);
+            distance += mismatched_lanes::<A>(diff);
+            if distance > max {
+                return None;
+            }
+        }
+
+        for (&a, &b) in lhs_rest.iter().zip(rhs_rest) {
+            distance += mismatched_lanes::<A>((a ^ b) as u64);
+        }
+
+        for i in full_bytes * Self::SYMBOLS_PER_BYTE..self.len() {
+            if self.get_bits(i) != other.get_bits(i) {
+                distance += 1;
+            }
+        }
+
+        (distance <= max).then_some(distance)
+    }
+}
+
+// -- Edit distance -----------------------------------------------------------
+
+impl<A: Alphabet> Seq<A> {
+    /// Computes the Levenshtein distance to `other`.
+    ///
+    /// Shorthand for `EditDistance::new().distance(self, other)`.
+    pub fn levenshtein(&self, other: &Self) -> usize {
+        EditDistance::new()
+            .distance(self, other)
+            .expect("unbounded distance always fits")
+    }
+}
+
+/// Global edit distance (unit cost substitutions, insertions and deletions).
+///
+/// Unbanded distances are computed with the Myers/Hyyrö bit-parallel
+/// algorithm, 64 DP rows per word; everything else falls back to a banded
+/// DP over the packed symbols.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditDistance {
+    band: Option<usize>,
+    max_distance: Option<usize>,
+}
+
+impl EditDistance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the alignment to diagonals within `band` of the main one.
+    ///
+    /// Sequences whose lengths differ by more than `band` have no alignment.
+    pub fn with_band(mut self, band: usize) -> Self {
+        self.band = Some(band);
+        self
+    }
+
+    /// Gives up as soon as the distance is known to exceed `max`.
+    pub fn with_max_distance(mut self, max: usize) -> Self {
+        self.max_distance = Some(max);
+        self
+    }
+
+    /// Returns the edit distance between `a` and `b`, or `None` if it exceeds
+    /// the maximum distance or no alignment fits in the band.
+    pub fn distance<A: Alphabet>(&self, a: &Seq<A>, b: &Seq<A>) -> Option<usize> {
+        match self.band {
+            None => self.bit_parallel(a, b),
+            Some(_) => self.matrix(a, b).map(|m| m.score(a.len(), b.len())),
+        }
+    }
+
+    /// Like [`EditDistance::distance`], but also returns an optimal edit
+    /// script turning `a` into `b`.
+    pub fn traceback<A: Alphabet>(&self, a: &Seq<A>, b: &Seq<A>) -> Option<EditScript> {
+        let matrix = self.matrix(a, b)?;
+        let (mut i, mut j) = (a.len(), b.len());
+        let mut script = EditScript::default();
+
+        // Walk back from the end, preferring diagonal moves
+        while i > 0 || j > 0 {
+            let score = matrix.score(i, j);
+            if i > 0 && j > 0 {
+                let same = a.get_bits(i - 1) == b.get_bits(j - 1);
+                if matrix.score(i - 1, j - 1) + (!same as usize) == score {
+                    script.push(if same {
+                        EditOp::Match
+                    } else {
+                        EditOp::Mismatch
+                    });
+                    i -= 1;
+                    j -= 1;
+                    continue;
+                }
+            }
+            if i > 0 && matrix.score(i - 1, j) + 1 == score {
+                script.push(EditOp::Insertion);
+                i -= 1;
+            } else {
+                script.push(EditOp::Deletion);
+                j -= 1;
+            }
+        }
+
+        script.ops.reverse();
+        Some(script)
+    }
+
+    fn bit_parallel<A: Alphabet>(&self, a: &Seq<A>, b: &Seq<A>) -> Option<usize> {
+        // The shorter sequence runs down the (bit-packed) columns
+        let (pattern, text) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+        let (m, n) = (pattern.len(), text.len());
+        let max = self.max_distance.unwrap_or(usize::MAX);
+        if n - m > max {
+            return None;
+        }
+        if m == 0 {
+            return Some(n);
+        }
+
+        let blocks = m.div_ceil(WORD);
+        let mut peq = vec![0u64; (1 << A::BITS) * blocks];
+        for i in 0..m {
+            peq[pattern.get_bits(i) as usize * blocks + i / WORD] |= 1 << (i % WORD);
+        }
+        let mut pv = vec![!0u64; blocks];
+        let mut mv = vec![0u64; blocks];
+        let last_bit = 1 << ((m - 1) % WORD);
+        let mut score = m;
+
+        for j in 0..n {
+            let symbol = text.get_bits(j) as usize;
+            let eq = &peq[symbol * blocks..(symbol + 1) * blocks];
+
+            // The top row counts up in global alignment
+            let mut hin = 1;
+            for b in 0..blocks - 1 {
+                hin = advance_block(&mut pv[b], &mut mv[b], eq[b], hin, 1 << 63);
+            }
+            let last = blocks - 1;
+            let hout = advance_block(&mut pv[last], &mut mv[last], eq[last], hin, last_bit);
+            score = score.wrapping_add_signed(hout as isize);
+
+            // Each remaining column lowers the final score by at most one
+            if score > max.saturating_add(n - j - 1) {
+                return None;
+            }
+        }
+        (score <= max).then_some(score)
+    }
+
+    fn matrix<A: Alphabet>(&self, a: &Seq<A>, b: &Seq<A>) -> Option<BandedMatrix> {
+        let (m, n) = (a.len(), b.len());
+        let max = self.max_distance.unwrap_or(usize::MAX);
+        // A path never strays further from the diagonal than the distance itself
+        let band = self.band.unwrap_or(usize::MAX).min(max).min(m.max(n));
+        if m.abs_diff(n) > band {
+            return None;
+        }
+
+        let mut matrix = BandedMatrix::new(m, n, band);
+        for j in matrix.columns(0) {
+            matrix.set(0, j, j as u32);
+        }
+        for i in 1..=m {
+            let symbol = a.get_bits(i - 1);
+            let mut row_min = UNREACHABLE;
+            for j in matrix.columns(i) {
+                let mut cost = matrix.get(i - 1, j) + 1;
+                if j > 0 {
+                    let diagonal = matrix.get(i - 1, j - 1) + (symbol != b.get_bits(j - 1)) as u32;
+                    cost = cost.min(diagonal).min(matrix.get(i, j - 1) + 1);
+                }
+                matrix.set(i, j, cost);
+                row_min = row_min.min(cost);
+            }
+            // Scores never decrease along a path, so the row minimum is a lower bound
+            if row_min as usize > max {
+                return None;
+            }
+        }
+
+        (matrix.score(m, n) <= max).then_some(matrix)
+    }
+}
+
+/// DP matrix storing only the cells within a band around the diagonal.
+struct BandedMatrix {
+    cells: Vec<u32>,
+    /// Index of the first stored cell of each row.
+    offsets: Vec<usize>,
+    cols: usize,
+    band: usize,
+}
+
+impl BandedMatrix {
+    fn new(rows: usize, cols: usize, band: usize) -> Self {
+        let mut matrix = Self {
+            cells: Vec::new(),
+            offsets: Vec::with_capacity(rows + 2),
+            cols,
+            band,
+        };
+        let mut offset = 0;
+        for i in 0..=rows {
+            matrix.offsets.push(offset);
+            offset += matrix.columns(i).len();
+        }
+        matrix.offsets.push(offset);
+        matrix.cells = vec![UNREACHABLE; offset];
+        matrix
+    }
+
+    fn columns(&self, row: usize) -> std::ops::Range<usize> {
+        row.saturating_sub(self.band)..row.saturating_add(self.band).min(self.cols) + 1
+    }
+
+    fn get(&self, row: usize, col: usize) -> u32 {
+        let start = row.saturating_sub(self.band);
+        if col < start || self.offsets[row] + col - start >= self.offsets[row + 1] {
+            return UNREACHABLE;
+        }
+        self.cells[self.offsets[row] + col - start]
+    }
+
+    fn set(&mut self, row: usize, col: usize, cost: u32) {
+        self.cells[self.offsets[row] + col - row.saturating_sub(self.band)] = cost;
+    }
+
+    fn score(&self, row: usize, col: usize) -> usize {
+        self.get(row, col) as usize
+    }
+}
+
+/// A single edit operation, in the sense of turning the first sequence into
+/// the second one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditOp {
+    /// Both sequences carry the same symbol (`=`).
+    Match,
+    /// The symbols differ (`X`).
+    Mismatch,
+    /// A symbol only present in the first sequence (`I`).
+    Insertion,
+    /// A symbol only present in the second sequence (`D`).
+    Deletion,
+}
+
+impl EditOp {
+    /// Returns the CIGAR character of the operation.
+    pub fn to_char(self) -> char {
+        match self {
+            EditOp::Match => '=',
+            EditOp::Mismatch => 'X',
+            EditOp::Insertion => 'I',
+            EditOp::Deletion => 'D',
+        }
+    }
+}
+
+/// Run-length encoded list of edit operations, printed like a CIGAR string.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditScript {
+    ops: Vec<(EditOp, usize)>,
+}
+
+impl EditScript {
+    /// Returns the runs of operations as `(op, count)` pairs.
+    pub fn ops(&self) -> &[(EditOp, usize)] {
+        &self.ops
+    }
+
+    /// Returns the number of non-match operations.
+    pub fn distance(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|(op, _)| *op != EditOp::Match)
+            .map(|(_, n)| n)
+            .sum()
+    }
+
+    /// Returns an iterator over the individual (unrolled) operations.
+    pub fn iter(&self) -> impl Iterator<Item = EditOp> + '_ {
+        self.ops
+            .iter()
+            .flat_map(|&(op, n)| std::iter::repeat_n(op, n))
+    }
+
+    fn push(&mut self, op: EditOp) {
+        match self.ops.last_mut() {
+            Some((last, n)) if *last == op => *n += 1,
+            _ => self.ops.push((op, 1)),
+        }
+    }
+}
+
+impl FromIterator<EditOp> for EditScript {
+    fn from_iter<I: IntoIterator<Item = EditOp>>(iter: I) -> Self {
+        let mut script = EditScript::default();
+        for op in iter {
+            script.push(op);
+        }
+        script
+    }
+}
+
+impl fmt::Display for EditScript {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (op, n) in &self.ops {
+            write!(f, "{n}{}", op.to_char())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+use std::io::{self, Write};
+
+use super::orientation;
+use crate::alphabet::{Nuc4, Strand};
+use crate::kmer::{
+    canonical_kmer, kmer_from_seq, kmer_mask, kmer_to_seq, reverse_complement_kmer, KmerCounter,
+    KmerCounts, MAX_K,
+};
+use crate::seq::Seq;
+
+/// A de Bruijn graph node read in one orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    pub node: usize,
+    pub strand: Strand,
+}
+
+impl Handle {
+    pub fn new(node: usize, strand: Strand) -> Self {
+        Self { node, strand }
+    }
+
+    /// Returns the same node in the opposite orientation.
+    pub fn flip(self) -> Self {
+        Self::new(self.node, flip(self.strand))
+    }
+}
+
+/// Bi-directed de Bruijn graph over canonical k-mers.
+///
+/// Every node is a canonical k-mer; reading it in [`Strand::Reverse`] spells
+/// its reverse complement. Edges are implicit: a handle is followed by every
+/// k-mer of the graph that extends it by one base, in whichever orientation
+/// that k-mer is stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeBruijnGraph {
+    k: usize,
+    /// Sorted canonical k-mer words.
+    kmers: Vec<u64>,
+    counts: Vec<u32>,
+}
+
+impl DeBruijnGraph {
+    /// Builds the graph of all k-mers of the given reads.
+    ///
+    /// Panics unless `1 <= k <= 32`.
+    pub fn from_seqs(seqs: &[Seq<Nuc4>], k: usize) -> Self {
+        Self::from_counts(&KmerCounter::new(k).count(seqs))
+    }
+
+    /// Builds the graph of a counted k-mer set, keeping the counts.
+    pub fn from_counts(counts: &KmerCounts) -> Self {
+        let (kmers, values) = counts.iter().unzip();
+        Self {
+            k: counts.k(),
+            kmers,
+            counts: values,
+        }
+    }
+
+    /// Builds the graph of a set of k-mer words in any orientation. Each
+    /// node counts how often its k-mer was given.
+    ///
+    /// Panics unless `1 <= k <= 32`.
+    pub fn from_kmers<I: IntoIterator<Item = u64>>(k: usize, words: I) -> Self {
+        assert!((1..=MAX_K).contains(&k), "k must be between 1 and {MAX_K}");
+        let mask = kmer_mask(k);
+        let mut all: Vec<u64> = words
+            .into_iter()
+            .map(|word| canonical_kmer(word & mask, k))
+            .collect();
+        all.sort_unstable();
+
+        let mut kmers: Vec<u64> = Vec::new();
+        let mut counts: Vec<u32> = Vec::new();
+        for word in all {
+            if kmers.last() == Some(&word) {
+                *counts.last_mut().unwrap() += 1;
+            } else {
+                kmers.push(word);
+                counts.push(1);
+            }
+        }
+        Self { k, kmers, counts }
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.kmers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kmers.is_empty()
+    }
+
+    /// Returns the canonical k-mer word of a node.
+    pub fn kmer(&self, node: usize) -> u64 {
+        self.kmers[node]
+    }
+
+    /// Returns how often the k-mer of a node was seen.
+    pub fn count(&self, node: usize) -> u32 {
+        self.counts[node]
+    }
+
+    /// Returns the k-mer word spelled by a handle.
+    pub fn word(&self, handle: Handle) -> u64 {
+        let kmer = self.kmers[handle.node];
+        match handle.strand {
+            Strand::Forward => kmer,
+            Strand::Reverse => reverse_complement_kmer(kmer, self.k),
+        }
+    }
+
+    /// Returns the k-mer spelled by a handle as a sequence.
+    pub fn spell(&self, handle: Handle) -> Seq<Nuc4> {
+        kmer_to_seq(self.word(handle), self.k)
+    }
+
+    /// Looks up a k-mer word, returning the handle that spells it.
+    pub fn find(&self, word: u64) -> Option<Handle> {
+        let canonical = canonical_kmer(word, self.k);
+        let node = self.kmers.binary_search(&canonical).ok()?;
+        let strand = if word == canonical {
+            Strand::Forward
+        } else {
+            Strand::Reverse
+        };
+        Some(Handle::new(node, strand))
+    }
+
+    /// Looks up a k-mer given as a sequence.
+    ///
+    /// Panics if the sequence length differs from `k`.
+    pub fn find_seq(&self, kmer: &Seq<Nuc4>) -> Option<Handle> {
+        assert_eq!(kmer.len(), self.k, "expected a {}-mer", self.k);
+        self.find(kmer_from_seq(kmer))
+    }
+
+    // -- Traversal -----------------------------------------------------------
+
+    /// Returns the handles that follow `handle`, i.e. whose k-mer starts
+    /// with its last `k - 1` bases.
+    pub fn successors(&self, handle: Handle) -> impl Iterator<Item = Handle> + '_ {
+        let shifted = self.word(handle) << 2 & kmer_mask(self.k);
+        (0..4).filter_map(move |base| self.find(shifted | base))
+    }
+
+    /// Returns the handles that precede `handle`.
+    pub fn predecessors(&self, handle: Handle) -> impl Iterator<Item = Handle> + '_ {
+        self.successors(handle.flip()).map(Handle::flip)
+    }
+
+    pub fn out_degree(&self, handle: Handle) -> usize {
+        self.successors(handle).count()
+    }
+
+    pub fn in_degree(&self, handle: Handle) -> usize {
+        self.predecessors(handle).count()
+    }
+
+    /// Returns the single successor of `handle` if the edge between them
+    /// is the only one leaving `handle` and entering the successor.
+    ///
+    /// Palindromic k-mers (even `k` only) are never extended through: a path
+    /// entering one leaves it on the opposite strand, so they form unitigs
+    /// of their own.
+    fn unique_successor(&self, handle: Handle) -> Option<Handle> {
+        if self.is_palindrome(handle.node) {
+            return None;
+        }
+        let mut successors = self.successors(handle);
+        let next = successors.next()?;
+        if successors.next().is_some()
+            || next.node == handle.node
+            || self.is_palindrome(next.node)
+            || self.in_degree(next) != 1
+        {
+            return None;
+        }
+        Some(next)
+    }
+
+    /// Checks if a node's k-mer is its own reverse complement.
+    fn is_palindrome(&self, node: usize) -> bool {
+        let word = self.kmers[node];
+        word == reverse_complement_kmer(word, self.k)
+    }
+
+    // -- Compaction ----------------------------------------------------------
+
+    /// Merges maximal non-branching paths into unitigs.
+    ///
+    /// Every node ends up in exactly one unitig; unitigs are linked where
+    /// their ends overlap by `k - 1` bases.
+    pub fn compact(&self) -> UnitigGraph {
+        let mut visited = vec![false; self.len()];
+        let mut unitigs = Vec::new();
+        for start in 0..self.len() {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+
+            let mut forward = vec![Handle::new(start, Strand::Forward)];
+            let mut current = forward[0];
+            while let Some(next) = self.unique_successor(current) {
+                if std::mem::replace(&mut visited[next.node], true) {
+                    break;
+                }
+                forward.push(next);
+                current = next;
+            }
+
+            let mut backward = Vec::new();
+            let mut current = Handle::new(start, Strand::Reverse);
+            while let Some(next) = self.unique_successor(current) {
+                if std::mem::replace(&mut visited[next.node], true) {
+                    break;
+                }
+                backward.push(next.flip());
+                current = next;
+            }
+
+            backward.reverse();
+            backward.extend(forward);
+            unitigs.push(self.unitig(backward));
+        }
+
+        let links = self.links(&unitigs);
+        UnitigGraph {
+            k: self.k,
+            unitigs,
+            links,
+        }
+    }
+
+    fn unitig(&self, nodes: Vec<Handle>) -> Unitig {
+        let first = self.word(nodes[0]);
+        let mut seq = Seq::new(self.k + nodes.len() - 1);
+        for i in 0..self.k {
+            seq.init_with(i, (first >> (2 * (self.k - 1 - i)) & 3) as u8);
+        }
+        for (i, &handle) in nodes.iter().enumerate().skip(1) {
+            seq.init_with(self.k - 1 + i, (self.word(handle) & 3) as u8);
+        }
+        let count = nodes.iter().map(|h| self.counts[h.node] as u64).sum();
+        Unitig { seq, nodes, count }
+    }
+
+    fn links(&self, unitigs: &[Unitig]) -> Vec<UnitigLink> {
+        // Unitig, offset and strand of every node
+        let mut place = vec![(0, 0, Strand::Forward); self.len()];
+        for (id, unitig) in unitigs.iter().enumerate() {
+            for (i, handle) in unitig.nodes.iter().enumerate() {
+                place[handle.node] = (id, i, handle.strand);
+            }
+        }
+
+        let mut links = Vec::new();
+        for (id, unitig) in unitigs.iter().enumerate() {
+            let ends = [
+                (Strand::Forward, *unitig.nodes.last().unwrap()),
+                (Strand::Reverse, unitig.nodes[0].flip()),
+            ];
+            for (from_strand, end) in ends {
+                for next in self.successors(end) {
+                    // Links only enter unitigs at either end, so a node read
+                    // as stored is the first one. Palindromes are unitigs of
+                    // their own and read the same either way.
+                    let (to, offset, strand) = place[next.node];
+                    let to_strands: &[Strand] = if self.is_palindrome(next.node) {
+                        &[Strand::Forward, Strand::Reverse]
+                    } else if offset == 0 && next.strand == strand {
+                        &[Strand::Forward]
+                    } else {
+                        &[Strand::Reverse]
+                    };
+                    for &to_strand in to_strands {
+                        links.push(UnitigLink::new(id, from_strand, to, to_strand).canonical());
+                    }
+                }
+            }
+        }
+        links.sort_unstable_by_key(UnitigLink::key);
+        links.dedup();
+        links
+    }
+}
+
+/// A maximal non-branching path of a [`DeBruijnGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unitig {
+    /// Sequence spelled by the path.
+    pub seq: Seq<Nuc4>,
+    /// Handles along the path.
+    pub nodes: Vec<Handle>,
+    /// Summed count of the path's k-mers.
+    pub count: u64,
+}
+
+/// An overlap of `k - 1` bases between the end of one oriented unitig and
+/// the start of another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnitigLink {
+    pub from: usize,
+    pub from_strand: Strand,
+    pub to: usize,
+    pub to_strand: Strand,
+}
+
+impl UnitigLink {
+    pub fn new(from: usize, from_strand: Strand, to: usize, to_strand: Strand) -> Self {
+        Self {
+            from,
+            from_strand,
+            to,
+            to_strand,
+        }
+    }
+
+    /// Returns the same overlap read along the other strand.
+    pub fn reverse(self) -> Self {
+        Self::new(
+            self.to,
+            flip(self.to_strand),
+            self.from,
+            flip(self.from_strand),
+        )
+    }
+
+    /// Returns whichever of the link and its reverse sorts first, so that
+    /// both spellings of an overlap compare equal.
+    pub fn canonical(self) -> Self {
+        let reverse = self.reverse();
+        if reverse.key() < self.key() {
+            reverse
+        } else {
+            self
+        }
+    }
+
+    fn key(&self) -> (usize, bool, usize, bool) {
+        (
+            self.from,
+            self.from_strand == Strand::Reverse,
+            self.to,
+            self.to_strand == Strand::Reverse,
+        )
+    }
+}
+
+/// Compacted de Bruijn graph of unitigs, see [`DeBruijnGraph::compact`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitigGraph {
+    k: usize,
+    unitigs: Vec<Unitig>,
+    links: Vec<UnitigLink>,
+}
+
+impl UnitigGraph {
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of unitigs.
+    pub fn len(&self) -> usize {
+        self.unitigs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unitigs.is_empty()
+    }
+
+    pub fn unitigs(&self) -> &[Unitig] {
+        &self.unitigs
+    }
+
+    /// Returns the links in canonical form, each overlap once.
+    pub fn links(&self) -> &[UnitigLink] {
+        &self.links
+    }
+
+    /// Writes the graph as GFA 1.0, one `S` line per unitig (named by its
+    /// index, with `LN` and summed k-mer count `KC` tags) and one `L` line
+    /// per link.
+    pub fn write_gfa<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "H\tVN:Z:1.0")?;
+        for (id, unitig) in self.unitigs.iter().enumerate() {
+            writeln!(
+                writer,
+                "S\t{id}\t{}\tLN:i:{}\tKC:i:{}",
+                unitig.seq,
+                unitig.seq.len(),
+                unitig.count
+            )?;
+        }
+        for link in &self.links {
+            writeln!(
+                writer,
+                "L\t{}\t{}\t{}\t{}\t{}M",
+                link.from,
+                orientation(link.from_strand),
+                link.to,
+                orientation(link.to_strand),
+                self.k - 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn flip(strand: Strand) -> Strand {
+    match strand {
+        Strand::Forward => Strand::Reverse,
+        Strand::Reverse => Strand::Forward,
+    }
+}
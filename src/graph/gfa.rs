@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+
+use super::Handle;
+use crate::align::{Cigar, CigarError};
+use crate::alphabet::{Nuc5, Strand};
+use crate::seq::{Seq, SeqError};
+
+#[derive(Debug)]
+pub enum GfaError {
+    Io(io::Error),
+    Seq(SeqError),
+    /// Malformed input on the given (1-based) line.
+    Parse {
+        line: usize,
+        message: String,
+    },
+    /// A record refers to a segment that was never defined.
+    UnknownSegment(String),
+    /// Two segments share a name.
+    DuplicateSegment(String),
+    /// A walk steps between segments that are not linked.
+    MissingLink {
+        from: String,
+        to: String,
+    },
+    /// A walk visits a segment stored without its sequence (`*`).
+    MissingSequence(String),
+    /// An overlap that is neither a CIGAR string nor `*`.
+    Cigar(CigarError),
+}
+
+impl fmt::Display for GfaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GfaError::Io(e) => write!(f, "I/O error: {e}"),
+            GfaError::Seq(e) => write!(f, "invalid sequence: {e:?}"),
+            GfaError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            GfaError::UnknownSegment(name) => write!(f, "unknown segment {name}"),
+            GfaError::DuplicateSegment(name) => write!(f, "duplicate segment {name}"),
+            GfaError::MissingLink { from, to } => write!(f, "no link from {from} to {to}"),
+            GfaError::MissingSequence(name) => write!(f, "segment {name} has no sequence"),
+            GfaError::Cigar(e) => write!(f, "invalid overlap: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for GfaError {}
+
+impl From<io::Error> for GfaError {
+    fn from(e: io::Error) -> Self {
+        GfaError::Io(e)
+    }
+}
+
+impl From<SeqError> for GfaError {
+    fn from(e: SeqError) -> Self {
+        GfaError::Seq(e)
+    }
+}
+
+impl From<CigarError> for GfaError {
+    fn from(e: CigarError) -> Self {
+        GfaError::Cigar(e)
+    }
+}
+
+// -- Records -----------------------------------------------------------------
+
+/// A named sequence; `seq` is `None` when the file stores `*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub name: String,
+    pub seq: Option<Seq<Nuc5>>,
+    pub length: usize,
+    /// Optional `TAG:TYPE:VALUE` fields, kept verbatim.
+    pub tags: Vec<String>,
+}
+
+impl Segment {
+    pub fn new(name: &str, seq: Seq<Nuc5>) -> Self {
+        Self {
+            name: name.to_string(),
+            length: seq.len(),
+            seq: Some(seq),
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// An overlap between the end of `from` and the start of `to`, both read in
+/// their orientation. `overlap` is a CIGAR string or `*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub from: Handle,
+    pub to: Handle,
+    pub overlap: String,
+    pub tags: Vec<String>,
+}
+
+impl Link {
+    pub fn new(from: Handle, to: Handle, overlap: &str) -> Self {
+        Self {
+            from,
+            to,
+            overlap: overlap.to_string(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// `contained` lies within `container`, starting at `pos` on the container's
+/// forward strand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Containment {
+    pub container: Handle,
+    pub contained: Handle,
+    pub pos: usize,
+    pub overlap: String,
+}
+
+/// A named walk through oriented segments. `overlaps` holds the CIGAR
+/// strings between consecutive steps, empty when not given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub name: String,
+    pub steps: Vec<Handle>,
+    pub overlaps: Vec<String>,
+}
+
+// -- Graph -------------------------------------------------------------------
+
+/// A GFA sequence graph: segments joined by links, with containments and
+/// paths. Records refer to segments by index.
+///
+/// Links are bi-directed: a link `a+ -> b-` can also be walked as
+/// `b+ -> a-`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Gfa {
+    segments: Vec<Segment>,
+    ids: HashMap<String, usize>,
+    links: Vec<Link>,
+    /// Links leaving each segment's forward and reverse handle.
+    adjacency: Vec<[Vec<usize>; 2]>,
+    containments: Vec<Containment>,
+    paths: Vec<Path>,
+}
+
+impl Gfa {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a segment, returning its index.
+    pub fn add_segment(&mut self, segment: Segment) -> Result<usize, GfaError> {
+        if self.ids.contains_key(&segment.name) {
+            return Err(GfaError::DuplicateSegment(segment.name));
+        }
+        let id = self.segments.len();
+        self.ids.insert(segment.name.clone(), id);
+        self.segments.push(segment);
+        self.adjacency.push([Vec::new(), Vec::new()]);
+        Ok(id)
+    }
+
+    /// Adds a link between existing segments.
+    ///
+    /// Panics if either segment index is out of range.
+    pub fn add_link(&mut self, link: Link) {
+        let index = self.links.len();
+        self.adjacency[link.from.node][side(link.from.strand)].push(index);
+        let back = link.to.flip();
+        // A link from a handle to its own reverse is listed once
+        if back != link.from {
+            self.adjacency[back.node][side(back.strand)].push(index);
+        }
+        self.links.push(link);
+    }
+
+    pub fn add_containment(&mut self, containment: Containment) {
+        self.containments.push(containment);
+    }
+
+    pub fn add_path(&mut self, path: Path) {
+        self.paths.push(path);
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    pub fn containments(&self) -> &[Containment] {
+        &self.containments
+    }
+
+    pub fn paths(&self) -> &[Path] {
+        &self.paths
+    }
+
+    /// Returns the index of a segment by name.
+    pub fn segment_id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn segment(&self, name: &str) -> Option<&Segment> {
+        self.segment_id(name).map(|id| &self.segments[id])
+    }
+
+    pub fn path(&self, name: &str) -> Option<&Path> {
+        self.paths.iter().find(|p| p.name == name)
+    }
+
+    // -- Traversal -----------------------------------------------------------
+
+    /// Returns the handles reachable from `handle` over one link, with the
+    /// link taken.
+    pub fn successors(&self, handle: Handle) -> impl Iterator<Item = (Handle, &Link)> + '_ {
+        self.adjacency[handle.node][side(handle.strand)]
+            .iter()
+            .map(move |&index| {
+                let link = &self.links[index];
+                if link.from == handle {
+                    (link.to, link)
+                } else {
+                    (link.from.flip(), link)
+                }
+            })
+    }
+
+    /// Returns the handles that lead into `handle` over one link.
+    pub fn predecessors(&self, handle: Handle) -> impl Iterator<Item = (Handle, &Link)> + '_ {
+        self.successors(handle.flip())
+            .map(|(other, link)| (other.flip(), link))
+    }
+
+    /// Returns a segment's sequence read along a strand.
+    pub fn oriented_seq(&self, handle: Handle) -> Result<Seq<Nuc5>, GfaError> {
+        let segment = &self.segments[handle.node];
+        let seq = segment
+            .seq
+            .as_ref()
+            .ok_or_else(|| GfaError::MissingSequence(segment.name.clone()))?;
+        Ok(match handle.strand {
+            Strand::Forward => seq.clone(),
+            Strand::Reverse => seq.reverse_complement(),
+        })
+    }
+
+    /// Spells the sequence of a walk, reverse complementing segments read
+    /// on the reverse strand and dropping the bases each link overlaps.
+    ///
+    /// Links without an overlap (`*`) are taken as blunt; overlaps that do
+    /// not parse as CIGAR strings are an error.
+    pub fn spell(&self, steps: &[Handle]) -> Result<Seq<Nuc5>, GfaError> {
+        let Some(&first) = steps.first() else {
+            return Ok(Seq::new(0));
+        };
+        let mut seq = self.oriented_seq(first)?;
+        for pair in steps.windows(2) {
+            let link = self
+                .successors(pair[0])
+                .find(|&(next, _)| next == pair[1])
+                .map(|(_, link)| link)
+                .ok_or_else(|| GfaError::MissingLink {
+                    from: self.step_name(pair[0]),
+                    to: self.step_name(pair[1]),
+                })?;
+            let overlap = link.overlap.parse::<Cigar>()?;
+            // Walking a link backwards swaps its sides
+            let skip = if link.from == pair[0] {
+                overlap.query_len()
+            } else {
+                overlap.reference_len()
+            };
+            let next = self.oriented_seq(pair[1])?;
+            seq = seq.append(&next.slice(skip.min(next.len())..));
+        }
+        Ok(seq)
+    }
+
+    /// Spells the sequence of a named path.
+    pub fn spell_path(&self, name: &str) -> Option<Result<Seq<Nuc5>, GfaError>> {
+        self.path(name).map(|path| self.spell(&path.steps))
+    }
+
+    fn step_name(&self, handle: Handle) -> String {
+        format!(
+            "{}{}",
+            self.segments[handle.node].name,
+            orientation(handle.strand)
+        )
+    }
+}
+
+fn side(strand: Strand) -> usize {
+    match strand {
+        Strand::Forward => 0,
+        Strand::Reverse => 1,
+    }
+}
+
+/// Returns the GFA sign of a strand.
+pub(crate) fn orientation(strand: Strand) -> char {
+    match strand {
+        Strand::Forward => '+',
+        Strand::Reverse => '-',
+    }
+}
@@ -0,0 +1,5 @@
+mod debruijn;
+mod gfa;
+
+pub use debruijn::*;
+pub use gfa::*;
@@ -1,3 +1,6 @@
+use crate::alphabet::Nuc4;
+use crate::seq::Seq;
+
 pub const CHAR_TO_TWO_BIT: [u8; 256] = {
     let mut cache = [0; 256];
     cache[99] = 1; // lowercase c
@@ -35,3 +38,160 @@ pub fn hash_chars_le(bytes: &[u8]) -> u8 {
         | (CHAR_TO_TWO_BIT[bytes[2] as usize] << 4)
         | (CHAR_TO_TWO_BIT[bytes[3] as usize] << 6)
 }
+
+/// Lookup table for CRC-32 (IEEE 802.3, reflected polynomial `0xEDB88320`).
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Incremental CRC-32 checksum, as used by zlib and gzip.
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32 {
+    pub fn new() -> Self {
+        Self { state: !0 }
+    }
+
+    /// Feeds `bytes` into the checksum.
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.state = CRC32_TABLE[((self.state ^ b as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    /// Returns the checksum of everything fed so far.
+    pub fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+/// Computes the CRC-32 checksum of a byte slice.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(bytes);
+    crc.finish()
+}
+
+/// Scrambles a 64-bit word (the MurmurHash3 finalizer).
+///
+/// Bijective, so distinct k-mer words keep distinct hashes.
+#[inline]
+pub fn mix64(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51afd7ed558ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ceb9fe1a85ec53);
+    x ^ (x >> 33)
+}
+
+// -- ntHash ------------------------------------------------------------------
+
+/// ntHash seeds for `A`, `C`, `G` and `T`, indexed by 2-bit code.
+const NT_SEEDS: [u64; 4] = [
+    0x3c8bfbb395c60474,
+    0x3193c18562a02b4c,
+    0x20323ed082572324,
+    0x295549f54be24456,
+];
+
+const NT_MULTI_SEED: u64 = 0x90b45d39fb6da1fa;
+const NT_MULTI_SHIFT: u32 = 27;
+
+/// Rolling canonical ntHash over the k-mers of a `Seq<Nuc4>`.
+///
+/// Each step updates the forward and reverse complement hashes in constant
+/// time and yields the smaller of the two, so a k-mer and its reverse
+/// complement hash alike. Any `k >= 1` is supported.
+#[derive(Debug, Clone)]
+pub struct NtHash<'a> {
+    seq: &'a Seq<Nuc4>,
+    k: usize,
+    pos: usize,
+    forward: u64,
+    reverse: u64,
+}
+
+impl<'a> NtHash<'a> {
+    /// Panics if `k` is 0.
+    pub fn new(seq: &'a Seq<Nuc4>, k: usize) -> Self {
+        assert!(k > 0, "k must be positive");
+        Self {
+            seq,
+            k,
+            pos: 0,
+            forward: 0,
+            reverse: 0,
+        }
+    }
+}
+
+impl Iterator for NtHash<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.pos + self.k > self.seq.len() {
+            return None;
+        }
+        let k = self.k as u32;
+        if self.pos == 0 {
+            for i in 0..self.k {
+                let code = self.seq.get_bits(i) as usize;
+                self.forward ^= NT_SEEDS[code].rotate_left(k - 1 - i as u32);
+                self.reverse ^= NT_SEEDS[3 - code].rotate_left(i as u32);
+            }
+        } else {
+            let out = self.seq.get_bits(self.pos - 1) as usize;
+            let inc = self.seq.get_bits(self.pos + self.k - 1) as usize;
+            self.forward =
+                self.forward.rotate_left(1) ^ NT_SEEDS[out].rotate_left(k) ^ NT_SEEDS[inc];
+            self.reverse = self.reverse.rotate_right(1)
+                ^ NT_SEEDS[3 - out].rotate_right(1)
+                ^ NT_SEEDS[3 - inc].rotate_left(k - 1);
+        }
+        self.pos += 1;
+        Some(self.forward.min(self.reverse))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.seq.len() + 1).saturating_sub(self.pos + self.k);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for NtHash<'_> {}
+
+/// Derives the `i`-th extra hash of a k-mer from its ntHash value, as ntHash
+/// does for Bloom filters. Hash 0 is the value itself.
+#[inline]
+pub fn nthash_multi(hash: u64, i: u32, k: usize) -> u64 {
+    if i == 0 {
+        return hash;
+    }
+    let h = hash.wrapping_mul(i as u64 ^ (k as u64).wrapping_mul(NT_MULTI_SEED));
+    h ^ (h >> NT_MULTI_SHIFT)
+}
@@ -0,0 +1,390 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use super::suffix_array::{text_position, Position, SuffixArray};
+use crate::alphabet::{Alphabet, NamedAlphabet};
+use crate::bitvec::{BitVec, BitVecBuilder};
+use crate::seq::Seq;
+
+const MAGIC: &[u8; 8] = b"NUCFMIDX";
+
+/// Current serialization format version.
+pub const FM_VERSION: u32 = 1;
+
+/// Rows between occurrence checkpoints.
+const OCC_RATE: usize = 64;
+
+/// Default distance between sampled text positions.
+pub const DEFAULT_SAMPLE_RATE: usize = 32;
+
+/// FM-index over one or more sequences.
+///
+/// Stores the Burrows-Wheeler transform packed as a `Seq<A>`, occurrence
+/// counts every 64 rows and every `sample_rate`-th text position of the
+/// suffix array. Patterns are matched by backward search and never span two
+/// sequences of a collection.
+#[derive(Debug, Clone)]
+pub struct FmIndex<A: Alphabet> {
+    /// BWT; rows preceded by a sentinel hold symbol 0.
+    bwt: Seq<A>,
+    /// BWT rows holding a sentinel, with the text position of their suffix.
+    sentinels: Vec<(usize, usize)>,
+    /// Text position of the sentinel after each sequence.
+    ends: Vec<usize>,
+    /// Number of suffixes starting with a sentinel or a symbol below `c`.
+    counts: Vec<usize>,
+    /// Symbol counts before every checkpoint row, `A::SIZE` per checkpoint.
+    occ: Vec<usize>,
+    sample_rate: usize,
+    /// Rows whose suffix array entry is sampled.
+    sampled: BitVec,
+    samples: Vec<usize>,
+}
+
+impl<A: Alphabet> FmIndex<A> {
+    /// Builds the index of a single sequence.
+    pub fn new(seq: &Seq<A>) -> Self {
+        Self::from_suffix_array(&SuffixArray::new(seq), DEFAULT_SAMPLE_RATE)
+    }
+
+    /// Builds the index of a collection of sequences.
+    pub fn from_seqs(seqs: &[Seq<A>]) -> Self {
+        Self::from_suffix_array(&SuffixArray::from_seqs(seqs), DEFAULT_SAMPLE_RATE)
+    }
+
+    /// Builds the index from a suffix array, keeping every text position
+    /// divisible by `sample_rate` for `locate`.
+    ///
+    /// Panics if `sample_rate` is 0.
+    pub fn from_suffix_array(sa: &SuffixArray<A>, sample_rate: usize) -> Self {
+        assert!(sample_rate > 0, "sample rate must be positive");
+        let n = sa.len();
+        let mut bwt = Seq::new(n);
+        let mut sentinels = Vec::with_capacity(sa.ends.len());
+        let mut sampled = BitVecBuilder::new(n);
+        let mut samples = Vec::with_capacity(n / sample_rate + 1);
+
+        for (row, &pos) in sa.sa.iter().enumerate() {
+            if pos == 0 || sa.ends.binary_search(&(pos - 1)).is_ok() {
+                sentinels.push((row, pos));
+            } else {
+                bwt.init_with(row, sa.text.get_bits(pos - 1));
+            }
+            if pos.is_multiple_of(sample_rate) {
+                sampled.set(row, true);
+                samples.push(pos);
+            }
+        }
+
+        let mut index = Self {
+            bwt,
+            sentinels,
+            ends: sa.ends.clone(),
+            counts: Vec::new(),
+            occ: Vec::new(),
+            sample_rate,
+            sampled: sampled.build(),
+            samples,
+        };
+        index.build_tables();
+        index
+    }
+
+    /// Returns the number of BWT rows, sentinels included.
+    pub fn len(&self) -> usize {
+        self.bwt.len()
+    }
+
+    /// Checks if the index holds no sequences.
+    pub fn is_empty(&self) -> bool {
+        self.bwt.is_empty()
+    }
+
+    /// Returns the number of indexed sequences.
+    pub fn seq_count(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns the distance between sampled text positions.
+    pub fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    /// Returns the packed BWT. Rows holding a sentinel read as symbol 0.
+    pub fn bwt(&self) -> &Seq<A> {
+        &self.bwt
+    }
+
+    /// Returns the suffix array interval of `pattern` by backward search.
+    pub fn range(&self, pattern: &Seq<A>) -> Range<usize> {
+        let mut range = 0..self.len();
+        for i in (0..pattern.len()).rev() {
+            if range.is_empty() {
+                break;
+            }
+            let c = pattern.get_bits(i);
+            range = self.lf(c, range.start)..self.lf(c, range.end);
+        }
+        range
+    }
+
+    /// Counts the occurrences of `pattern`.
+    pub fn count(&self, pattern: &Seq<A>) -> usize {
+        self.range(pattern).len()
+    }
+
+    /// Returns all occurrences of `pattern`, sorted by position.
+    pub fn locate(&self, pattern: &Seq<A>) -> Vec<Position> {
+        let mut hits: Vec<Position> = self
+            .range(pattern)
+            .map(|row| text_position(&self.ends, self.suffix_at(row)))
+            .collect();
+        hits.sort_unstable();
+        hits
+    }
+
+    /// Returns the number of non-sentinel occurrences of symbol `c` in
+    /// `bwt[..row]`.
+    pub fn occ(&self, c: u8, row: usize) -> usize {
+        let block = row / OCC_RATE;
+        let mut count = self.occ[block * A::SIZE as usize + c as usize];
+        for i in block * OCC_RATE..row {
+            count += (self.bwt.get_bits(i) == c) as usize;
+        }
+        if c == 0 {
+            // Sentinel rows are stored as symbol 0
+            count -= self.sentinels.partition_point(|&(r, _)| r < row);
+        }
+        count
+    }
+
+    /// Maps row `row` through the LF mapping for symbol `c`.
+    fn lf(&self, c: u8, row: usize) -> usize {
+        self.counts[c as usize] + self.occ(c, row)
+    }
+
+    /// Recovers the suffix array entry of `row` by walking LF to the nearest
+    /// sample.
+    fn suffix_at(&self, mut row: usize) -> usize {
+        let mut steps = 0;
+        loop {
+            if self.sampled.get(row) {
+                return self.samples[self.sampled.rank1(row)] + steps;
+            }
+            if let Ok(i) = self.sentinels.binary_search_by_key(&row, |&(r, _)| r) {
+                return self.sentinels[i].1 + steps;
+            }
+            row = self.lf(self.bwt.get_bits(row), row);
+            steps += 1;
+        }
+    }
+
+    /// Derives the symbol counts and occurrence checkpoints from the BWT.
+    fn build_tables(&mut self) {
+        let size = A::SIZE as usize;
+        let mut tally = vec![0; size];
+        self.occ = Vec::with_capacity((self.len() / OCC_RATE + 1) * size);
+        for i in 0..self.len() {
+            if i.is_multiple_of(OCC_RATE) {
+                self.occ.extend_from_slice(&tally);
+            }
+            tally[self.bwt.get_bits(i) as usize] += 1;
+        }
+        if self.len().is_multiple_of(OCC_RATE) {
+            self.occ.extend_from_slice(&tally);
+        }
+
+        // Checkpoints hold raw counts; `occ` corrects symbol 0 for sentinels
+        tally[0] -= self.sentinels.len();
+        self.counts = Vec::with_capacity(size + 1);
+        self.counts.push(self.sentinels.len());
+        for c in 0..size {
+            self.counts.push(self.counts[c] + tally[c]);
+        }
+    }
+}
+
+// -- Serialization -----------------------------------------------------------
+
+impl<A: NamedAlphabet> FmIndex<A> {
+    /// Writes the index to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads an index written by [`FmIndex::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Serializes the index. Occurrence tables are rebuilt when reading.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FM_VERSION.to_le_bytes())?;
+        writer.write_all(&[A::BITS, A::NAME.len() as u8])?;
+        writer.write_all(A::NAME.as_bytes())?;
+        write_u64(&mut writer, self.len())?;
+        write_u64(&mut writer, self.sample_rate)?;
+
+        write_u64(&mut writer, self.ends.len())?;
+        for &end in &self.ends {
+            write_u64(&mut writer, end)?;
+        }
+        for &(row, pos) in &self.sentinels {
+            write_u64(&mut writer, row)?;
+            write_u64(&mut writer, pos)?;
+        }
+        for &word in self.sampled.as_words() {
+            writer.write_all(&word.to_le_bytes())?;
+        }
+        for &pos in &self.samples {
+            write_u64(&mut writer, pos)?;
+        }
+        writer.write_all(self.bwt.as_bytes())
+    }
+
+    /// Deserializes an index written by [`FmIndex::write_to`].
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not an FM-index"));
+        }
+        let mut header = [0u8; 6];
+        reader.read_exact(&mut header)?;
+        let version = u32::from_le_bytes(header[..4].try_into().unwrap());
+        if version != FM_VERSION {
+            return Err(invalid("unsupported FM-index version"));
+        }
+        let mut name = vec![0u8; header[5] as usize];
+        reader.read_exact(&mut name)?;
+        if header[4] != A::BITS || name != A::NAME.as_bytes() {
+            return Err(invalid("FM-index was built for another alphabet"));
+        }
+
+        let n = read_u64(&mut reader)?;
+        let sample_rate = read_u64(&mut reader)?;
+        if sample_rate == 0 {
+            return Err(invalid("sample rate must be positive"));
+        }
+
+        let seq_count = read_u64(&mut reader)?;
+        let ends = read_u64s(&mut reader, seq_count)?;
+        // Each sequence ends in a sentinel, the last one at the end of the text
+        let ends_valid =
+            ends.windows(2).all(|w| w[0] < w[1]) && ends.last().map_or(n == 0, |&end| end + 1 == n);
+        if !ends_valid {
+            return Err(invalid("sequence ends are out of order"));
+        }
+        let mut sentinels = Vec::new();
+        for _ in 0..seq_count {
+            sentinels.push((read_u64(&mut reader)?, read_u64(&mut reader)?));
+        }
+        if sentinels.iter().any(|&(row, pos)| row >= n || pos >= n) {
+            return Err(invalid("sentinel out of bounds"));
+        }
+        if sentinels.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(invalid("sentinel rows are not increasing"));
+        }
+        // Sentinel suffixes start each sequence
+        let mut starts: Vec<usize> = sentinels.iter().map(|&(_, pos)| pos).collect();
+        starts.sort_unstable();
+        let expected = std::iter::once(0).chain(ends.iter().map(|&e| e + 1));
+        if !starts.iter().copied().eq(expected.take(seq_count)) {
+            return Err(invalid("sentinel positions do not match the sequence ends"));
+        }
+
+        // Grows with the input, so a corrupt length cannot allocate up front
+        let mut words = Vec::new();
+        for _ in 0..n.div_ceil(64) {
+            let mut buf = [0u8; 8];
+            reader.read_exact(&mut buf)?;
+            words.push(u64::from_le_bytes(buf));
+        }
+        let sampled = BitVec::from_words(words, n);
+        let samples = read_u64s(&mut reader, sampled.count_ones())?;
+        if samples.iter().any(|&pos| pos >= n) {
+            return Err(invalid("sample out of bounds"));
+        }
+
+        let byte_len = Seq::<A>::bytes_to_store(n);
+        let mut bytes = Vec::new();
+        reader.take(byte_len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != byte_len {
+            return Err(invalid("FM-index is truncated"));
+        }
+        let mut bwt = Seq::from_bytes(&bytes);
+        bwt.trim(n);
+        if sentinels.iter().any(|&(row, _)| bwt.get_bits(row) != 0) {
+            return Err(invalid("sentinel row holds a symbol"));
+        }
+        if (0..n).any(|i| bwt.get_bits(i) >= A::SIZE) {
+            return Err(invalid("BWT holds a symbol outside the alphabet"));
+        }
+
+        let mut index = Self {
+            bwt,
+            sentinels,
+            ends,
+            counts: Vec::new(),
+            occ: Vec::new(),
+            sample_rate,
+            sampled,
+            samples,
+        };
+        index.build_tables();
+        index.check_walks()?;
+        Ok(index)
+    }
+
+    /// Checks that the LF walk from every row reaches a sentinel, so that
+    /// [`FmIndex::locate`] terminates. Each row is walked once.
+    fn check_walks(&self) -> io::Result<()> {
+        const UNSEEN: u8 = 0;
+        const ON_PATH: u8 = 1;
+        const DONE: u8 = 2;
+
+        let mut state = vec![UNSEEN; self.len()];
+        for &(row, _) in &self.sentinels {
+            state[row] = DONE;
+        }
+        let mut path = Vec::new();
+        for start in 0..self.len() {
+            let mut row = start;
+            while state[row] == UNSEEN {
+                state[row] = ON_PATH;
+                path.push(row);
+                row = self.lf(self.bwt.get_bits(row), row);
+            }
+            if state[row] == ON_PATH {
+                return Err(invalid("BWT has an LF cycle without a sentinel"));
+            }
+            for row in path.drain(..) {
+                state[row] = DONE;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn write_u64<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    writer.write_all(&(value as u64).to_le_bytes())
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<usize> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    usize::try_from(u64::from_le_bytes(buf)).map_err(|_| invalid("value does not fit in usize"))
+}
+
+fn read_u64s<R: Read>(reader: &mut R, count: usize) -> io::Result<Vec<usize>> {
+    (0..count).map(|_| read_u64(reader)).collect()
+}
@@ -0,0 +1,7 @@
+mod fm;
+mod suffix_array;
+mod wavelet;
+
+pub use fm::*;
+pub use suffix_array::*;
+pub use wavelet::*;
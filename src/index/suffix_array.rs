@@ -0,0 +1,308 @@
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use crate::alphabet::Alphabet;
+use crate::seq::Seq;
+
+/// Marks unset suffix array slots during construction.
+const EMPTY: usize = usize::MAX;
+
+/// A location in an indexed collection of sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// Index of the sequence in the collection.
+    pub seq: usize,
+    /// Offset within that sequence.
+    pub offset: usize,
+}
+
+/// Suffix array over one or more sequences.
+///
+/// The sequences are concatenated with a sentinel after each of them. Every
+/// sentinel is smaller than all symbols and sentinels of earlier sequences
+/// sort first, so no suffix comparison runs past the end of its sequence.
+#[derive(Debug, Clone)]
+pub struct SuffixArray<A: Alphabet> {
+    /// Concatenated sequences; sentinel slots hold symbol 0.
+    pub(super) text: Seq<A>,
+    /// Text position of the sentinel after each sequence.
+    pub(super) ends: Vec<usize>,
+    pub(super) sa: Vec<usize>,
+}
+
+impl<A: Alphabet> SuffixArray<A> {
+    /// Builds the suffix array of a single sequence.
+    pub fn new(seq: &Seq<A>) -> Self {
+        Self::from_seqs(std::slice::from_ref(seq))
+    }
+
+    /// Builds a generalized suffix array over a collection of sequences in
+    /// linear time (SA-IS).
+    pub fn from_seqs(seqs: &[Seq<A>]) -> Self {
+        let len = seqs.iter().map(|s| s.len() + 1).sum();
+        let mut text = Seq::new(len);
+        let mut ends = Vec::with_capacity(seqs.len());
+        let mut pos = 0;
+        for seq in seqs {
+            for i in 0..seq.len() {
+                text.init_with(pos + i, seq.get_bits(i));
+            }
+            pos += seq.len();
+            ends.push(pos);
+            pos += 1;
+        }
+
+        let mut index = Self {
+            text,
+            ends,
+            sa: Vec::new(),
+        };
+        // One bucket per sentinel plus one per symbol of the alphabet
+        let upper = index.ends.len() + A::SIZE as usize - 1;
+        index.sa = sa_is(&index.integer_text(), upper);
+        index
+    }
+
+    /// Returns the number of suffixes, sentinels included.
+    pub fn len(&self) -> usize {
+        self.sa.len()
+    }
+
+    /// Checks if the array indexes no sequences.
+    pub fn is_empty(&self) -> bool {
+        self.sa.is_empty()
+    }
+
+    /// Returns the number of indexed sequences.
+    pub fn seq_count(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns the suffix array as text positions of the concatenation.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.sa
+    }
+
+    /// Maps a position in the concatenated text to a sequence and offset.
+    pub fn position(&self, text_pos: usize) -> Position {
+        text_position(&self.ends, text_pos)
+    }
+
+    /// Returns the longest common prefix of each suffix with its predecessor
+    /// in suffix array order (Kasai et al.). The first entry is 0.
+    pub fn lcp(&self) -> Vec<usize> {
+        let text = self.integer_text();
+        let n = text.len();
+        let mut rank = vec![0; n];
+        for (i, &pos) in self.sa.iter().enumerate() {
+            rank[pos] = i;
+        }
+
+        let mut lcp = vec![0; n];
+        let mut h: usize = 0;
+        for pos in 0..n {
+            if rank[pos] == 0 {
+                h = 0;
+                continue;
+            }
+            let prev = self.sa[rank[pos] - 1];
+            // Sentinels are unique, so matches never run past one
+            while pos + h < n && prev + h < n && text[pos + h] == text[prev + h] {
+                h += 1;
+            }
+            lcp[rank[pos]] = h;
+            h = h.saturating_sub(1);
+        }
+        lcp
+    }
+
+    /// Returns the suffix array interval of suffixes starting with `pattern`.
+    pub fn range(&self, pattern: &Seq<A>) -> Range<usize> {
+        let start = self
+            .sa
+            .partition_point(|&pos| self.compare(pos, pattern) == Ordering::Less);
+        let end = self
+            .sa
+            .partition_point(|&pos| self.compare(pos, pattern) != Ordering::Greater);
+        start..end
+    }
+
+    /// Counts the occurrences of `pattern`.
+    pub fn count(&self, pattern: &Seq<A>) -> usize {
+        self.range(pattern).len()
+    }
+
+    /// Returns all occurrences of `pattern`, sorted by position.
+    pub fn locate(&self, pattern: &Seq<A>) -> Vec<Position> {
+        let mut hits: Vec<Position> = self.sa[self.range(pattern)]
+            .iter()
+            .map(|&pos| self.position(pos))
+            .collect();
+        hits.sort_unstable();
+        hits
+    }
+
+    /// Compares the suffix at `pos` against `pattern`, treating suffixes
+    /// that start with `pattern` as equal.
+    fn compare(&self, pos: usize, pattern: &Seq<A>) -> Ordering {
+        let end = self.ends[self.ends.partition_point(|&end| end < pos)];
+        for i in 0..pattern.len() {
+            if pos + i == end {
+                // The sentinel sorts below every symbol
+                return Ordering::Less;
+            }
+            match self.text.get_bits(pos + i).cmp(&pattern.get_bits(i)) {
+                Ordering::Equal => continue,
+                other => return other,
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// Expands the text to integers: sentinel `i` becomes `i`, symbols are
+    /// shifted above all sentinels.
+    fn integer_text(&self) -> Vec<usize> {
+        let sentinels = self.ends.len();
+        let mut text: Vec<usize> = (0..self.text.len())
+            .map(|i| sentinels + self.text.get_bits(i) as usize)
+            .collect();
+        for (i, &end) in self.ends.iter().enumerate() {
+            text[end] = i;
+        }
+        text
+    }
+}
+
+/// Maps a position in a concatenation with sentinels at `ends`.
+pub(super) fn text_position(ends: &[usize], text_pos: usize) -> Position {
+    let seq = ends.partition_point(|&end| end < text_pos);
+    let start = if seq == 0 { 0 } else { ends[seq - 1] + 1 };
+    Position {
+        seq,
+        offset: text_pos - start,
+    }
+}
+
+/// SA-IS (Nong, Zhang & Chan) over integers in `0..=upper`.
+///
+/// The end of `s` is treated as an implicit sentinel smaller than every
+/// symbol; `upper` sizes the buckets.
+pub(crate) fn sa_is(s: &[usize], upper: usize) -> Vec<usize> {
+    let n = s.len();
+    match n {
+        0 => return Vec::new(),
+        1 => return vec![0],
+        2 => return if s[0] < s[1] { vec![0, 1] } else { vec![1, 0] },
+        _ => {}
+    }
+
+    // S-type (true) or L-type (false) suffixes
+    let mut ls = vec![false; n];
+    for i in (0..n - 1).rev() {
+        ls[i] = if s[i] == s[i + 1] {
+            ls[i + 1]
+        } else {
+            s[i] < s[i + 1]
+        };
+    }
+
+    // Bucket starts for S-type (`sum_s`) and L-type (`sum_l`) suffixes
+    let mut sum_l = vec![0; upper + 2];
+    let mut sum_s = vec![0; upper + 2];
+    for i in 0..n {
+        if ls[i] {
+            sum_l[s[i] + 1] += 1;
+        } else {
+            sum_s[s[i]] += 1;
+        }
+    }
+    for c in 0..=upper {
+        sum_s[c] += sum_l[c];
+        sum_l[c + 1] += sum_s[c];
+    }
+
+    let induce = |lms: &[usize], sa: &mut Vec<usize>| {
+        sa.clear();
+        sa.resize(n, EMPTY);
+        let mut buf = sum_s.clone();
+        for &d in lms {
+            if d == n {
+                continue;
+            }
+            sa[buf[s[d]]] = d;
+            buf[s[d]] += 1;
+        }
+
+        buf.copy_from_slice(&sum_l);
+        sa[buf[s[n - 1]]] = n - 1;
+        buf[s[n - 1]] += 1;
+        for i in 0..n {
+            let v = sa[i];
+            if v != EMPTY && v >= 1 && !ls[v - 1] {
+                sa[buf[s[v - 1]]] = v - 1;
+                buf[s[v - 1]] += 1;
+            }
+        }
+
+        buf.copy_from_slice(&sum_l);
+        for i in (0..n).rev() {
+            let v = sa[i];
+            if v != EMPTY && v >= 1 && ls[v - 1] {
+                buf[s[v - 1] + 1] -= 1;
+                sa[buf[s[v - 1] + 1]] = v - 1;
+            }
+        }
+    };
+
+    // Leftmost S-type positions and their rank among them
+    let mut lms_map = vec![EMPTY; n + 1];
+    let mut lms = Vec::new();
+    for i in 1..n {
+        if !ls[i - 1] && ls[i] {
+            lms_map[i] = lms.len();
+            lms.push(i);
+        }
+    }
+
+    let mut sa = Vec::with_capacity(n);
+    induce(&lms, &mut sa);
+
+    let m = lms.len();
+    if m > 0 {
+        let mut sorted_lms: Vec<usize> = sa
+            .iter()
+            .copied()
+            .filter(|&v| lms_map[v] != EMPTY)
+            .collect();
+
+        // Name the LMS substrings; equal substrings share a name
+        let mut rec_s = vec![0; m];
+        let mut rec_upper = 0;
+        for i in 1..m {
+            let (mut l, mut r) = (sorted_lms[i - 1], sorted_lms[i]);
+            let end_l = lms.get(lms_map[l] + 1).copied().unwrap_or(n);
+            let end_r = lms.get(lms_map[r] + 1).copied().unwrap_or(n);
+            let mut same = end_l - l == end_r - r;
+            if same {
+                while l < end_l && s[l] == s[r] {
+                    l += 1;
+                    r += 1;
+                }
+                if l == n || s[l] != s[r] {
+                    same = false;
+                }
+            }
+            if !same {
+                rec_upper += 1;
+            }
+            rec_s[lms_map[sorted_lms[i]]] = rec_upper;
+        }
+
+        let rec_sa = sa_is(&rec_s, rec_upper);
+        for (slot, &i) in sorted_lms.iter_mut().zip(&rec_sa) {
+            *slot = lms[i];
+        }
+        induce(&sorted_lms, &mut sa);
+    }
+    sa
+}
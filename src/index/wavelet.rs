@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+
+use crate::alphabet::Alphabet;
+use crate::bitvec::{BitVec, BitVecBuilder};
+use crate::seq::Seq;
+
+/// Wavelet tree over a packed sequence, answering access, rank and select
+/// in `A::BITS` bit vector operations regardless of the alphabet size.
+///
+/// The levels are stored as a wavelet matrix: each level holds one bit of
+/// every symbol, most significant first, and stably moves the zeros to the
+/// front before the next level.
+#[derive(Debug, Clone)]
+pub struct WaveletTree<A: Alphabet> {
+    levels: Vec<BitVec>,
+    /// Number of zeros on each level.
+    zeros: Vec<usize>,
+    len: usize,
+    _marker: PhantomData<A>,
+}
+
+impl<A: Alphabet> WaveletTree<A> {
+    /// Builds the tree from a sequence.
+    pub fn new(seq: &Seq<A>) -> Self {
+        let len = seq.len();
+        let mut symbols: Vec<u8> = (0..len).map(|i| seq.get_bits(i)).collect();
+        let mut levels = Vec::with_capacity(A::BITS as usize);
+        let mut zeros = Vec::with_capacity(A::BITS as usize);
+
+        for level in 0..A::BITS {
+            let shift = A::BITS - 1 - level;
+            let mut bits = BitVecBuilder::new(len);
+            for (i, &s) in symbols.iter().enumerate() {
+                bits.set(i, s >> shift & 1 == 1);
+            }
+            let (low, high): (Vec<u8>, Vec<u8>) =
+                symbols.iter().partition(|&&s| s >> shift & 1 == 0);
+            zeros.push(low.len());
+            symbols = low;
+            symbols.extend(high);
+            levels.push(bits.build());
+        }
+
+        Self {
+            levels,
+            zeros,
+            len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the length of the indexed sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the indexed sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the packed symbol at `i`.
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn get_bits(&self, mut i: usize) -> u8 {
+        assert!(
+            i < self.len,
+            "index {i} out of bounds for length {}",
+            self.len
+        );
+        let mut symbol = 0;
+        for (bits, &zeros) in self.levels.iter().zip(&self.zeros) {
+            symbol <<= 1;
+            if bits.get(i) {
+                symbol |= 1;
+                i = zeros + bits.rank1(i);
+            } else {
+                i = bits.rank0(i);
+            }
+        }
+        symbol
+    }
+
+    /// Returns the symbol at `i`.
+    pub fn get(&self, i: usize) -> A::Elements {
+        A::ELEMENTS[self.get_bits(i) as usize]
+    }
+
+    /// Counts the occurrences of the packed symbol `c` in `0..i`.
+    ///
+    /// Panics if `i > len`.
+    pub fn rank_bits(&self, c: u8, i: usize) -> usize {
+        assert!(
+            i <= self.len,
+            "rank {i} out of bounds for length {}",
+            self.len
+        );
+        let (mut start, mut end) = (0, i);
+        for (level, (bits, &zeros)) in self.levels.iter().zip(&self.zeros).enumerate() {
+            if c >> (A::BITS as usize - 1 - level) & 1 == 1 {
+                start = zeros + bits.rank1(start);
+                end = zeros + bits.rank1(end);
+            } else {
+                start = bits.rank0(start);
+                end = bits.rank0(end);
+            }
+        }
+        end - start
+    }
+
+    /// Counts the occurrences of `c` in `0..i`.
+    pub fn rank(&self, c: A::Elements, i: usize) -> usize {
+        self.rank_bits(c.into(), i)
+    }
+
+    /// Returns the position of the `k`-th (0-based) occurrence of the packed
+    /// symbol `c`.
+    pub fn select_bits(&self, c: u8, k: usize) -> Option<usize> {
+        if k >= self.rank_bits(c, self.len) {
+            return None;
+        }
+
+        // Find where the symbol's run starts on the last level
+        let mut start = 0;
+        for (level, (bits, &zeros)) in self.levels.iter().zip(&self.zeros).enumerate() {
+            if c >> (A::BITS as usize - 1 - level) & 1 == 1 {
+                start = zeros + bits.rank1(start);
+            } else {
+                start = bits.rank0(start);
+            }
+        }
+
+        // Walk back up from the k-th entry of that run
+        let mut pos = start + k;
+        for (level, (bits, &zeros)) in self.levels.iter().zip(&self.zeros).enumerate().rev() {
+            pos = if c >> (A::BITS as usize - 1 - level) & 1 == 1 {
+                bits.select1(pos - zeros)?
+            } else {
+                bits.select0(pos)?
+            };
+        }
+        Some(pos)
+    }
+
+    /// Returns the position of the `k`-th (0-based) occurrence of `c`.
+    pub fn select(&self, c: A::Elements, k: usize) -> Option<usize> {
+        self.select_bits(c.into(), k)
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+
+use crate::alphabet::{Alphabet, NamedAlphabet};
+use crate::hash::crc32;
+use crate::seq::Seq;
+
+const MAGIC: &[u8; 8] = b"NUCSEQAR";
+
+/// Current format version.
+pub const VERSION: u32 = 1;
+
+/// Size of the fixed trailer at the end of every archive.
+const TRAILER_SIZE: usize = 8 + 4 + MAGIC.len();
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u32),
+    AlphabetMismatch {
+        expected: String,
+        found: String,
+    },
+    ChecksumMismatch,
+    Truncated,
+    NotFound(String),
+    /// Two records share a name.
+    DuplicateName(String),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArchiveError::Io(e) => write!(f, "I/O error: {e}"),
+            ArchiveError::BadMagic => write!(f, "not a sequence archive"),
+            ArchiveError::UnsupportedVersion(v) => write!(f, "unsupported archive version {v}"),
+            ArchiveError::AlphabetMismatch { expected, found } => {
+                write!(f, "archive holds {found} sequences, expected {expected}")
+            }
+            ArchiveError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            ArchiveError::Truncated => write!(f, "archive is truncated"),
+            ArchiveError::NotFound(name) => write!(f, "no sequence named {name}"),
+            ArchiveError::DuplicateName(name) => write!(f, "duplicate sequence {name}"),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<io::Error> for ArchiveError {
+    fn from(e: io::Error) -> Self {
+        ArchiveError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    length: usize,
+    offset: usize,
+    crc: u32,
+}
+
+impl Entry {
+    fn byte_len<A: Alphabet>(&self) -> usize {
+        Seq::<A>::bytes_to_store(self.length)
+    }
+}
+
+/// Streams named sequences into a versioned, checksummed archive.
+///
+/// Records keep their packed `Seq` bytes as-is, so reading a record never
+/// repacks symbols. The layout (all integers little-endian) is:
+///
+/// ```text
+/// header   magic "NUCSEQAR" | version u32 | bits u8 | name_len u8 | alphabet name
+/// data     packed bytes of every record, back to back
+/// index    count u64 | per record: name_len u32 | name | length u64 | offset u64 | crc32 u32
+/// trailer  index_offset u64 | index crc32 u32 | magic "NUCSEQAR"
+/// ```
+///
+/// Record names are unique within an archive.
+#[derive(Debug)]
+pub struct ArchiveWriter<W: Write, A: NamedAlphabet> {
+    writer: W,
+    position: usize,
+    entries: Vec<Entry>,
+    names: HashSet<String>,
+    _marker: PhantomData<A>,
+}
+
+impl<W: Write, A: NamedAlphabet> ArchiveWriter<W, A> {
+    /// Writes the archive header.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&[A::BITS, A::NAME.len() as u8])?;
+        writer.write_all(A::NAME.as_bytes())?;
+
+        Ok(Self {
+            writer,
+            position: MAGIC.len() + 4 + 2 + A::NAME.len(),
+            entries: Vec::new(),
+            names: HashSet::new(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Appends a record with its packed bytes written verbatim.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if a record of the same
+    /// name was already written.
+    pub fn write(&mut self, name: &str, seq: &Seq<A>) -> io::Result<()> {
+        if !self.names.insert(name.to_string()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("duplicate sequence {name}"),
+            ));
+        }
+        let data = seq.as_bytes();
+        self.writer.write_all(data)?;
+        self.entries.push(Entry {
+            name: name.to_string(),
+            length: seq.len(),
+            offset: self.position,
+            crc: crc32(data),
+        });
+        self.position += data.len();
+        Ok(())
+    }
+
+    /// Writes the index and trailer, returning the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut index = Vec::new();
+        index.extend_from_slice(&(self.entries.len() as u64).to_le_bytes());
+        for entry in &self.entries {
+            index.extend_from_slice(&(entry.name.len() as u32).to_le_bytes());
+            index.extend_from_slice(entry.name.as_bytes());
+            index.extend_from_slice(&(entry.length as u64).to_le_bytes());
+            index.extend_from_slice(&(entry.offset as u64).to_le_bytes());
+            index.extend_from_slice(&entry.crc.to_le_bytes());
+        }
+
+        self.writer.write_all(&index)?;
+        self.writer
+            .write_all(&(self.position as u64).to_le_bytes())?;
+        self.writer.write_all(&crc32(&index).to_le_bytes())?;
+        self.writer.write_all(MAGIC)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Lazily reads an archive from a byte buffer such as a memory map.
+///
+/// Only the index is parsed up front; record data is checksummed and copied
+/// out on access.
+#[derive(Debug)]
+pub struct ArchiveReader<B: AsRef<[u8]>, A: NamedAlphabet> {
+    bytes: B,
+    entries: Vec<Entry>,
+    lookup: HashMap<String, usize>,
+    _marker: PhantomData<A>,
+}
+
+#[cfg(feature = "mmap")]
+impl<A: NamedAlphabet> ArchiveReader<memmap2::Mmap, A> {
+    /// Memory-maps an archive file and reads its index.
+    ///
+    /// The file must not be modified while it is mapped.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ArchiveError> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapping is read-only; callers must not truncate the file while it is open
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Self::new(map)
+    }
+}
+
+impl<B: AsRef<[u8]>, A: NamedAlphabet> ArchiveReader<B, A> {
+    /// Validates the header and trailer and parses the index.
+    pub fn new(bytes: B) -> Result<Self, ArchiveError> {
+        let buf = bytes.as_ref();
+        let mut header = Cursor::new(buf, 0);
+        if header.take(MAGIC.len())? != MAGIC {
+            return Err(ArchiveError::BadMagic);
+        }
+        let version = header.u32()?;
+        if version != VERSION {
+            return Err(ArchiveError::UnsupportedVersion(version));
+        }
+        let bits = header.take(1)?[0];
+        let name_len = header.take(1)?[0] as usize;
+        let name = String::from_utf8_lossy(header.take(name_len)?).into_owned();
+        if name != A::NAME || bits != A::BITS {
+            return Err(ArchiveError::AlphabetMismatch {
+                expected: A::NAME.to_string(),
+                found: name,
+            });
+        }
+        let data_start = header.pos;
+
+        if buf.len() < data_start + TRAILER_SIZE {
+            return Err(ArchiveError::Truncated);
+        }
+        let mut trailer = Cursor::new(buf, buf.len() - TRAILER_SIZE);
+        let index_offset = trailer.u64()? as usize;
+        let index_crc = trailer.u32()?;
+        if trailer.take(MAGIC.len())? != MAGIC {
+            return Err(ArchiveError::Truncated);
+        }
+
+        let index_end = buf.len() - TRAILER_SIZE;
+        if index_offset < data_start || index_offset > index_end {
+            return Err(ArchiveError::Truncated);
+        }
+        if crc32(&buf[index_offset..index_end]) != index_crc {
+            return Err(ArchiveError::ChecksumMismatch);
+        }
+
+        let mut index = Cursor::new(&buf[..index_end], index_offset);
+        let count = index.u64()? as usize;
+        // Every entry takes at least 24 bytes; don't trust `count` for allocation
+        let capacity = count.min((index_end - index_offset) / 24);
+        let mut entries = Vec::with_capacity(capacity);
+        let mut lookup = HashMap::with_capacity(capacity);
+        for i in 0..count {
+            let name_len = index.u32()? as usize;
+            let name = String::from_utf8_lossy(index.take(name_len)?).into_owned();
+            let entry = Entry {
+                name,
+                length: index.u64()? as usize,
+                offset: index.u64()? as usize,
+                crc: index.u32()?,
+            };
+            if entry.offset < data_start
+                || entry.offset.saturating_add(entry.byte_len::<A>()) > index_offset
+            {
+                return Err(ArchiveError::Truncated);
+            }
+            if lookup.insert(entry.name.clone(), i).is_some() {
+                return Err(ArchiveError::DuplicateName(entry.name));
+            }
+            entries.push(entry);
+        }
+
+        Ok(Self {
+            bytes,
+            entries,
+            lookup,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if the archive holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the record names in file order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|e| e.name.as_str())
+    }
+
+    /// Returns the length of the named sequence without touching its data.
+    pub fn seq_len(&self, name: &str) -> Result<usize, ArchiveError> {
+        Ok(self.entries[self.position(name)?].length)
+    }
+
+    /// Returns the verified packed bytes of the named record.
+    pub fn packed(&self, name: &str) -> Result<&[u8], ArchiveError> {
+        self.packed_at(self.position(name)?)
+    }
+
+    /// Reads the named record.
+    pub fn get(&self, name: &str) -> Result<Seq<A>, ArchiveError> {
+        self.get_at(self.position(name)?)
+    }
+
+    /// Reads the record at `index` in file order.
+    pub fn get_at(&self, index: usize) -> Result<Seq<A>, ArchiveError> {
+        let mut seq = Seq::from_bytes(self.packed_at(index)?);
+        seq.trim(self.entries[index].length);
+        Ok(seq)
+    }
+
+    /// Returns an iterator over all records in file order.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(&str, Seq<A>), ArchiveError>> {
+        (0..self.len()).map(|i| Ok((self.entries[i].name.as_str(), self.get_at(i)?)))
+    }
+
+    fn position(&self, name: &str) -> Result<usize, ArchiveError> {
+        self.lookup
+            .get(name)
+            .copied()
+            .ok_or_else(|| ArchiveError::NotFound(name.to_string()))
+    }
+
+    fn packed_at(&self, index: usize) -> Result<&[u8], ArchiveError> {
+        let entry = &self.entries[index];
+        let data = &self.bytes.as_ref()[entry.offset..entry.offset + entry.byte_len::<A>()];
+        if crc32(data) != entry.crc {
+            return Err(ArchiveError::ChecksumMismatch);
+        }
+        Ok(data)
+    }
+}
+
+/// Bounds-checked little-endian reads from a byte slice.
+struct Cursor<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(buf: &'a [u8], pos: usize) -> Self {
+        Self { buf, pos }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ArchiveError> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + n)
+            .ok_or(ArchiveError::Truncated)?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, ArchiveError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, ArchiveError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
@@ -0,0 +1,552 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::alphabet::{Alphabet, Nuc5};
+use crate::io::bgzf::{read_or_eof, BgzfReader};
+use crate::io::sam::{
+    hex, Cigar, CigarOp, Flags, ReferenceSequence, SamHeader, SamRecord, TagArray, TagValue,
+};
+use crate::seq::Seq;
+
+const BAM_MAGIC: &[u8; 4] = b"BAM\x01";
+const BAI_MAGIC: &[u8; 4] = b"BAI\x01";
+/// Bin holding the per-reference metadata of a `.bai` index.
+const METADATA_BIN: u32 = 37450;
+
+// -- Sequences ---------------------------------------------------------------
+
+/// `Nuc5` codes of the 4-bit BAM codes `=ACMGRSVTWYHKDBN`; `=` and the
+/// ambiguity codes read as `N`.
+const CODE_TO_NUC5: [u8; 16] = [4, 0, 1, 4, 2, 4, 4, 4, 3, 4, 4, 4, 4, 4, 4, 4];
+
+/// Maps a byte holding two BAM bases to the `Nuc5` byte holding the same
+/// two bases, which also stores the first one in the high bits.
+const SEQ_LOOKUP: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = CODE_TO_NUC5[i >> 4] << Nuc5::BITS | CODE_TO_NUC5[i & 0xF];
+        i += 1;
+    }
+    table
+};
+
+/// Decodes `len` bases packed two per byte.
+fn decode_seq(packed: &[u8], len: usize) -> Seq<Nuc5> {
+    let bytes: Vec<u8> = packed.iter().map(|&b| SEQ_LOOKUP[b as usize]).collect();
+    let mut seq = Seq::from_bytes(&bytes);
+    seq.trim(len);
+    seq.clear_padding();
+    seq
+}
+
+// -- Binning -----------------------------------------------------------------
+
+/// Returns the smallest bin fully containing the 0-based range
+/// `start..end`.
+pub fn reg2bin(start: usize, end: usize) -> u32 {
+    let end = end.max(start + 1) - 1;
+    for (shift, offset) in [(14, 4681), (17, 585), (20, 73), (23, 9), (26, 1)] {
+        if start >> shift == end >> shift {
+            return (offset + (start >> shift)) as u32;
+        }
+    }
+    0
+}
+
+/// Returns all bins that may hold records overlapping `start..end`.
+fn reg2bins(start: usize, end: usize) -> Vec<u32> {
+    let end = end.max(start + 1) - 1;
+    let mut bins = vec![0];
+    for (shift, offset) in [(26, 1), (23, 9), (20, 73), (17, 585), (14, 4681)] {
+        bins.extend((offset + (start >> shift)..=offset + (end >> shift)).map(|b| b as u32));
+    }
+    bins
+}
+
+// -- Index -------------------------------------------------------------------
+
+/// A range of virtual offsets in a BGZF file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Chunk {
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ReferenceIndex {
+    bins: HashMap<u32, Vec<Chunk>>,
+    /// Smallest virtual offset of a record overlapping each 16 kb window.
+    intervals: Vec<u64>,
+    /// Mapped and unmapped record counts.
+    counts: Option<(u64, u64)>,
+}
+
+/// A BAM index (`.bai`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BamIndex {
+    references: Vec<ReferenceIndex>,
+    unplaced: Option<u64>,
+}
+
+impl BamIndex {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != BAI_MAGIC {
+            return Err(invalid("not a BAM index"));
+        }
+        let mut references = Vec::new();
+        for _ in 0..read_count(&mut reader)? {
+            let mut reference = ReferenceIndex::default();
+            for _ in 0..read_count(&mut reader)? {
+                let bin = read_u32(&mut reader)?;
+                let chunks = (0..read_count(&mut reader)?)
+                    .map(|_| {
+                        Ok(Chunk {
+                            start: read_u64(&mut reader)?,
+                            end: read_u64(&mut reader)?,
+                        })
+                    })
+                    .collect::<io::Result<Vec<_>>>()?;
+                if bin == METADATA_BIN {
+                    let counts = chunks.get(1).ok_or_else(|| invalid("short metadata bin"))?;
+                    reference.counts = Some((counts.start, counts.end));
+                } else {
+                    reference.bins.insert(bin, chunks);
+                }
+            }
+            reference.intervals = (0..read_count(&mut reader)?)
+                .map(|_| read_u64(&mut reader))
+                .collect::<io::Result<_>>()?;
+            references.push(reference);
+        }
+
+        // The count of unplaced records is optional
+        let mut unplaced = [0u8; 8];
+        let unplaced =
+            read_or_eof(&mut reader, &mut unplaced)?.then(|| u64::from_le_bytes(unplaced));
+        Ok(Self {
+            references,
+            unplaced,
+        })
+    }
+
+    pub fn reference_count(&self) -> usize {
+        self.references.len()
+    }
+
+    /// Returns the number of mapped and unmapped records placed on a
+    /// reference, if the index records them.
+    pub fn read_counts(&self, reference: usize) -> Option<(u64, u64)> {
+        self.references.get(reference)?.counts
+    }
+
+    /// Returns the number of records without a reference, if recorded.
+    pub fn unplaced_count(&self) -> Option<u64> {
+        self.unplaced
+    }
+
+    /// Returns the sorted, merged chunks that may hold records overlapping
+    /// `range` on a reference.
+    pub fn chunks(&self, reference: usize, range: Range<usize>) -> Vec<Chunk> {
+        let Some(index) = self.references.get(reference) else {
+            return Vec::new();
+        };
+        let min_offset = index.intervals.get(range.start >> 14).copied().unwrap_or(0);
+        let mut chunks: Vec<Chunk> = reg2bins(range.start, range.end)
+            .into_iter()
+            .filter_map(|bin| index.bins.get(&bin))
+            .flatten()
+            .filter(|chunk| chunk.end > min_offset)
+            .copied()
+            .collect();
+        chunks.sort_unstable();
+
+        let mut merged: Vec<Chunk> = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            match merged.last_mut() {
+                Some(last) if chunk.start <= last.end => last.end = last.end.max(chunk.end),
+                _ => merged.push(chunk),
+            }
+        }
+        merged
+    }
+}
+
+// -- Reader ------------------------------------------------------------------
+
+/// Reads BAM records into [`SamRecord`]s.
+pub struct BamReader<R: Read> {
+    reader: BgzfReader<R>,
+    header: SamHeader,
+    /// Reference names in the order of the binary reference list.
+    names: Vec<String>,
+}
+
+impl<R: Read> BamReader<R> {
+    /// Opens a BAM stream and parses its header.
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut reader = BgzfReader::new(reader);
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != BAM_MAGIC {
+            return Err(invalid("not a BAM file"));
+        }
+
+        let len = read_count(&mut reader)?;
+        let text = read_bytes(&mut reader, len)?;
+        // The text may be padded with NULs
+        let text = String::from_utf8_lossy(&text);
+        let mut header = SamHeader::default();
+        for (i, line) in text.trim_end_matches('\0').lines().enumerate() {
+            if !line.is_empty() {
+                header
+                    .parse_line(i + 1, line)
+                    .map_err(|e| invalid(&e.to_string()))?;
+            }
+        }
+
+        let mut references = Vec::new();
+        for _ in 0..read_count(&mut reader)? {
+            let len = read_count(&mut reader)?;
+            let name = read_bytes(&mut reader, len)?;
+            let name = c_string(&name)?;
+            let length = read_u32(&mut reader)? as usize;
+            references.push(ReferenceSequence {
+                name,
+                length,
+                fields: Vec::new(),
+            });
+        }
+        let names = references.iter().map(|r| r.name.clone()).collect();
+        // The binary list is authoritative; the text may omit @SQ lines
+        if header.references.is_empty() {
+            header.references = references;
+        }
+        Ok(Self {
+            reader,
+            header,
+            names,
+        })
+    }
+
+    pub fn header(&self) -> &SamHeader {
+        &self.header
+    }
+
+    /// Returns the virtual offset of the next record.
+    pub fn virtual_offset(&self) -> u64 {
+        self.reader.virtual_offset()
+    }
+
+    /// Reads the next record with its reference id.
+    fn read_record(&mut self) -> io::Result<Option<(i32, SamRecord)>> {
+        let mut size = [0u8; 4];
+        if !read_or_eof(&mut self.reader, &mut size)? {
+            return Ok(None);
+        }
+        let data = read_bytes(&mut self.reader, u32::from_le_bytes(size) as usize)?;
+        self.decode(&data).map(Some)
+    }
+
+    fn reference_name(&self, id: i32) -> io::Result<Option<String>> {
+        match id {
+            -1 => Ok(None),
+            id => self
+                .names
+                .get(id as usize)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| invalid("unknown reference id")),
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> io::Result<(i32, SamRecord)> {
+        let mut fields = Fields { data };
+        let reference_id = fields.i32()?;
+        let pos = fields.i32()?;
+        let name_len = fields.u8()? as usize;
+        let mapq = fields.u8()?;
+        let _bin = fields.u16()?;
+        let cigar_len = fields.u16()? as usize;
+        let flags = fields.u16()?;
+        let seq_len = fields.u32()? as usize;
+        let mate_reference_id = fields.i32()?;
+        let mate_pos = fields.i32()?;
+        let template_len = fields.i32()?;
+
+        let name = c_string(fields.take(name_len)?)?;
+        let mut cigar = (0..cigar_len)
+            .map(|_| cigar_op(fields.u32()?))
+            .collect::<io::Result<Vec<_>>>()?;
+        let seq = decode_seq(fields.take(seq_len.div_ceil(2))?, seq_len);
+        let qual = fields.take(seq_len)?;
+        let qual = (seq_len > 0 && qual[0] != 0xFF).then(|| qual.to_vec());
+
+        let mut tags = Vec::new();
+        while !fields.data.is_empty() {
+            let tag: [u8; 2] = fields.take(2)?.try_into().unwrap();
+            tags.push((tag, fields.tag_value()?));
+        }
+
+        // CIGARs too long for the record are stored as `<len>S<ref>N` plus
+        // a CG tag
+        if let [(CigarOp::SoftClip, len), (CigarOp::Skip, _)] = cigar[..] {
+            if len as usize == seq_len {
+                if let Some(i) = tags.iter().position(|(t, _)| t == b"CG") {
+                    if let (_, TagValue::Array(TagArray::UInt32(ops))) = tags.remove(i) {
+                        cigar = ops.into_iter().map(cigar_op).collect::<io::Result<_>>()?;
+                    }
+                }
+            }
+        }
+
+        let record = SamRecord {
+            name,
+            flags: Flags::from_bits(flags),
+            reference: self.reference_name(reference_id)?,
+            pos: usize::try_from(pos).ok(),
+            mapq: (mapq != 255).then_some(mapq),
+            cigar: Cigar::from(cigar),
+            mate_reference: self.reference_name(mate_reference_id)?,
+            mate_pos: usize::try_from(mate_pos).ok(),
+            template_len: template_len as i64,
+            seq,
+            qual,
+            tags,
+        };
+        Ok((reference_id, record))
+    }
+}
+
+impl<R: Read + Seek> BamReader<R> {
+    /// Returns the records overlapping the 0-based `range` of a reference,
+    /// located through an index of a coordinate-sorted file.
+    pub fn query(
+        &mut self,
+        index: &BamIndex,
+        reference: &str,
+        range: Range<usize>,
+    ) -> io::Result<Query<'_, R>> {
+        let id = self
+            .names
+            .iter()
+            .position(|name| name == reference)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown reference {reference}"),
+                )
+            })?;
+        let chunks = index.chunks(id, range.clone());
+        Ok(Query {
+            reader: self,
+            chunks,
+            chunk: 0,
+            seeked: false,
+            reference: id as i32,
+            range,
+        })
+    }
+}
+
+impl<R: Read> Iterator for BamReader<R> {
+    type Item = io::Result<SamRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record()
+            .map(|record| record.map(|(_, record)| record))
+            .transpose()
+    }
+}
+
+/// Iterator over the records of a region, see [`BamReader::query`].
+pub struct Query<'a, R: Read + Seek> {
+    reader: &'a mut BamReader<R>,
+    chunks: Vec<Chunk>,
+    chunk: usize,
+    seeked: bool,
+    reference: i32,
+    range: Range<usize>,
+}
+
+impl<R: Read + Seek> Query<'_, R> {
+    fn next_record(&mut self) -> io::Result<Option<SamRecord>> {
+        while let Some(chunk) = self.chunks.get(self.chunk) {
+            if !self.seeked {
+                self.reader.reader.seek_virtual(chunk.start)?;
+                self.seeked = true;
+            }
+            if self.reader.virtual_offset() >= chunk.end {
+                self.chunk += 1;
+                self.seeked = false;
+                continue;
+            }
+            let Some((reference, record)) = self.reader.read_record()? else {
+                break;
+            };
+            let start = record.pos.unwrap_or(0);
+            // Records are sorted, so nothing overlaps past this one
+            if reference != self.reference || start >= self.range.end {
+                break;
+            }
+            if start + record.cigar.reference_len().max(1) > self.range.start {
+                return Ok(Some(record));
+            }
+        }
+        self.chunk = self.chunks.len();
+        Ok(None)
+    }
+}
+
+impl<R: Read + Seek> Iterator for Query<'_, R> {
+    type Item = io::Result<SamRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.next_record();
+        if record.is_err() {
+            self.chunk = self.chunks.len();
+        }
+        record.transpose()
+    }
+}
+
+/// Decodes an operation stored as `len << 4 | code`.
+fn cigar_op(op: u32) -> io::Result<(CigarOp, u32)> {
+    let kind = CigarOp::ALL
+        .get((op & 0xF) as usize)
+        .ok_or_else(|| invalid("invalid CIGAR operation"))?;
+    Ok((*kind, op >> 4))
+}
+
+// -- Binary fields -----------------------------------------------------------
+
+/// Little-endian values of a record, consumed from the front.
+struct Fields<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if n > self.data.len() {
+            return Err(invalid("truncated BAM record"));
+        }
+        let (head, tail) = self.data.split_at(n);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        self.array().map(i32::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    /// Reads a NUL-terminated string.
+    fn c_str(&mut self) -> io::Result<String> {
+        let len = self
+            .data
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| invalid("unterminated BAM string"))?;
+        let text = self.take(len + 1)?;
+        c_string(text)
+    }
+
+    fn tag_value(&mut self) -> io::Result<TagValue> {
+        Ok(match self.u8()? {
+            b'A' => TagValue::Char(self.u8()? as char),
+            b'c' => TagValue::Int(self.u8()? as i8 as i64),
+            b'C' => TagValue::Int(self.u8()? as i64),
+            b's' => TagValue::Int(self.u16()? as i16 as i64),
+            b'S' => TagValue::Int(self.u16()? as i64),
+            b'i' => TagValue::Int(self.i32()? as i64),
+            b'I' => TagValue::Int(self.u32()? as i64),
+            b'f' => TagValue::Float(f32::from_bits(self.u32()?)),
+            b'Z' => TagValue::String(self.c_str()?),
+            b'H' => TagValue::Hex(hex(&self.c_str()?).ok_or_else(|| invalid("invalid hex tag"))?),
+            b'B' => {
+                let kind = self.u8()?;
+                let n = self.u32()? as usize;
+                macro_rules! values {
+                    ($variant:ident, $read:expr) => {
+                        TagArray::$variant((0..n).map(|_| $read).collect::<io::Result<_>>()?)
+                    };
+                }
+                TagValue::Array(match kind {
+                    b'c' => values!(Int8, self.u8().map(|v| v as i8)),
+                    b'C' => values!(UInt8, self.u8()),
+                    b's' => values!(Int16, self.u16().map(|v| v as i16)),
+                    b'S' => values!(UInt16, self.u16()),
+                    b'i' => values!(Int32, self.i32()),
+                    b'I' => values!(UInt32, self.u32()),
+                    b'f' => values!(Float, self.u32().map(f32::from_bits)),
+                    _ => return Err(invalid("invalid array tag type")),
+                })
+            }
+            _ => return Err(invalid("invalid tag type")),
+        })
+    }
+}
+
+/// Decodes a string up to its first NUL.
+fn c_string(bytes: &[u8]) -> io::Result<String> {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8(bytes[..end].to_vec()).map_err(|_| invalid("non-UTF-8 BAM string"))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads `len` bytes, growing the buffer as data arrives rather than
+/// trusting a length taken from the file.
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+/// Reads a non-negative `int32` count.
+fn read_count<R: Read>(reader: &mut R) -> io::Result<usize> {
+    i32::from_le_bytes(read_u32(reader)?.to_le_bytes())
+        .try_into()
+        .map_err(|_| invalid("negative count"))
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
@@ -0,0 +1,399 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::hash::crc32;
+
+/// Largest uncompressed size of a block.
+pub const BGZF_BLOCK_SIZE: usize = 65536;
+
+/// Reads BGZF files: gzip members of at most 64 KiB each, which BAM and
+/// tabix-indexed files are made of.
+///
+/// Positions are virtual offsets, the compressed offset of a block shifted
+/// left by 16 bits plus the offset within its uncompressed data.
+#[derive(Debug)]
+pub struct BgzfReader<R: Read> {
+    reader: R,
+    /// Uncompressed data of the current block.
+    block: Vec<u8>,
+    pos: usize,
+    /// Compressed offsets of the current and the next block.
+    block_offset: u64,
+    next_offset: u64,
+}
+
+impl<R: Read> BgzfReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            block: Vec::new(),
+            pos: 0,
+            block_offset: 0,
+            next_offset: 0,
+        }
+    }
+
+    /// Returns the virtual offset of the next byte.
+    pub fn virtual_offset(&self) -> u64 {
+        if self.pos == self.block.len() {
+            self.next_offset << 16
+        } else {
+            self.block_offset << 16 | self.pos as u64
+        }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Loads the next block, returning `false` at the end of the input.
+    fn read_block(&mut self) -> io::Result<bool> {
+        let mut header = [0u8; 12];
+        if !read_or_eof(&mut self.reader, &mut header)? {
+            return Ok(false);
+        }
+        if header[..4] != [31, 139, 8, 4] {
+            return Err(invalid("not a BGZF block"));
+        }
+        let extra_len = u16::from_le_bytes([header[10], header[11]]) as usize;
+        let mut extra = vec![0u8; extra_len];
+        self.reader.read_exact(&mut extra)?;
+        let block_size = block_size(&extra).ok_or_else(|| invalid("BGZF block without size"))?;
+        let data_len = block_size
+            .checked_sub(extra_len + 20)
+            .ok_or_else(|| invalid("BGZF block size too small"))?;
+
+        let mut data = vec![0u8; data_len + 8];
+        self.reader.read_exact(&mut data)?;
+        let trailer = &data[data_len..];
+        let crc = u32::from_le_bytes(trailer[..4].try_into().unwrap());
+        let size = u32::from_le_bytes(trailer[4..].try_into().unwrap()) as usize;
+        if size > BGZF_BLOCK_SIZE {
+            return Err(invalid("BGZF block too large"));
+        }
+
+        self.block.clear();
+        self.block.reserve(size);
+        inflate(&data[..data_len], &mut self.block, size)?;
+        if self.block.len() != size || crc32(&self.block) != crc {
+            return Err(invalid("BGZF block checksum mismatch"));
+        }
+        self.pos = 0;
+        self.block_offset = self.next_offset;
+        self.next_offset += block_size as u64;
+        Ok(true)
+    }
+}
+
+impl<R: Read + Seek> BgzfReader<R> {
+    /// Moves to a virtual offset.
+    pub fn seek_virtual(&mut self, offset: u64) -> io::Result<()> {
+        let (block, within) = (offset >> 16, (offset & 0xFFFF) as usize);
+        self.reader.seek(SeekFrom::Start(block))?;
+        self.next_offset = block;
+        self.block.clear();
+        self.pos = 0;
+        if self.read_block()? {
+            if within > self.block.len() {
+                return Err(invalid("virtual offset past the end of its block"));
+            }
+            self.pos = within;
+        } else if within > 0 {
+            return Err(invalid("virtual offset past the end of the file"));
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for BgzfReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Skips empty blocks such as the end-of-file marker
+        while self.pos == self.block.len() {
+            if !self.read_block()? {
+                return Ok(0);
+            }
+        }
+        let n = buf.len().min(self.block.len() - self.pos);
+        buf[..n].copy_from_slice(&self.block[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Fills `buf`, returning `false` if the input ended before the first
+/// byte.
+pub(crate) fn read_or_eof<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+/// Finds the `BC` subfield and returns the total block size it records.
+fn block_size(mut extra: &[u8]) -> Option<usize> {
+    while extra.len() >= 4 {
+        let len = u16::from_le_bytes([extra[2], extra[3]]) as usize;
+        let data = extra.get(4..4 + len)?;
+        if extra[..2] == *b"BC" && len == 2 {
+            return Some(u16::from_le_bytes([data[0], data[1]]) as usize + 1);
+        }
+        extra = &extra[4 + len..];
+    }
+    None
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+// -- Inflate -----------------------------------------------------------------
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order in which code length code lengths are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+const MAX_BITS: usize = 15;
+
+/// Decompresses a raw DEFLATE stream (RFC 1951), appending to `out`.
+///
+/// Fails as soon as `out` would grow past `limit` bytes, so corrupt input
+/// cannot inflate without bound.
+pub(crate) fn inflate(input: &[u8], out: &mut Vec<u8>, limit: usize) -> io::Result<()> {
+    let mut bits = BitReader::new(input);
+    loop {
+        let last = bits.take(1)? == 1;
+        match bits.take(2)? {
+            0 => {
+                let bytes = bits.stored(4)?;
+                let len = u16::from_le_bytes([bytes[0], bytes[1]]);
+                if len != !u16::from_le_bytes([bytes[2], bytes[3]]) {
+                    return Err(invalid("corrupt stored block length"));
+                }
+                if out.len() + len as usize > limit {
+                    return Err(too_large());
+                }
+                out.extend_from_slice(bits.stored(len as usize)?);
+            }
+            1 => {
+                let (literals, distances) = fixed_codes();
+                inflate_block(&mut bits, out, limit, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut bits)?;
+                inflate_block(&mut bits, out, limit, &literals, &distances)?;
+            }
+            _ => return Err(invalid("invalid deflate block type")),
+        }
+        if last {
+            return Ok(());
+        }
+    }
+}
+
+fn inflate_block(
+    bits: &mut BitReader,
+    out: &mut Vec<u8>,
+    limit: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> io::Result<()> {
+    loop {
+        let symbol = literals.decode(bits)? as usize;
+        match symbol {
+            0..=255 if out.len() == limit => return Err(too_large()),
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let index = symbol - 257;
+                if index >= LENGTH_BASE.len() {
+                    return Err(invalid("invalid deflate length code"));
+                }
+                let len = LENGTH_BASE[index] as usize + bits.take(LENGTH_EXTRA[index])? as usize;
+                let index = distances.decode(bits)? as usize;
+                if index >= DISTANCE_BASE.len() {
+                    return Err(invalid("invalid deflate distance code"));
+                }
+                let distance =
+                    DISTANCE_BASE[index] as usize + bits.take(DISTANCE_EXTRA[index])? as usize;
+                if distance > out.len() {
+                    return Err(invalid("deflate distance too far back"));
+                }
+                if out.len() + len > limit {
+                    return Err(too_large());
+                }
+                // Copies may overlap their own output
+                let start = out.len() - distance;
+                for i in 0..len {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+}
+
+fn too_large() -> io::Error {
+    invalid("deflate output exceeds its expected size")
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_codes(bits: &mut BitReader) -> io::Result<(Huffman, Huffman)> {
+    let literal_count = bits.take(5)? as usize + 257;
+    let distance_count = bits.take(5)? as usize + 1;
+    let code_count = bits.take(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_count] {
+        code_lengths[index] = bits.take(3)? as u8;
+    }
+    let codes = Huffman::new(&code_lengths);
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match codes.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or_else(|| invalid("deflate repeat without a length"))?;
+                (previous, 3 + bits.take(2)?)
+            }
+            17 => (0, 3 + bits.take(3)?),
+            _ => (0, 11 + bits.take(7)?),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+    if lengths.len() > literal_count + distance_count {
+        return Err(invalid("deflate code lengths overflow"));
+    }
+    if lengths[256] == 0 {
+        return Err(invalid("deflate block without end code"));
+    }
+    let (literals, distances) = lengths.split_at(literal_count);
+    Ok((Huffman::new(literals), Huffman::new(distances)))
+}
+
+/// Canonical Huffman code, decoded one bit at a time.
+struct Huffman {
+    /// Number of codes of each length.
+    counts: [u16; MAX_BITS + 1],
+    /// Symbols ordered by code.
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for len in 1..=MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; offsets[MAX_BITS + 1] as usize];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len > 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> io::Result<u16> {
+        // Codes of each length follow the last code of the previous length
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= bits.take(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid("invalid deflate Huffman code"))
+    }
+}
+
+/// Reads bits least significant first.
+struct BitReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    buffer: u32,
+    count: u8,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    #[inline]
+    fn take(&mut self, n: u8) -> io::Result<u32> {
+        while self.count < n {
+            let byte = *self
+                .input
+                .get(self.pos)
+                .ok_or_else(|| invalid("truncated deflate stream"))?;
+            self.buffer |= (byte as u32) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u32 << n) - 1);
+        self.buffer >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    /// Drops the bits left in the current byte and returns the next `n`
+    /// bytes.
+    fn stored(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        self.buffer = 0;
+        self.count = 0;
+        let bytes = self
+            .input
+            .get(self.pos..self.pos + n)
+            .ok_or_else(|| invalid("truncated deflate stream"))?;
+        self.pos += n;
+        Ok(bytes)
+    }
+}
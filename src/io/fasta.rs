@@ -1,61 +1,182 @@
-use std::io::{BufReader, BufRead, Read};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::marker::PhantomData;
+
+use crate::alphabet::Alphabet;
+use crate::seq::{Seq, SeqError};
+use crate::softmask::CaseMask;
+
+#[derive(Debug)]
+pub enum FastaError {
+    Io(io::Error),
+    Seq(SeqError),
+}
+
+impl fmt::Display for FastaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FastaError::Io(e) => write!(f, "I/O error: {e}"),
+            FastaError::Seq(e) => write!(f, "invalid sequence: {e:?}"),
+        }
+    }
+}
+
+impl std::error::Error for FastaError {}
+
+impl From<io::Error> for FastaError {
+    fn from(e: io::Error) -> Self {
+        FastaError::Io(e)
+    }
+}
+
+impl From<SeqError> for FastaError {
+    fn from(e: SeqError) -> Self {
+        FastaError::Seq(e)
+    }
+}
 
 #[derive(Debug)]
-pub struct FastaReader<R: Read> {
+pub struct FastaReader<R: Read, A: Alphabet> {
     reader: BufReader<R>,
     next_id: Option<String>,
-
+    soft_mask: bool,
+    _marker: PhantomData<A>,
 }
 
-impl<R: Read + std::fmt::Debug> FastaReader<R> {
+impl<R: Read, A: Alphabet> FastaReader<R, A> {
     pub fn new(reader: R) -> Self {
-        Self { reader: BufReader::new(reader), next_id: None }
+        Self {
+            reader: BufReader::new(reader),
+            next_id: None,
+            soft_mask: false,
+            _marker: PhantomData,
+        }
     }
-}
 
-impl<R: Read + std::fmt::Debug> Iterator for FastaReader<R> {
-    type Item = FastaDna;
+    /// Records lowercase (soft-masked) symbols in each record's `mask`.
+    pub fn with_soft_mask(mut self, enabled: bool) -> Self {
+        self.soft_mask = enabled;
+        self
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut id = self.next_id.take().unwrap_or_default();
-        let mut sequence = String::with_capacity(1000);
+    /// Skips to the first header line and returns its identifier.
+    fn first_id(&mut self, line: &mut String) -> Result<Option<String>, FastaError> {
+        loop {
+            line.clear();
+            if self.reader.read_line(line)? == 0 {
+                return Ok(None);
+            }
+            if let Some(id) = line.strip_prefix('>') {
+                return Ok(Some(id.trim().to_string()));
+            }
+        }
+    }
 
+    fn read_record(&mut self) -> Result<Option<FastaRecord<A>>, FastaError> {
         let mut line = String::new();
+        let id = match self.next_id.take() {
+            Some(id) => id,
+            None => match self.first_id(&mut line)? {
+                Some(id) => id,
+                None => return Ok(None),
+            },
+        };
+
+        let mut sequence = String::with_capacity(1000);
+        let mut mask = self.soft_mask.then(|| CaseMask::new(0));
         loop {
-            match self.reader.read_line(&mut line) {
-                Ok(0) => {
-                    if id.is_empty() {
-                        return None;
-                    } else {
-                        self.next_id = None;
-                        return Some(FastaDna { id, sequence: Dna::from_ascii(&sequence) });
-                    }
-                }
-                Ok(_) => {
-                    if let Some(l) = line.strip_prefix('>') {
-                        self.next_id = Some(l.trim().to_string());
-                        if !id.is_empty() {
-                            return Some(FastaDna { id, sequence: Dna::from_ascii(&sequence) });
-                        }
-                        id = line[1..].trim().to_string();
-                        sequence.clear();
-                    } else {
-                        sequence.push_str(line.trim());
-                    }
-                }
-                Err(_) => {
-                    return None;
-                }
-            }
             line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            if let Some(next) = line.strip_prefix('>') {
+                self.next_id = Some(next.trim().to_string());
+                break;
+            }
+            let symbols = line.trim();
+            sequence.push_str(symbols);
+            if let Some(mask) = mask.as_mut() {
+                mask.extend_from_ascii(symbols.as_bytes());
+            }
         }
+
+        Ok(Some(FastaRecord {
+            id,
+            sequence: Seq::try_from(sequence.as_str())?,
+            mask,
+        }))
     }
 }
 
-use crate::dna::Dna;
+impl<R: Read, A: Alphabet> Iterator for FastaReader<R, A> {
+    type Item = Result<FastaRecord<A>, FastaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+#[derive(Debug)]
+pub struct FastaWriter<W: Write> {
+    writer: W,
+    line_width: usize,
+}
+
+impl<W: Write> FastaWriter<W> {
+    /// Creates a writer wrapping sequences at 60 symbols per line.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            line_width: 60,
+        }
+    }
+
+    /// Sets the number of symbols per line. `0` disables wrapping.
+    pub fn with_line_width(mut self, line_width: usize) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    /// Writes a record, lowercasing soft-masked symbols if a mask is present.
+    pub fn write_record<A: Alphabet>(&mut self, record: &FastaRecord<A>) -> io::Result<()> {
+        let mut ascii = record.sequence.to_string().into_bytes();
+        if let Some(mask) = &record.mask {
+            mask.apply(&mut ascii);
+        }
+
+        writeln!(self.writer, ">{}", record.id)?;
+        let width = if self.line_width == 0 {
+            ascii.len().max(1)
+        } else {
+            self.line_width
+        };
+        for line in ascii.chunks(width) {
+            self.writer.write_all(line)?;
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
 
 #[derive(Debug, PartialEq)]
-pub struct FastaDna {
+pub struct FastaRecord<A: Alphabet> {
     pub id: String,
-    pub sequence: Dna,
+    pub sequence: Seq<A>,
+    pub mask: Option<CaseMask>,
+}
+
+impl<A: Alphabet> FastaRecord<A> {
+    pub fn new(id: String, sequence: Seq<A>) -> Self {
+        Self {
+            id,
+            sequence,
+            mask: None,
+        }
+    }
 }
@@ -0,0 +1,478 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+
+use crate::align::Cigar;
+use crate::alphabet::{Nuc5, Strand};
+use crate::graph::{orientation, Containment, Gfa, GfaError, Handle, Link, Path, Segment};
+use crate::seq::Seq;
+
+/// GFA dialects for writing. Reading accepts both, even mixed, since their
+/// record types can be told apart line by line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GfaVersion {
+    /// `S`, `L`, `C` and `P` records.
+    V1,
+    /// `S` records with lengths, `E` edges and `O` ordered groups.
+    V2,
+}
+
+/// Reads a whole graph.
+///
+/// Records may refer to segments defined further down. GFA 2 edges that
+/// are neither dovetails nor containments, and records without a graph
+/// counterpart (walks, fragments, gaps, unordered groups), are skipped.
+pub fn read<R: BufRead>(reader: R) -> Result<Gfa, GfaError> {
+    // Segments go first so that other records can be resolved
+    let mut gfa = Gfa::new();
+    let mut records = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if line.starts_with("S\t") {
+            let fields: Vec<&str> = line.split('\t').collect();
+            gfa.add_segment(parse_segment(number + 1, &fields)?)?;
+        } else if matches!(
+            line.as_bytes(),
+            [b'L' | b'C' | b'P' | b'E' | b'O', b'\t', ..]
+        ) {
+            records.push((number + 1, line.to_string()));
+        }
+    }
+    for (number, line) in records {
+        let fields: Vec<&str> = line.split('\t').collect();
+        add_record(&mut gfa, number, &fields)?;
+    }
+    Ok(gfa)
+}
+
+/// Writes a graph in the given dialect.
+///
+/// GFA 2 edges need the overlap lengths, so writing them fails with
+/// [`io::ErrorKind::InvalidInput`] if an overlap is not a CIGAR string.
+pub fn write<W: Write>(mut writer: W, gfa: &Gfa, version: GfaVersion) -> io::Result<()> {
+    let segments = gfa.segments();
+    let name = |handle: Handle| &segments[handle.node].name;
+    match version {
+        GfaVersion::V1 => {
+            writeln!(writer, "H\tVN:Z:1.0")?;
+            for segment in segments {
+                write!(writer, "S\t{}\t{}", segment.name, sequence(segment))?;
+                if segment.seq.is_none() && !segment.tags.iter().any(|t| t.starts_with("LN:")) {
+                    write!(writer, "\tLN:i:{}", segment.length)?;
+                }
+                write_tags(&mut writer, &segment.tags)?;
+            }
+            for link in gfa.links() {
+                write!(
+                    writer,
+                    "L\t{}\t{}\t{}\t{}\t{}",
+                    name(link.from),
+                    orientation(link.from.strand),
+                    name(link.to),
+                    orientation(link.to.strand),
+                    link.overlap
+                )?;
+                write_tags(&mut writer, &link.tags)?;
+            }
+            for c in gfa.containments() {
+                writeln!(
+                    writer,
+                    "C\t{}\t{}\t{}\t{}\t{}\t{}",
+                    name(c.container),
+                    orientation(c.container.strand),
+                    name(c.contained),
+                    orientation(c.contained.strand),
+                    c.pos,
+                    c.overlap
+                )?;
+            }
+            for path in gfa.paths() {
+                let steps: Vec<String> = path.steps.iter().map(|&s| step(gfa, s)).collect();
+                let overlaps = if path.overlaps.is_empty() {
+                    "*".to_string()
+                } else {
+                    path.overlaps.join(",")
+                };
+                writeln!(writer, "P\t{}\t{}\t{overlaps}", path.name, steps.join(","))?;
+            }
+        }
+        GfaVersion::V2 => {
+            writeln!(writer, "H\tVN:Z:2.0")?;
+            for segment in segments {
+                write!(
+                    writer,
+                    "S\t{}\t{}\t{}",
+                    segment.name,
+                    segment.length,
+                    sequence(segment)
+                )?;
+                write_tags(&mut writer, &segment.tags)?;
+            }
+            for link in gfa.links() {
+                let cigar = parse_overlap(&link.overlap)?;
+                let first = oriented_range(gfa, link.from, cigar.reference_len(), true);
+                let second = oriented_range(gfa, link.to, cigar.query_len(), false);
+                write_edge(
+                    &mut writer,
+                    gfa,
+                    link.from,
+                    link.to,
+                    first,
+                    second,
+                    &link.overlap,
+                )?;
+                write_tags(&mut writer, &link.tags)?;
+            }
+            for c in gfa.containments() {
+                let contained_len = parse_overlap(&c.overlap)?.query_len();
+                let span = if contained_len == 0 {
+                    segments[c.contained.node].length
+                } else {
+                    contained_len
+                };
+                let first = (c.pos, c.pos + span);
+                let second = (0, segments[c.contained.node].length);
+                write_edge(
+                    &mut writer,
+                    gfa,
+                    c.container,
+                    c.contained,
+                    first,
+                    second,
+                    &c.overlap,
+                )?;
+                writeln!(writer)?;
+            }
+            for path in gfa.paths() {
+                let steps: Vec<String> = path.steps.iter().map(|&s| step(gfa, s)).collect();
+                writeln!(writer, "O\t{}\t{}", path.name, steps.join(" "))?;
+            }
+        }
+    }
+    writer.flush()
+}
+
+// -- Reading -----------------------------------------------------------------
+
+fn parse_error(line: usize, message: &str) -> GfaError {
+    GfaError::Parse {
+        line,
+        message: message.to_string(),
+    }
+}
+
+fn field<'a>(fields: &[&'a str], index: usize, line: usize) -> Result<&'a str, GfaError> {
+    fields
+        .get(index)
+        .copied()
+        .ok_or_else(|| parse_error(line, &format!("{} record is missing fields", fields[0])))
+}
+
+fn number(text: &str, line: usize) -> Result<usize, GfaError> {
+    text.parse()
+        .map_err(|_| parse_error(line, &format!("invalid number {text}")))
+}
+
+/// Parses either `S name seq` (GFA 1) or `S name length seq` (GFA 2).
+fn parse_segment(line: usize, fields: &[&str]) -> Result<Segment, GfaError> {
+    let name = field(fields, 1, line)?.to_string();
+    let second = field(fields, 2, line)?;
+    // Sequences never consist of digits, so a number marks GFA 2
+    let (length, text, tags) = if second.bytes().all(|b| b.is_ascii_digit()) {
+        (
+            Some(number(second, line)?),
+            field(fields, 3, line)?,
+            &fields[4..],
+        )
+    } else {
+        (None, second, &fields[3..])
+    };
+    let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+
+    let seq = match text {
+        "*" => None,
+        _ => Some(Seq::<Nuc5>::try_from(text)?),
+    };
+    let length = match (length, &seq) {
+        (Some(length), _) => length,
+        (None, Some(seq)) => seq.len(),
+        (None, None) => tags
+            .iter()
+            .find_map(|t| t.strip_prefix("LN:i:"))
+            .map_or(Ok(0), |n| number(n, line))?,
+    };
+    Ok(Segment {
+        name,
+        seq,
+        length,
+        tags,
+    })
+}
+
+fn lookup(gfa: &Gfa, name: &str) -> Result<usize, GfaError> {
+    gfa.segment_id(name)
+        .ok_or_else(|| GfaError::UnknownSegment(name.to_string()))
+}
+
+fn parse_strand(text: &str, line: usize) -> Result<Strand, GfaError> {
+    match text {
+        "+" => Ok(Strand::Forward),
+        "-" => Ok(Strand::Reverse),
+        _ => Err(parse_error(line, &format!("invalid orientation {text}"))),
+    }
+}
+
+/// Resolves a segment name and a separate orientation field.
+fn handle(gfa: &Gfa, name: &str, strand: &str, line: usize) -> Result<Handle, GfaError> {
+    Ok(Handle::new(lookup(gfa, name)?, parse_strand(strand, line)?))
+}
+
+/// Resolves a reference with a trailing orientation, like `seg+`.
+fn reference(gfa: &Gfa, text: &str, line: usize) -> Result<Handle, GfaError> {
+    if text.is_empty() || !text.is_char_boundary(text.len() - 1) {
+        return Err(parse_error(
+            line,
+            &format!("invalid segment reference {text}"),
+        ));
+    }
+    let (name, strand) = text.split_at(text.len() - 1);
+    handle(gfa, name, strand, line)
+}
+
+fn is_tag(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 5 && bytes[2] == b':' && bytes[4] == b':'
+}
+
+fn add_record(gfa: &mut Gfa, line: usize, fields: &[&str]) -> Result<(), GfaError> {
+    match fields[0] {
+        "L" => {
+            let from = handle(gfa, field(fields, 1, line)?, field(fields, 2, line)?, line)?;
+            let to = handle(gfa, field(fields, 3, line)?, field(fields, 4, line)?, line)?;
+            let overlap = overlap(fields.get(5).copied().unwrap_or("*"), line)?;
+            let mut link = Link::new(from, to, overlap);
+            link.tags = fields.iter().skip(6).map(|t| t.to_string()).collect();
+            gfa.add_link(link);
+        }
+        "C" => {
+            let container = handle(gfa, field(fields, 1, line)?, field(fields, 2, line)?, line)?;
+            let contained = handle(gfa, field(fields, 3, line)?, field(fields, 4, line)?, line)?;
+            gfa.add_containment(Containment {
+                container,
+                contained,
+                pos: number(field(fields, 5, line)?, line)?,
+                overlap: overlap(fields.get(6).copied().unwrap_or("*"), line)?.to_string(),
+            });
+        }
+        "P" => {
+            let steps = field(fields, 2, line)?
+                .split(',')
+                .map(|s| reference(gfa, s, line))
+                .collect::<Result<_, _>>()?;
+            let overlaps = match fields.get(3).copied().unwrap_or("*") {
+                "*" => Vec::new(),
+                text => text
+                    .split(',')
+                    .map(|o| overlap(o, line).map(str::to_string))
+                    .collect::<Result<_, _>>()?,
+            };
+            gfa.add_path(Path {
+                name: field(fields, 1, line)?.to_string(),
+                steps,
+                overlaps,
+            });
+        }
+        "O" => {
+            let steps = fields[2..]
+                .iter()
+                .filter(|f| !is_tag(f))
+                .flat_map(|f| f.split(' '))
+                .filter(|s| !s.is_empty())
+                .map(|s| reference(gfa, s, line))
+                .collect::<Result<_, _>>()?;
+            gfa.add_path(Path {
+                name: field(fields, 1, line)?.to_string(),
+                steps,
+                overlaps: Vec::new(),
+            });
+        }
+        "E" => add_edge(gfa, line, fields)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Checks that an overlap is a CIGAR string or `*`.
+fn overlap(text: &str, line: usize) -> Result<&str, GfaError> {
+    match text.parse::<Cigar>() {
+        Ok(_) => Ok(text),
+        Err(e) => Err(parse_error(line, &format!("invalid overlap {text}: {e}"))),
+    }
+}
+
+/// Parses an overlap for writing.
+fn parse_overlap(text: &str) -> io::Result<Cigar> {
+    text.parse().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid overlap {text}: {e}"),
+        )
+    })
+}
+
+/// Classifies a GFA 2 edge as a dovetail link or a containment.
+fn add_edge(gfa: &mut Gfa, line: usize, fields: &[&str]) -> Result<(), GfaError> {
+    let first = reference(gfa, field(fields, 2, line)?, line)?;
+    let second = reference(gfa, field(fields, 3, line)?, line)?;
+    let position = |i: usize| -> Result<usize, GfaError> {
+        number(field(fields, i, line)?.trim_end_matches('$'), line)
+    };
+    let (beg1, end1, beg2, end2) = (position(4)?, position(5)?, position(6)?, position(7)?);
+    let alignment = fields.get(8).copied().unwrap_or("*");
+    let traced = alignment == "*" || alignment.bytes().all(|b| b.is_ascii_digit() || b == b',');
+    if !traced {
+        overlap(alignment, line)?;
+    }
+    let tags: Vec<String> = fields.iter().skip(9).map(|t| t.to_string()).collect();
+
+    // Without a CIGAR (`*` or a trace) the positions give the overlap
+    let (span1, span2) = (end1.saturating_sub(beg1), end2.saturating_sub(beg2));
+    let overlap = |swapped: bool| match (traced, swapped) {
+        (true, false) => span_overlap(span1, span2),
+        (true, true) => span_overlap(span2, span1),
+        (false, false) => alignment.to_string(),
+        (false, true) => swap_sides(alignment),
+    };
+
+    // Positions are on the forward strands; map them onto the oriented ones.
+    // Full coverage of either segment makes a containment.
+    let (len1, len2) = (
+        gfa.segments()[first.node].length,
+        gfa.segments()[second.node].length,
+    );
+    let (b1, e1) = oriented(first.strand, beg1, end1, len1);
+    let (b2, e2) = oriented(second.strand, beg2, end2, len2);
+
+    if beg2 == 0 && end2 == len2 {
+        gfa.add_containment(Containment {
+            container: first,
+            contained: second,
+            pos: beg1,
+            overlap: overlap(false),
+        });
+    } else if beg1 == 0 && end1 == len1 {
+        gfa.add_containment(Containment {
+            container: second,
+            contained: first,
+            pos: beg2,
+            overlap: overlap(true),
+        });
+    } else if e1 == len1 && b2 == 0 {
+        let mut link = Link::new(first, second, &overlap(false));
+        link.tags = tags;
+        gfa.add_link(link);
+    } else if b1 == 0 && e2 == len2 {
+        let mut link = Link::new(second, first, &overlap(true));
+        link.tags = tags;
+        gfa.add_link(link);
+    }
+    Ok(())
+}
+
+/// Builds an overlap CIGAR covering `first` bases of the first sequence
+/// and `second` of the second one.
+fn span_overlap(first: usize, second: usize) -> String {
+    let shared = first.min(second);
+    match first.cmp(&second) {
+        Ordering::Equal => format!("{shared}M"),
+        Ordering::Greater => format!("{shared}M{}D", first - second),
+        Ordering::Less => format!("{shared}M{}I", second - first),
+    }
+}
+
+/// Maps a forward-strand range onto a strand.
+fn oriented(strand: Strand, beg: usize, end: usize, len: usize) -> (usize, usize) {
+    match strand {
+        Strand::Forward => (beg, end),
+        Strand::Reverse => (len - end.min(len), len - beg.min(len)),
+    }
+}
+
+/// Swaps the roles of the two sequences in a CIGAR string.
+fn swap_sides(cigar: &str) -> String {
+    cigar
+        .chars()
+        .map(|c| match c {
+            'I' => 'D',
+            'D' => 'I',
+            c => c,
+        })
+        .collect()
+}
+
+// -- Writing -----------------------------------------------------------------
+
+fn sequence(segment: &Segment) -> String {
+    segment
+        .seq
+        .as_ref()
+        .map_or_else(|| "*".to_string(), |s| s.to_string())
+}
+
+fn step(gfa: &Gfa, handle: Handle) -> String {
+    format!(
+        "{}{}",
+        gfa.segments()[handle.node].name,
+        orientation(handle.strand)
+    )
+}
+
+/// Terminates a record, appending its tags.
+fn write_tags<W: Write>(writer: &mut W, tags: &[String]) -> io::Result<()> {
+    for tag in tags {
+        write!(writer, "\t{tag}")?;
+    }
+    writeln!(writer)
+}
+
+/// Returns the forward-strand range of `overlap` bases at the end (`at_end`)
+/// or start of a handle's oriented sequence.
+fn oriented_range(gfa: &Gfa, handle: Handle, overlap: usize, at_end: bool) -> (usize, usize) {
+    let len = gfa.segments()[handle.node].length;
+    let overlap = overlap.min(len);
+    let (beg, end) = if at_end {
+        (len - overlap, len)
+    } else {
+        (0, overlap)
+    };
+    oriented(handle.strand, beg, end, len)
+}
+
+/// Writes an `E` line without its terminator.
+fn write_edge<W: Write>(
+    writer: &mut W,
+    gfa: &Gfa,
+    first: Handle,
+    second: Handle,
+    (beg1, end1): (usize, usize),
+    (beg2, end2): (usize, usize),
+    alignment: &str,
+) -> io::Result<()> {
+    let position = |pos: usize, handle: Handle| {
+        if pos == gfa.segments()[handle.node].length {
+            format!("{pos}$")
+        } else {
+            pos.to_string()
+        }
+    };
+    write!(
+        writer,
+        "E\t*\t{}\t{}\t{}\t{}\t{}\t{}\t{alignment}",
+        step(gfa, first),
+        step(gfa, second),
+        position(beg1, first),
+        position(end1, first),
+        position(beg2, second),
+        position(end2, second)
+    )
+}
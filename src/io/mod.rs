@@ -1,2 +1,9 @@
-// pub mod fasta;
+pub mod archive;
+pub mod bam;
+pub mod bgzf;
+pub mod fasta;
 // pub mod fastq;
+pub mod gfa;
+pub mod msa;
+pub mod sam;
+pub mod twobit;
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use crate::alphabet::Alphabet;
+use crate::msa::{GappedSeq, Msa, MsaError};
+
+/// Columns per line (FASTA) or per block (Clustal) when writing.
+const LINE_WIDTH: usize = 60;
+
+/// Text formats for multiple sequence alignments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MsaFormat {
+    /// FASTA records with `-` gaps, all of the same length.
+    Fasta,
+    /// Clustal W/Omega `.aln` blocks.
+    Clustal,
+    /// Stockholm 1.0; markup lines are skipped.
+    Stockholm,
+    /// Relaxed PHYLIP (whitespace separated names), sequential or interleaved.
+    Phylip,
+}
+
+/// Reads a whole alignment in the given format.
+pub fn read<R: BufRead, A: Alphabet>(reader: R, format: MsaFormat) -> Result<Msa<A>, MsaError> {
+    let rows = match format {
+        MsaFormat::Fasta => read_fasta(reader)?,
+        MsaFormat::Clustal => read_blocks(reader, is_clustal_header, "CLUSTAL")?,
+        MsaFormat::Stockholm => read_blocks(reader, is_stockholm_header, "# STOCKHOLM")?,
+        MsaFormat::Phylip => read_phylip(reader)?,
+    };
+
+    let mut msa = Msa::new();
+    for (name, text) in rows {
+        msa.push(&name, text.parse::<GappedSeq<A>>()?)?;
+    }
+    Ok(msa)
+}
+
+/// Writes an alignment in the given format.
+pub fn write<W: Write, A: Alphabet>(
+    mut writer: W,
+    msa: &Msa<A>,
+    format: MsaFormat,
+) -> io::Result<()> {
+    let rows: Vec<String> = msa.rows().iter().map(|r| r.to_string()).collect();
+    let names = msa.names();
+    let width = names.iter().map(|n| n.len()).max().unwrap_or(0);
+
+    match format {
+        MsaFormat::Fasta => {
+            for (name, row) in names.iter().zip(&rows) {
+                writeln!(writer, ">{name}")?;
+                for chunk in row.as_bytes().chunks(LINE_WIDTH) {
+                    writer.write_all(chunk)?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+        }
+        MsaFormat::Clustal => {
+            writeln!(writer, "CLUSTAL W multiple sequence alignment\n")?;
+            let conserved = conservation_line(msa);
+            for start in (0..msa.columns()).step_by(LINE_WIDTH) {
+                let end = (start + LINE_WIDTH).min(msa.columns());
+                writeln!(writer)?;
+                for (name, row) in names.iter().zip(&rows) {
+                    writeln!(writer, "{name:<w$}{}", &row[start..end], w = width + 4)?;
+                }
+                writeln!(writer, "{:w$}{}", "", &conserved[start..end], w = width + 4)?;
+            }
+        }
+        MsaFormat::Stockholm => {
+            writeln!(writer, "# STOCKHOLM 1.0\n")?;
+            for (name, row) in names.iter().zip(&rows) {
+                writeln!(writer, "{name:<w$}{row}", w = width + 2)?;
+            }
+            writeln!(writer, "//")?;
+        }
+        MsaFormat::Phylip => {
+            writeln!(writer, "{} {}", msa.len(), msa.columns())?;
+            for (name, row) in names.iter().zip(&rows) {
+                writeln!(writer, "{name:<w$}{row}", w = width.max(8) + 2)?;
+            }
+        }
+    }
+    writer.flush()
+}
+
+/// Marks columns where every row has the same residue with `*`.
+fn conservation_line<A: Alphabet>(msa: &Msa<A>) -> String {
+    msa.iter_columns()
+        .map(|column| {
+            let first = column.first().copied().flatten();
+            let conserved = first.is_some() && column.iter().all(|&s| s == first);
+            if conserved {
+                '*'
+            } else {
+                ' '
+            }
+        })
+        .collect()
+}
+
+fn parse_error(line: usize, message: &str) -> MsaError {
+    MsaError::Parse {
+        line,
+        message: message.to_string(),
+    }
+}
+
+fn read_fasta<R: BufRead>(reader: R) -> Result<Vec<(String, String)>, MsaError> {
+    let mut rows: Vec<(String, String)> = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if let Some(id) = line.strip_prefix('>') {
+            rows.push((id.trim().to_string(), String::new()));
+        } else if let Some((_, text)) = rows.last_mut() {
+            text.push_str(line.trim());
+        } else if !line.trim().is_empty() {
+            return Err(parse_error(number + 1, "sequence before the first header"));
+        }
+    }
+    Ok(rows)
+}
+
+fn is_clustal_header(line: &str) -> bool {
+    ["CLUSTAL", "MUSCLE", "PROBCONS"]
+        .iter()
+        .any(|p| line.starts_with(p))
+}
+
+fn is_stockholm_header(line: &str) -> bool {
+    line.starts_with("# STOCKHOLM")
+}
+
+/// Reads the `name sequence` blocks shared by Clustal and Stockholm.
+///
+/// Rows may be split over several blocks; lines starting with whitespace
+/// (Clustal conservation marks) or `#` (Stockholm markup) are skipped, and
+/// `//` ends the alignment.
+fn read_blocks<R: BufRead>(
+    reader: R,
+    is_header: fn(&str) -> bool,
+    expected: &str,
+) -> Result<Vec<(String, String)>, MsaError> {
+    let mut rows: Vec<(String, String)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut seen_header = false;
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if !seen_header {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !is_header(&line) {
+                return Err(parse_error(
+                    number + 1,
+                    &format!("expected {expected} header"),
+                ));
+            }
+            seen_header = true;
+            continue;
+        }
+        if line.trim() == "//" {
+            break;
+        }
+        if line.trim().is_empty() || line.starts_with(char::is_whitespace) || line.starts_with('#')
+        {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let name = fields.next().unwrap();
+        let text = fields
+            .next()
+            .ok_or_else(|| parse_error(number + 1, "row without sequence"))?;
+        let row = *index.entry(name.to_string()).or_insert_with(|| {
+            rows.push((name.to_string(), String::new()));
+            rows.len() - 1
+        });
+        rows[row].1.push_str(text);
+    }
+
+    if !seen_header {
+        return Err(parse_error(1, &format!("expected {expected} header")));
+    }
+    Ok(rows)
+}
+
+/// Reads relaxed PHYLIP: a `rows columns` header, then the rows in order,
+/// each starting with its name.
+///
+/// Sequential files wrap every row over as many lines as it needs;
+/// interleaved ones give a line per row, then blocks continuing the rows in
+/// order. Sequential is tried first.
+fn read_phylip<R: BufRead>(reader: R) -> Result<Vec<(String, String)>, MsaError> {
+    let mut lines = reader.lines().enumerate();
+    let (count, columns) = loop {
+        let Some((number, line)) = lines.next() else {
+            return Err(parse_error(1, "missing PHYLIP header"));
+        };
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<usize> = line
+            .split_whitespace()
+            .map(|f| f.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| parse_error(number + 1, "invalid PHYLIP header"))?;
+        match fields[..] {
+            [count, columns] => break (count, columns),
+            _ => return Err(parse_error(number + 1, "invalid PHYLIP header")),
+        }
+    };
+
+    let mut body = Vec::new();
+    let mut last_line = 0;
+    for (number, line) in lines {
+        let line = line?;
+        last_line = number + 1;
+        if !line.trim().is_empty() {
+            body.push((number + 1, line));
+        }
+    }
+    if let Some(rows) = sequential_phylip(&body, count, columns) {
+        return Ok(rows);
+    }
+
+    let mut rows: Vec<(String, String)> = Vec::new();
+    let mut next = 0;
+    for (number, line) in &body {
+        if rows.len() < count {
+            let mut fields = line.split_whitespace();
+            let name = fields.next().unwrap().to_string();
+            rows.push((name, fields.collect()));
+        } else if count > 0 {
+            rows[next % count].1.extend(line.split_whitespace());
+            next += 1;
+        } else {
+            return Err(parse_error(*number, "sequence data for an empty alignment"));
+        }
+    }
+
+    if rows.len() != count {
+        let message = format!("expected {count} rows, found {}", rows.len());
+        return Err(parse_error(last_line, &message));
+    }
+    if let Some((name, text)) = rows.iter().find(|(_, text)| text.len() != columns) {
+        return Err(MsaError::LengthMismatch {
+            name: name.clone(),
+            expected: columns,
+            found: text.len(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Reads rows that each start on a new line and continue until they hold
+/// `columns` residues, or returns `None` if the lines do not fit that
+/// layout.
+fn sequential_phylip(
+    lines: &[(usize, String)],
+    count: usize,
+    columns: usize,
+) -> Option<Vec<(String, String)>> {
+    let mut rows: Vec<(String, String)> = Vec::with_capacity(count.min(lines.len()));
+    for (_, line) in lines {
+        let mut fields = line.split_whitespace();
+        let open = rows.last().is_some_and(|(_, text)| text.len() < columns);
+        if open {
+            rows.last_mut()?.1.extend(fields);
+        } else if rows.len() == count {
+            return None;
+        } else {
+            let name = fields.next()?.to_string();
+            rows.push((name, fields.collect()));
+        }
+        if rows.last()?.1.len() > columns {
+            return None;
+        }
+    }
+    let complete = rows.len() == count && rows.iter().all(|(_, text)| text.len() == columns);
+    complete.then_some(rows)
+}
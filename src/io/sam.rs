@@ -0,0 +1,555 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read};
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take, take_while, take_while_m_n};
+use nom::character::complete::{anychar, char, i64 as integer, one_of};
+use nom::combinator::{all_consuming, map, map_opt, map_res, rest};
+use nom::multi::many0;
+use nom::number::complete::float;
+use nom::sequence::{pair, preceded, separated_pair};
+use nom::IResult;
+
+pub use crate::align::{Cigar, CigarOp};
+use crate::alphabet::{Alphabet, Nuc5, IUPAC_TO_MASK};
+use crate::seq::Seq;
+
+#[derive(Debug)]
+pub enum SamError {
+    Io(io::Error),
+    /// Malformed input on the given (1-based) line.
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for SamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SamError::Io(e) => write!(f, "I/O error: {e}"),
+            SamError::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SamError {}
+
+impl From<io::Error> for SamError {
+    fn from(e: io::Error) -> Self {
+        SamError::Io(e)
+    }
+}
+
+// -- Flags -------------------------------------------------------------------
+
+/// The bitwise FLAG field of an alignment record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Flags(u16);
+
+impl Flags {
+    /// Template has multiple segments.
+    pub const PAIRED: Flags = Flags(0x1);
+    /// Every segment is properly aligned.
+    pub const PROPER_PAIR: Flags = Flags(0x2);
+    pub const UNMAPPED: Flags = Flags(0x4);
+    pub const MATE_UNMAPPED: Flags = Flags(0x8);
+    /// SEQ is reverse complemented.
+    pub const REVERSE: Flags = Flags(0x10);
+    pub const MATE_REVERSE: Flags = Flags(0x20);
+    pub const FIRST_IN_PAIR: Flags = Flags(0x40);
+    pub const LAST_IN_PAIR: Flags = Flags(0x80);
+    pub const SECONDARY: Flags = Flags(0x100);
+    pub const QC_FAIL: Flags = Flags(0x200);
+    pub const DUPLICATE: Flags = Flags(0x400);
+    pub const SUPPLEMENTARY: Flags = Flags(0x800);
+
+    pub fn from_bits(bits: u16) -> Self {
+        Flags(bits)
+    }
+
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Checks if all flags of `other` are set.
+    pub fn contains(self, other: Flags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn insert(&mut self, other: Flags) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Flags) {
+        self.0 &= !other.0;
+    }
+
+    pub fn is_unmapped(self) -> bool {
+        self.contains(Flags::UNMAPPED)
+    }
+
+    pub fn is_reverse(self) -> bool {
+        self.contains(Flags::REVERSE)
+    }
+
+    /// Checks if the record is neither secondary nor supplementary.
+    pub fn is_primary(self) -> bool {
+        self.0 & (Flags::SECONDARY.0 | Flags::SUPPLEMENTARY.0) == 0
+    }
+}
+
+impl BitOr for Flags {
+    type Output = Flags;
+
+    fn bitor(self, rhs: Flags) -> Flags {
+        Flags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Flags {
+    fn bitor_assign(&mut self, rhs: Flags) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Flags {
+    type Output = Flags;
+
+    fn bitand(self, rhs: Flags) -> Flags {
+        Flags(self.0 & rhs.0)
+    }
+}
+
+// -- Optional fields ---------------------------------------------------------
+
+/// Value of an optional `TAG:TYPE:VALUE` field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagValue {
+    /// Printable character (`A`).
+    Char(char),
+    /// Signed integer (`i`, and the sized BAM integer types).
+    Int(i64),
+    /// Single-precision float (`f`).
+    Float(f32),
+    /// Printable string (`Z`).
+    String(String),
+    /// Byte array in hex (`H`).
+    Hex(Vec<u8>),
+    /// Numeric array (`B`).
+    Array(TagArray),
+}
+
+/// Typed numeric array of a `B` field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagArray {
+    Int8(Vec<i8>),
+    UInt8(Vec<u8>),
+    Int16(Vec<i16>),
+    UInt16(Vec<u16>),
+    Int32(Vec<i32>),
+    UInt32(Vec<u32>),
+    Float(Vec<f32>),
+}
+
+// -- Header ------------------------------------------------------------------
+
+/// A reference sequence line (`@SQ`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceSequence {
+    /// `SN`
+    pub name: String,
+    /// `LN`
+    pub length: usize,
+    /// Remaining `TAG:value` pairs.
+    pub fields: Vec<(String, String)>,
+}
+
+/// A read group line (`@RG`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadGroup {
+    /// `ID`
+    pub id: String,
+    /// Remaining `TAG:value` pairs.
+    pub fields: Vec<(String, String)>,
+}
+
+impl ReadGroup {
+    /// Returns a field by tag, such as `SM` (sample) or `PL` (platform).
+    pub fn get(&self, tag: &str) -> Option<&str> {
+        field_value(&self.fields, tag)
+    }
+}
+
+/// A program line (`@PG`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// `ID`
+    pub id: String,
+    /// Remaining `TAG:value` pairs.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Program {
+    /// Returns a field by tag, such as `PN` (name), `VN` (version), `CL`
+    /// (command line) or `PP` (previous program).
+    pub fn get(&self, tag: &str) -> Option<&str> {
+        field_value(&self.fields, tag)
+    }
+}
+
+fn field_value<'a>(fields: &'a [(String, String)], tag: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(t, _)| t == tag)
+        .map(|(_, value)| value.as_str())
+}
+
+/// The `@`-prefixed header of a SAM file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SamHeader {
+    /// Format version (`@HD VN`).
+    pub version: Option<String>,
+    /// Sorting order (`@HD SO`).
+    pub sort_order: Option<String>,
+    pub references: Vec<ReferenceSequence>,
+    pub read_groups: Vec<ReadGroup>,
+    pub programs: Vec<Program>,
+    /// Text of the `@CO` lines.
+    pub comments: Vec<String>,
+}
+
+impl SamHeader {
+    /// Returns the index of a reference sequence by name.
+    pub fn reference_id(&self, name: &str) -> Option<usize> {
+        self.references.iter().position(|r| r.name == name)
+    }
+
+    pub fn read_group(&self, id: &str) -> Option<&ReadGroup> {
+        self.read_groups.iter().find(|rg| rg.id == id)
+    }
+
+    /// Adds one header line, `@` included.
+    pub(crate) fn parse_line(&mut self, line: usize, text: &str) -> Result<(), SamError> {
+        if let Some(comment) = text.strip_prefix("@CO\t") {
+            self.comments.push(comment.to_string());
+            return Ok(());
+        }
+        let (kind, mut fields) = all_consuming(header_line)(text)
+            .map(|(_, parsed)| parsed)
+            .map_err(|_| parse_error(line, "malformed header line"))?;
+        let mut take_field = |tag: &str| {
+            let index = fields.iter().position(|(t, _)| t == tag)?;
+            Some(fields.remove(index).1)
+        };
+
+        match kind {
+            "HD" => {
+                self.version = take_field("VN");
+                self.sort_order = take_field("SO");
+            }
+            "SQ" => {
+                let name = take_field("SN").ok_or_else(|| parse_error(line, "@SQ without SN"))?;
+                let length = take_field("LN")
+                    .and_then(|n| n.parse().ok())
+                    .ok_or_else(|| parse_error(line, "@SQ without a valid LN"))?;
+                self.references.push(ReferenceSequence {
+                    name,
+                    length,
+                    fields,
+                });
+            }
+            "RG" => {
+                let id = take_field("ID").ok_or_else(|| parse_error(line, "@RG without ID"))?;
+                self.read_groups.push(ReadGroup { id, fields });
+            }
+            "PG" => {
+                let id = take_field("ID").ok_or_else(|| parse_error(line, "@PG without ID"))?;
+                self.programs.push(Program { id, fields });
+            }
+            // Unknown record types are allowed and skipped
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+// -- Records -----------------------------------------------------------------
+
+/// One alignment line.
+///
+/// Positions are 0-based; unavailable fields (`*`, `0`, `255`) are `None`
+/// or empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamRecord {
+    /// `QNAME`
+    pub name: String,
+    pub flags: Flags,
+    /// `RNAME`
+    pub reference: Option<String>,
+    /// `POS`, the leftmost mapped reference position.
+    pub pos: Option<usize>,
+    /// `MAPQ`
+    pub mapq: Option<u8>,
+    pub cigar: Cigar,
+    /// `RNEXT`, with `=` resolved to `RNAME`.
+    pub mate_reference: Option<String>,
+    /// `PNEXT`
+    pub mate_pos: Option<usize>,
+    /// `TLEN`
+    pub template_len: i64,
+    /// `SEQ`; IUPAC ambiguity codes and `=` read as `N`.
+    pub seq: Seq<Nuc5>,
+    /// Phred quality scores, decoded from `QUAL`.
+    pub qual: Option<Vec<u8>>,
+    pub tags: Vec<([u8; 2], TagValue)>,
+}
+
+impl SamRecord {
+    /// Returns an optional field by tag, such as `b"NM"`.
+    pub fn tag(&self, name: &[u8; 2]) -> Option<&TagValue> {
+        self.tags.iter().find(|(t, _)| t == name).map(|(_, v)| v)
+    }
+
+    fn parse(line: usize, text: &str) -> Result<Self, SamError> {
+        let fields: Vec<&str> = text.split('\t').collect();
+        if fields.len() < 11 {
+            return Err(parse_error(line, "expected 11 mandatory fields"));
+        }
+        let number = |index: usize, name: &str| -> Result<i64, SamError> {
+            fields[index]
+                .parse()
+                .map_err(|_| parse_error(line, &format!("invalid {name}")))
+        };
+        let optional = |text: &str| (text != "*").then(|| text.to_string());
+
+        let flags =
+            u16::try_from(number(1, "FLAG")?).map_err(|_| parse_error(line, "invalid FLAG"))?;
+        let pos = position(number(3, "POS")?);
+        let mapq =
+            u8::try_from(number(4, "MAPQ")?).map_err(|_| parse_error(line, "invalid MAPQ"))?;
+        let cigar = fields[5]
+            .parse::<Cigar>()
+            .map_err(|e| parse_error(line, &e.to_string()))?;
+        let reference = optional(fields[2]);
+        let mate_reference = match fields[6] {
+            "=" => reference.clone(),
+            text => optional(text),
+        };
+        let seq = match fields[9] {
+            "*" => Seq::new(0),
+            text => decode_seq(text.as_bytes()).ok_or_else(|| parse_error(line, "invalid SEQ"))?,
+        };
+        let qual = match fields[10] {
+            "*" => None,
+            text if text.len() != seq.len() => {
+                return Err(parse_error(line, "QUAL and SEQ differ in length"))
+            }
+            text => Some(
+                decode_qual(text.as_bytes()).ok_or_else(|| parse_error(line, "invalid QUAL"))?,
+            ),
+        };
+        let tags = fields[11..]
+            .iter()
+            .map(|field| {
+                all_consuming(tag_field)(field)
+                    .map(|(_, tag)| tag)
+                    .map_err(|_| parse_error(line, &format!("invalid optional field {field}")))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(SamRecord {
+            name: fields[0].to_string(),
+            flags: Flags(flags),
+            reference,
+            pos,
+            mapq: (mapq != 255).then_some(mapq),
+            cigar,
+            mate_reference,
+            mate_pos: position(number(7, "PNEXT")?),
+            template_len: number(8, "TLEN")?,
+            seq,
+            qual,
+            tags,
+        })
+    }
+}
+
+/// Maps a 1-based position, 0 when unavailable, to a 0-based one.
+fn position(pos: i64) -> Option<usize> {
+    (pos > 0).then(|| pos as usize - 1)
+}
+
+/// Packs bases into `Nuc5`, reading other IUPAC codes and `=` as `N`.
+pub(crate) fn decode_seq(ascii: &[u8]) -> Option<Seq<Nuc5>> {
+    let mut seq = Seq::new(ascii.len());
+    for (i, &b) in ascii.iter().enumerate() {
+        let bits = match Nuc5::BYTE_TO_BITS[b as usize] {
+            0xFF if b == b'=' || IUPAC_TO_MASK[b as usize] != 0 => 4,
+            0xFF => return None,
+            bits => bits,
+        };
+        seq.init_with(i, bits);
+    }
+    Some(seq)
+}
+
+/// Decodes Phred+33 quality characters.
+fn decode_qual(ascii: &[u8]) -> Option<Vec<u8>> {
+    ascii
+        .iter()
+        .map(|&b| (b'!'..=b'~').contains(&b).then(|| b - 33))
+        .collect()
+}
+
+fn parse_error(line: usize, message: &str) -> SamError {
+    SamError::Parse {
+        line,
+        message: message.to_string(),
+    }
+}
+
+// -- Grammar -----------------------------------------------------------------
+
+/// `TG:value` pairs of a header line.
+type HeaderFields = Vec<(String, String)>;
+
+/// `@XX` followed by tab-separated `TG:value` fields.
+fn header_line(input: &str) -> IResult<&str, (&str, HeaderFields)> {
+    let two = |input| take_while_m_n(2, 2, |c: char| c.is_ascii_alphanumeric())(input);
+    pair(
+        preceded(char('@'), take(2usize)),
+        many0(preceded(
+            char('\t'),
+            map(
+                separated_pair(two, char(':'), take_while(|c| c != '\t')),
+                |(tag, value): (&str, &str)| (tag.to_string(), value.to_string()),
+            ),
+        )),
+    )(input)
+}
+
+/// A `TAG:TYPE:VALUE` field.
+fn tag_field(input: &str) -> IResult<&str, ([u8; 2], TagValue)> {
+    let (input, name) = take_while_m_n(2, 2, |c: char| c.is_ascii_alphanumeric())(input)?;
+    let (input, kind) = preceded(char(':'), one_of("AifZHB"))(input)?;
+    let (input, _) = char(':')(input)?;
+    let (input, value) = match kind {
+        'A' => map(anychar, TagValue::Char)(input)?,
+        'i' => map(integer, TagValue::Int)(input)?,
+        'f' => map(float, TagValue::Float)(input)?,
+        'Z' => map(rest, |s: &str| TagValue::String(s.to_string()))(input)?,
+        'H' => map_opt(rest, |s: &str| hex(s).map(TagValue::Hex))(input)?,
+        _ => map(tag_array, TagValue::Array)(input)?,
+    };
+    let bytes = name.as_bytes();
+    Ok((input, ([bytes[0], bytes[1]], value)))
+}
+
+/// The `<subtype>,v1,v2,...` payload of a `B` field.
+fn tag_array(input: &str) -> IResult<&str, TagArray> {
+    let values = |input| many0(preceded(char(','), integer))(input);
+    alt((
+        preceded(tag("c"), map_res(values, |v| ints(v).map(TagArray::Int8))),
+        preceded(tag("C"), map_res(values, |v| ints(v).map(TagArray::UInt8))),
+        preceded(tag("s"), map_res(values, |v| ints(v).map(TagArray::Int16))),
+        preceded(tag("S"), map_res(values, |v| ints(v).map(TagArray::UInt16))),
+        preceded(tag("i"), map_res(values, |v| ints(v).map(TagArray::Int32))),
+        preceded(tag("I"), map_res(values, |v| ints(v).map(TagArray::UInt32))),
+        preceded(
+            tag("f"),
+            map(many0(preceded(char(','), float)), TagArray::Float),
+        ),
+    ))(input)
+}
+
+fn ints<T: TryFrom<i64>>(values: Vec<i64>) -> Result<Vec<T>, T::Error> {
+    values.into_iter().map(T::try_from).collect()
+}
+
+pub(crate) fn hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// -- Reader ------------------------------------------------------------------
+
+/// Reads the header and then the alignment records of a SAM file.
+#[derive(Debug)]
+pub struct SamReader<R: Read> {
+    reader: BufReader<R>,
+    header: SamHeader,
+    /// First record line, read while looking for the end of the header.
+    pending: Option<String>,
+    line: usize,
+}
+
+impl<R: Read> SamReader<R> {
+    /// Creates a reader, parsing the header up front.
+    pub fn new(reader: R) -> Result<Self, SamError> {
+        let mut sam = Self {
+            reader: BufReader::new(reader),
+            header: SamHeader::default(),
+            pending: None,
+            line: 0,
+        };
+        while let Some(text) = sam.next_line()? {
+            if text.starts_with('@') {
+                sam.header.parse_line(sam.line, &text)?;
+            } else {
+                sam.pending = Some(text);
+                break;
+            }
+        }
+        Ok(sam)
+    }
+
+    pub fn header(&self) -> &SamHeader {
+        &self.header
+    }
+
+    /// Reads the next non-empty line without its terminator.
+    fn next_line(&mut self) -> Result<Option<String>, SamError> {
+        let mut text = String::new();
+        loop {
+            text.clear();
+            if self.reader.read_line(&mut text)? == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            let trimmed = text.trim_end_matches(['\n', '\r']);
+            if !trimmed.is_empty() {
+                return Ok(Some(trimmed.to_string()));
+            }
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<SamRecord>, SamError> {
+        let text = match self.pending.take() {
+            Some(text) => text,
+            None => match self.next_line()? {
+                Some(text) => text,
+                None => return Ok(None),
+            },
+        };
+        SamRecord::parse(self.line, &text).map(Some)
+    }
+}
+
+impl<R: Read> Iterator for SamReader<R> {
+    type Item = Result<SamRecord, SamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
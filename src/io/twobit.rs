@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::ops::{Range, RangeBounds};
+use std::path::Path;
+
+use crate::alphabet::{Nuc4, Nuc5};
+use crate::io::fasta::FastaRecord;
+use crate::masked::MaskedSeq;
+use crate::seq::{checked_range, Seq};
+use crate::softmask::CaseMask;
+
+/// Magic number at the start of every `.2bit` file.
+const SIGNATURE: u32 = 0x1A41_2743;
+
+/// Lookup table: packed `Nuc4` byte → packed 2bit byte.
+///
+/// 2bit uses T=0, C=1, A=2, G=3 with the first base in the highest bits,
+/// so converting is a per-symbol relabelling of the same layout.
+const NUC4_TO_TWOBIT: [u8; 256] = {
+    const SYMBOL: [u8; 4] = [2, 1, 3, 0];
+    let mut lut = [0u8; 256];
+    let mut b = 0;
+    while b < 256 {
+        let mut out = 0u8;
+        let mut shift = 0;
+        while shift < 8 {
+            out |= SYMBOL[(b >> shift) & 0b11] << shift;
+            shift += 2;
+        }
+        lut[b] = out;
+        b += 1;
+    }
+    lut
+};
+
+/// Lookup table: packed 2bit byte → packed `Nuc4` byte.
+const TWOBIT_TO_NUC4: [u8; 256] = {
+    const SYMBOL: [u8; 4] = [3, 1, 0, 2];
+    let mut lut = [0u8; 256];
+    let mut b = 0;
+    while b < 256 {
+        let mut out = 0u8;
+        let mut shift = 0;
+        while shift < 8 {
+            out |= SYMBOL[(b >> shift) & 0b11] << shift;
+            shift += 2;
+        }
+        lut[b] = out;
+        b += 1;
+    }
+    lut
+};
+
+/// A named sequence stored in a `.2bit` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwoBitRecord {
+    pub name: String,
+    pub seq: MaskedSeq,
+    pub mask: Option<CaseMask>,
+}
+
+impl From<FastaRecord<Nuc5>> for TwoBitRecord {
+    fn from(record: FastaRecord<Nuc5>) -> Self {
+        Self {
+            name: record.id,
+            seq: MaskedSeq::from(&record.sequence),
+            mask: record.mask,
+        }
+    }
+}
+
+/// Per-sequence tables read from a record header.
+struct RecordHeader {
+    dna_size: usize,
+    n_blocks: Vec<Range<usize>>,
+    mask_blocks: Vec<Range<usize>>,
+    data_offset: u64,
+}
+
+/// Random-access reader for UCSC `.2bit` files.
+#[derive(Debug)]
+pub struct TwoBitReader<R: Read + Seek> {
+    reader: R,
+    big_endian: bool,
+    names: Vec<String>,
+    offsets: HashMap<String, u64>,
+}
+
+impl TwoBitReader<BufReader<File>> {
+    /// Opens a `.2bit` file and reads its index.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> TwoBitReader<R> {
+    /// Reads the file header and sequence index.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        reader.seek(SeekFrom::Start(0))?;
+        let big_endian = match read_u32(&mut reader, false)? {
+            SIGNATURE => false,
+            s if s.swap_bytes() == SIGNATURE => true,
+            _ => return Err(invalid("not a 2bit file")),
+        };
+        let version = read_u32(&mut reader, big_endian)?;
+        if version > 1 {
+            return Err(invalid("unsupported 2bit version"));
+        }
+        let count = read_u32(&mut reader, big_endian)? as usize;
+        read_u32(&mut reader, big_endian)?; // reserved
+
+        // The count comes from the file, so the index grows as it is read
+        let mut names = Vec::new();
+        let mut offsets = HashMap::new();
+        for _ in 0..count {
+            let mut name_size = [0u8; 1];
+            reader.read_exact(&mut name_size)?;
+            let mut name = vec![0u8; name_size[0] as usize];
+            reader.read_exact(&mut name)?;
+            let name =
+                String::from_utf8(name).map_err(|_| invalid("sequence name is not UTF-8"))?;
+            let offset = if version == 1 {
+                read_u64(&mut reader, big_endian)?
+            } else {
+                read_u32(&mut reader, big_endian)? as u64
+            };
+            offsets.insert(name.clone(), offset);
+            names.push(name);
+        }
+
+        Ok(Self {
+            reader,
+            big_endian,
+            names,
+            offsets,
+        })
+    }
+
+    /// Returns the sequence names in file order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the length of the named sequence.
+    pub fn seq_len(&mut self, name: &str) -> io::Result<usize> {
+        let offset = self.offset(name)?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+        Ok(read_u32(&mut self.reader, self.big_endian)? as usize)
+    }
+
+    /// Reads the whole named sequence.
+    pub fn read(&mut self, name: &str) -> io::Result<TwoBitRecord> {
+        self.fetch(name, ..)
+    }
+
+    /// Reads `range` of the named sequence, seeking straight to the packed bases.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the range is out of
+    /// bounds.
+    pub fn fetch<B: RangeBounds<usize>>(
+        &mut self,
+        name: &str,
+        range: B,
+    ) -> io::Result<TwoBitRecord> {
+        let header = self.record_header(name)?;
+        let Range { start, end } = checked_range(range, header.dna_size).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "range out of bounds for {name} of length {}",
+                    header.dna_size
+                ),
+            )
+        })?;
+
+        let first_byte = start / 4;
+        let last_byte = end.div_ceil(4);
+        self.reader
+            .seek(SeekFrom::Start(header.data_offset + first_byte as u64))?;
+        let mut packed = vec![0u8; last_byte - first_byte];
+        self.reader.read_exact(&mut packed)?;
+        for byte in packed.iter_mut() {
+            *byte = TWOBIT_TO_NUC4[*byte as usize];
+        }
+
+        let skip = start - first_byte * 4;
+        let bases = Seq::<Nuc4>::from_bytes(&packed).slice(skip..skip + end - start);
+        let n_runs = clip(&header.n_blocks, start, end);
+        let mask = CaseMask::from_intervals(end - start, clip(&header.mask_blocks, start, end));
+
+        Ok(TwoBitRecord {
+            name: name.to_string(),
+            seq: MaskedSeq::new(bases, n_runs),
+            mask: Some(mask),
+        })
+    }
+
+    /// Reads every sequence in file order.
+    pub fn records(&mut self) -> io::Result<Vec<TwoBitRecord>> {
+        let names = self.names.clone();
+        names.iter().map(|name| self.read(name)).collect()
+    }
+
+    fn offset(&self, name: &str) -> io::Result<u64> {
+        self.offsets.get(name).copied().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("no sequence named {name}"))
+        })
+    }
+
+    fn record_header(&mut self, name: &str) -> io::Result<RecordHeader> {
+        let offset = self.offset(name)?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let dna_size = read_u32(&mut self.reader, self.big_endian)? as usize;
+        let n_blocks = self.read_blocks()?;
+        let mask_blocks = self.read_blocks()?;
+        read_u32(&mut self.reader, self.big_endian)?; // reserved
+        let data_offset = self.reader.stream_position()?;
+
+        Ok(RecordHeader {
+            dna_size,
+            n_blocks,
+            mask_blocks,
+            data_offset,
+        })
+    }
+
+    /// Reads a block count followed by the block starts and sizes.
+    fn read_blocks(&mut self) -> io::Result<Vec<Range<usize>>> {
+        let count = read_u32(&mut self.reader, self.big_endian)? as usize;
+        let mut starts = Vec::new();
+        for _ in 0..count {
+            starts.push(read_u32(&mut self.reader, self.big_endian)? as usize);
+        }
+        let mut blocks = Vec::with_capacity(starts.len());
+        for start in starts {
+            let size = read_u32(&mut self.reader, self.big_endian)? as usize;
+            blocks.push(start..start + size);
+        }
+        Ok(blocks)
+    }
+}
+
+/// Writer for UCSC `.2bit` files.
+///
+/// Files are written little-endian. Version 1 (64-bit offsets) is used only
+/// when the file would not fit in 4 GiB.
+#[derive(Debug)]
+pub struct TwoBitWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> TwoBitWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes a complete `.2bit` file and returns the underlying writer.
+    pub fn write(mut self, records: &[TwoBitRecord]) -> io::Result<W> {
+        let index_size = |offset_size: u64| -> u64 {
+            records
+                .iter()
+                .map(|r| 1 + r.name.len() as u64 + offset_size)
+                .sum()
+        };
+        let mask_blocks: Vec<Vec<Range<usize>>> = records
+            .iter()
+            .map(|r| r.mask.iter().flat_map(|m| m.intervals()).collect())
+            .collect();
+        let record_sizes: Vec<u64> = records
+            .iter()
+            .zip(&mask_blocks)
+            .map(|(r, masks)| {
+                16 + 8 * (r.seq.n_runs().len() + masks.len()) as u64
+                    + r.seq.len().div_ceil(4) as u64
+            })
+            .collect();
+
+        let mut version = 0;
+        let mut offset = 16 + index_size(4);
+        if offset + record_sizes.iter().sum::<u64>() > u32::MAX as u64 {
+            version = 1;
+            offset = 16 + index_size(8);
+        }
+
+        write_u32(&mut self.writer, SIGNATURE)?;
+        write_u32(&mut self.writer, version)?;
+        write_u32(&mut self.writer, to_u32(records.len())?)?;
+        write_u32(&mut self.writer, 0)?;
+
+        for (record, size) in records.iter().zip(&record_sizes) {
+            let name = record.name.as_bytes();
+            if name.len() > u8::MAX as usize {
+                return Err(invalid("sequence name longer than 255 bytes"));
+            }
+            self.writer.write_all(&[name.len() as u8])?;
+            self.writer.write_all(name)?;
+            if version == 1 {
+                self.writer.write_all(&offset.to_le_bytes())?;
+            } else {
+                write_u32(&mut self.writer, offset as u32)?;
+            }
+            offset += size;
+        }
+
+        for (record, masks) in records.iter().zip(&mask_blocks) {
+            write_u32(&mut self.writer, to_u32(record.seq.len())?)?;
+            self.write_blocks(record.seq.n_runs())?;
+            self.write_blocks(masks)?;
+            write_u32(&mut self.writer, 0)?;
+
+            let packed: Vec<u8> = record
+                .seq
+                .as_nuc4()
+                .as_bytes()
+                .iter()
+                .map(|&b| NUC4_TO_TWOBIT[b as usize])
+                .collect();
+            self.writer.write_all(&packed)?;
+        }
+
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_blocks(&mut self, blocks: &[Range<usize>]) -> io::Result<()> {
+        write_u32(&mut self.writer, to_u32(blocks.len())?)?;
+        for block in blocks {
+            write_u32(&mut self.writer, to_u32(block.start)?)?;
+        }
+        for block in blocks {
+            write_u32(&mut self.writer, to_u32(block.len())?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Intersects sorted blocks with `start..end` and shifts them to start at 0.
+fn clip(blocks: &[Range<usize>], start: usize, end: usize) -> Vec<Range<usize>> {
+    blocks
+        .iter()
+        .filter(|b| b.start < end && b.end > start)
+        .map(|b| b.start.max(start) - start..b.end.min(end) - start)
+        .collect()
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn to_u32(value: usize) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| invalid("value does not fit in 32 bits"))
+}
+
+fn read_u32<R: Read>(reader: &mut R, big_endian: bool) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(if big_endian {
+        u32::from_be_bytes(buf)
+    } else {
+        u32::from_le_bytes(buf)
+    })
+}
+
+fn read_u64<R: Read>(reader: &mut R, big_endian: bool) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(if big_endian {
+        u64::from_be_bytes(buf)
+    } else {
+        u64::from_le_bytes(buf)
+    })
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
@@ -0,0 +1,201 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::alphabet::Nuc4;
+use crate::hash::{nthash_multi, NtHash};
+use crate::seq::Seq;
+
+const MAGIC: &[u8; 8] = b"NUCBLOOM";
+
+/// Current Bloom filter format version.
+pub const BLOOM_VERSION: u32 = 1;
+
+/// Bloom filter over canonical k-mers.
+///
+/// Every k-mer sets `hashes` bits, derived from its rolling ntHash value,
+/// so a read is inserted or queried in a single pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KmerBloomFilter {
+    k: usize,
+    hashes: u32,
+    /// Number of bits.
+    size: u64,
+    words: Vec<u64>,
+}
+
+impl KmerBloomFilter {
+    /// Creates an empty filter with `size` bits and `hashes` hashes per
+    /// k-mer.
+    ///
+    /// Panics if any argument is 0.
+    pub fn new(k: usize, size: u64, hashes: u32) -> Self {
+        assert!(
+            k > 0 && size > 0 && hashes > 0,
+            "bloom filter parameters must be positive"
+        );
+        Self {
+            k,
+            hashes,
+            size,
+            words: vec![0; size.div_ceil(64) as usize],
+        }
+    }
+
+    /// Creates a filter sized for `items` k-mers at the given false
+    /// positive rate.
+    pub fn with_false_positive_rate(k: usize, items: u64, rate: f64) -> Self {
+        assert!(
+            rate > 0.0 && rate < 1.0,
+            "false positive rate must be in (0, 1)"
+        );
+        let ln2 = std::f64::consts::LN_2;
+        let size = (-(items.max(1) as f64) * rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let hashes = ((size as f64 / items.max(1) as f64) * ln2).round().max(1.0) as u32;
+        Self::new(k, size.max(64), hashes)
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of hashes per k-mer.
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Returns the number of bits.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> u64 {
+        self.words.iter().map(|w| w.count_ones() as u64).sum()
+    }
+
+    /// Estimates the false positive rate from the fraction of set bits.
+    pub fn false_positive_rate(&self) -> f64 {
+        (self.count_ones() as f64 / self.size as f64).powi(self.hashes as i32)
+    }
+
+    /// Inserts a k-mer by its ntHash value.
+    pub fn insert_hash(&mut self, hash: u64) {
+        for i in 0..self.hashes {
+            let bit = nthash_multi(hash, i, self.k) % self.size;
+            self.words[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Checks a k-mer by its ntHash value.
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        (0..self.hashes).all(|i| {
+            let bit = nthash_multi(hash, i, self.k) % self.size;
+            self.words[(bit / 64) as usize] >> (bit % 64) & 1 == 1
+        })
+    }
+
+    /// Inserts every k-mer of a read.
+    pub fn insert(&mut self, seq: &Seq<Nuc4>) {
+        for hash in NtHash::new(seq, self.k) {
+            self.insert_hash(hash);
+        }
+    }
+
+    /// Checks a single k-mer in either orientation.
+    ///
+    /// Panics if the sequence length differs from `k`.
+    pub fn contains(&self, kmer: &Seq<Nuc4>) -> bool {
+        assert_eq!(kmer.len(), self.k, "expected a {}-mer", self.k);
+        self.contains_hash(NtHash::new(kmer, self.k).next().unwrap())
+    }
+
+    /// Returns the fraction of a read's k-mers found in the filter, 0 for
+    /// reads shorter than `k`.
+    pub fn query(&self, seq: &Seq<Nuc4>) -> f64 {
+        hit_fraction(NtHash::new(seq, self.k).map(|hash| self.contains_hash(hash)))
+    }
+
+    /// Adds all k-mers of a filter with the same parameters.
+    ///
+    /// Panics if the parameters differ.
+    pub fn union(&mut self, other: &KmerBloomFilter) {
+        assert!(
+            self.k == other.k && self.hashes == other.hashes && self.size == other.size,
+            "bloom filters have different parameters"
+        );
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    // -- Serialization -------------------------------------------------------
+
+    /// Writes the filter to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a filter written by [`KmerBloomFilter::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&BLOOM_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.k as u32).to_le_bytes())?;
+        writer.write_all(&self.hashes.to_le_bytes())?;
+        writer.write_all(&self.size.to_le_bytes())?;
+        for word in &self.words {
+            writer.write_all(&word.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 28];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid("not a bloom filter"));
+        }
+        let field = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+        if field(8) != BLOOM_VERSION {
+            return Err(invalid("unsupported bloom filter version"));
+        }
+        let (k, hashes) = (field(12) as usize, field(16));
+        let size = u64::from_le_bytes(header[20..28].try_into().unwrap());
+        if k == 0 || hashes == 0 || size == 0 {
+            return Err(invalid("bloom filter parameters must be positive"));
+        }
+
+        let mut words = Vec::new();
+        let mut buf = [0u8; 8];
+        for _ in 0..size.div_ceil(64) {
+            reader.read_exact(&mut buf)?;
+            words.push(u64::from_le_bytes(buf));
+        }
+        Ok(Self {
+            k,
+            hashes,
+            size,
+            words,
+        })
+    }
+}
+
+/// Returns the fraction of `true` values, 0 when empty.
+pub(crate) fn hit_fraction(hits: impl Iterator<Item = bool>) -> f64 {
+    let (found, total) = hits.fold((0usize, 0usize), |(f, t), hit| (f + hit as usize, t + 1));
+    if total == 0 {
+        0.0
+    } else {
+        found as f64 / total as f64
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
@@ -0,0 +1,191 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use super::bloom::hit_fraction;
+use crate::alphabet::Nuc4;
+use crate::hash::{nthash_multi, NtHash};
+use crate::seq::Seq;
+
+const MAGIC: &[u8; 8] = b"NUCCMSKT";
+
+/// Current count-min sketch format version.
+pub const COUNT_MIN_VERSION: u32 = 1;
+
+/// Count-min sketch of canonical k-mer abundances.
+///
+/// `depth` rows of `width` saturating counters; row `i` is indexed by the
+/// `i`-th hash derived from the k-mer's ntHash value. Estimates never
+/// undercount.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountMinSketch {
+    k: usize,
+    width: usize,
+    depth: u32,
+    counters: Vec<u32>,
+}
+
+impl CountMinSketch {
+    /// Creates an empty sketch.
+    ///
+    /// Panics if any argument is 0.
+    pub fn new(k: usize, width: usize, depth: u32) -> Self {
+        assert!(
+            k > 0 && width > 0 && depth > 0,
+            "sketch parameters must be positive"
+        );
+        Self {
+            k,
+            width,
+            depth,
+            counters: vec![0; width * depth as usize],
+        }
+    }
+
+    /// Creates a sketch whose estimates exceed the true count by at most
+    /// `epsilon` times the total count with probability `1 - delta`.
+    pub fn with_error(k: usize, epsilon: f64, delta: f64) -> Self {
+        assert!(
+            epsilon > 0.0 && delta > 0.0 && delta < 1.0,
+            "invalid error bounds"
+        );
+        let width = (std::f64::consts::E / epsilon).ceil() as usize;
+        let depth = (1.0 / delta).ln().ceil().max(1.0) as u32;
+        Self::new(k, width, depth)
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Adds one occurrence of a k-mer by its ntHash value.
+    pub fn insert_hash(&mut self, hash: u64) {
+        for row in 0..self.depth {
+            let cell = self.cell(hash, row);
+            self.counters[cell] = self.counters[cell].saturating_add(1);
+        }
+    }
+
+    /// Estimates the count of a k-mer by its ntHash value.
+    pub fn estimate_hash(&self, hash: u64) -> u32 {
+        (0..self.depth)
+            .map(|row| self.counters[self.cell(hash, row)])
+            .min()
+            .unwrap()
+    }
+
+    /// Adds every k-mer of a read.
+    pub fn insert(&mut self, seq: &Seq<Nuc4>) {
+        for hash in NtHash::new(seq, self.k) {
+            self.insert_hash(hash);
+        }
+    }
+
+    /// Estimates the count of a single k-mer in either orientation.
+    ///
+    /// Panics if the sequence length differs from `k`.
+    pub fn estimate(&self, kmer: &Seq<Nuc4>) -> u32 {
+        assert_eq!(kmer.len(), self.k, "expected a {}-mer", self.k);
+        self.estimate_hash(NtHash::new(kmer, self.k).next().unwrap())
+    }
+
+    /// Returns the estimated count of every k-mer of a read, in order.
+    pub fn estimates(&self, seq: &Seq<Nuc4>) -> Vec<u32> {
+        NtHash::new(seq, self.k)
+            .map(|hash| self.estimate_hash(hash))
+            .collect()
+    }
+
+    /// Returns the fraction of a read's k-mers estimated to occur at least
+    /// `min_count` times, 0 for reads shorter than `k`.
+    pub fn query(&self, seq: &Seq<Nuc4>, min_count: u32) -> f64 {
+        hit_fraction(NtHash::new(seq, self.k).map(|hash| self.estimate_hash(hash) >= min_count))
+    }
+
+    /// Adds the counts of a sketch with the same parameters.
+    ///
+    /// Panics if the parameters differ.
+    pub fn merge(&mut self, other: &CountMinSketch) {
+        assert!(
+            self.k == other.k && self.width == other.width && self.depth == other.depth,
+            "sketches have different parameters"
+        );
+        for (a, &b) in self.counters.iter_mut().zip(&other.counters) {
+            *a = a.saturating_add(b);
+        }
+    }
+
+    #[inline]
+    fn cell(&self, hash: u64, row: u32) -> usize {
+        row as usize * self.width + (nthash_multi(hash, row, self.k) % self.width as u64) as usize
+    }
+
+    // -- Serialization -------------------------------------------------------
+
+    /// Writes the sketch to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a sketch written by [`CountMinSketch::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&COUNT_MIN_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.k as u32).to_le_bytes())?;
+        writer.write_all(&self.depth.to_le_bytes())?;
+        writer.write_all(&(self.width as u64).to_le_bytes())?;
+        for counter in &self.counters {
+            writer.write_all(&counter.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 28];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid("not a count-min sketch"));
+        }
+        let field = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+        if field(8) != COUNT_MIN_VERSION {
+            return Err(invalid("unsupported count-min sketch version"));
+        }
+        let (k, depth) = (field(12) as usize, field(16));
+        let width = usize::try_from(u64::from_le_bytes(header[20..28].try_into().unwrap()))
+            .map_err(|_| invalid("sketch width does not fit in usize"))?;
+        if k == 0 || depth == 0 || width == 0 {
+            return Err(invalid("sketch parameters must be positive"));
+        }
+
+        let mut counters = Vec::new();
+        let mut buf = [0u8; 4];
+        for _ in 0..width as u64 * depth as u64 {
+            reader.read_exact(&mut buf)?;
+            counters.push(u32::from_le_bytes(buf));
+        }
+        Ok(Self {
+            k,
+            width,
+            depth,
+            counters,
+        })
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
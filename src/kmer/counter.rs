@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::thread;
+
+use super::kmers::{canonical_kmer, kmer_from_seq, MAX_K};
+use crate::alphabet::Nuc4;
+use crate::hash::mix64;
+use crate::seq::Seq;
+
+const MAGIC: &[u8; 8] = b"NUCKMERS";
+
+/// Current k-mer table format version.
+pub const KMER_TABLE_VERSION: u32 = 1;
+
+/// Partitions per worker thread in the parallel mode.
+const PARTITIONS_PER_THREAD: usize = 4;
+
+/// Counts canonical k-mers of `Seq<Nuc4>` records.
+///
+/// Single-threaded counting goes through a hash map. With more than one
+/// thread the records are split between workers that scatter their k-mers
+/// into hash partitions, which are then sorted and counted independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KmerCounter {
+    k: usize,
+    threads: usize,
+    min_count: u32,
+}
+
+impl KmerCounter {
+    /// Creates a single-threaded counter keeping every k-mer.
+    ///
+    /// Panics unless `1 <= k <= 32`.
+    pub fn new(k: usize) -> Self {
+        assert!((1..=MAX_K).contains(&k), "k must be between 1 and {MAX_K}");
+        Self {
+            k,
+            threads: 1,
+            min_count: 1,
+        }
+    }
+
+    /// Sets the number of worker threads; 0 uses the available parallelism.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = if threads == 0 {
+            thread::available_parallelism().map_or(1, |n| n.get())
+        } else {
+            threads
+        };
+        self
+    }
+
+    /// Drops k-mers seen fewer than `min_count` times from the result.
+    pub fn with_min_count(mut self, min_count: u32) -> Self {
+        self.min_count = min_count;
+        self
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Counts the canonical k-mers of all records.
+    pub fn count(&self, seqs: &[Seq<Nuc4>]) -> KmerCounts {
+        let mut entries = if self.threads > 1 && seqs.len() > 1 {
+            self.count_partitioned(seqs)
+        } else {
+            self.count_hashed(seqs)
+        };
+        entries.sort_unstable_by_key(|&(kmer, _)| kmer);
+        KmerCounts { k: self.k, entries }
+    }
+
+    fn count_hashed(&self, seqs: &[Seq<Nuc4>]) -> Vec<(u64, u32)> {
+        let mut counts: HashMap<u64, u32> = HashMap::new();
+        for seq in seqs {
+            for kmer in seq.canonical_kmers(self.k) {
+                let count = counts.entry(kmer).or_insert(0);
+                *count = count.saturating_add(1);
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|&(_, count)| count >= self.min_count)
+            .collect()
+    }
+
+    fn count_partitioned(&self, seqs: &[Seq<Nuc4>]) -> Vec<(u64, u32)> {
+        let threads = self.threads.min(seqs.len());
+        let partitions = threads * PARTITIONS_PER_THREAD;
+
+        // Scatter: every worker splits the k-mers of its records by hash
+        let chunk = seqs.len().div_ceil(threads);
+        let scattered: Vec<Vec<Vec<u64>>> = thread::scope(|s| {
+            let workers: Vec<_> = seqs
+                .chunks(chunk)
+                .map(|records| {
+                    s.spawn(move || {
+                        let mut buckets = vec![Vec::new(); partitions];
+                        for seq in records {
+                            for kmer in seq.canonical_kmers(self.k) {
+                                buckets[mix64(kmer) as usize % partitions].push(kmer);
+                            }
+                        }
+                        buckets
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+
+        // Gather: every worker sorts and counts its share of the partitions
+        let scattered = &scattered;
+        thread::scope(|s| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    s.spawn(move || {
+                        let mut entries = Vec::new();
+                        for p in (worker..partitions).step_by(threads) {
+                            let mut kmers: Vec<u64> = scattered
+                                .iter()
+                                .flat_map(|b| b[p].iter().copied())
+                                .collect();
+                            kmers.sort_unstable();
+                            for run in kmers.chunk_by(|a, b| a == b) {
+                                let count = u32::try_from(run.len()).unwrap_or(u32::MAX);
+                                if count >= self.min_count {
+                                    entries.push((run[0], count));
+                                }
+                            }
+                        }
+                        entries
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().unwrap())
+                .collect()
+        })
+    }
+}
+
+/// Canonical k-mer counts, sorted by packed k-mer word.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KmerCounts {
+    k: usize,
+    entries: Vec<(u64, u32)>,
+}
+
+impl KmerCounts {
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of distinct k-mers.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the count of a canonical k-mer word, 0 if absent.
+    pub fn get(&self, kmer: u64) -> u32 {
+        self.entries
+            .binary_search_by_key(&kmer, |&(word, _)| word)
+            .map_or(0, |i| self.entries[i].1)
+    }
+
+    /// Returns the count of a k-mer given as a sequence, in either
+    /// orientation.
+    ///
+    /// Panics if the sequence length differs from `k`.
+    pub fn count_of(&self, kmer: &Seq<Nuc4>) -> u32 {
+        assert_eq!(kmer.len(), self.k, "expected a {}-mer", self.k);
+        self.get(canonical_kmer(kmer_from_seq(kmer), self.k))
+    }
+
+    /// Returns the sum of all counts.
+    pub fn total(&self) -> u64 {
+        self.entries.iter().map(|&(_, count)| count as u64).sum()
+    }
+
+    /// Returns the `(k-mer word, count)` pairs in k-mer order.
+    pub fn entries(&self) -> &[(u64, u32)] {
+        &self.entries
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        self.entries.iter().copied()
+    }
+
+    /// Returns how many distinct k-mers occur exactly `c` times, indexed by
+    /// `c`; entry 0 is always 0.
+    pub fn histogram(&self) -> Vec<u64> {
+        let max = self.entries.iter().map(|&(_, c)| c).max().unwrap_or(0);
+        let mut histogram = vec![0; max as usize + 1];
+        for &(_, count) in &self.entries {
+            histogram[count as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Writes the non-empty histogram bins as `count<TAB>k-mers` lines, like
+    /// `jellyfish histo`.
+    pub fn write_histogram<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (count, &kmers) in self.histogram().iter().enumerate() {
+            if kmers > 0 {
+                writeln!(writer, "{count}\t{kmers}")?;
+            }
+        }
+        writer.flush()
+    }
+
+    // -- Binary table --------------------------------------------------------
+
+    /// Writes the table to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a table written by [`KmerCounts::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Serializes the table: a header, then little-endian `(u64, u32)` pairs.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&KMER_TABLE_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.k as u32).to_le_bytes())?;
+        writer.write_all(&(self.entries.len() as u64).to_le_bytes())?;
+        for &(kmer, count) in &self.entries {
+            writer.write_all(&kmer.to_le_bytes())?;
+            writer.write_all(&count.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes a table written by [`KmerCounts::write_to`].
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 24];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid("not a k-mer table"));
+        }
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != KMER_TABLE_VERSION {
+            return Err(invalid("unsupported k-mer table version"));
+        }
+        let k = u32::from_le_bytes(header[12..16].try_into().unwrap()) as usize;
+        if !(1..=MAX_K).contains(&k) {
+            return Err(invalid("k-mer length out of range"));
+        }
+        let len = u64::from_le_bytes(header[16..24].try_into().unwrap());
+
+        let mut entries = Vec::new();
+        let mut record = [0u8; 12];
+        for _ in 0..len {
+            reader.read_exact(&mut record)?;
+            let kmer = u64::from_le_bytes(record[..8].try_into().unwrap());
+            let count = u32::from_le_bytes(record[8..].try_into().unwrap());
+            entries.push((kmer, count));
+        }
+        if !entries.is_sorted_by(|a, b| a.0 < b.0) {
+            return Err(invalid("k-mer table is not sorted"));
+        }
+        Ok(Self { k, entries })
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
@@ -0,0 +1,163 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::alphabet::Nuc4;
+use crate::hash::{mix64, NtHash};
+use crate::seq::Seq;
+
+const MAGIC: &[u8; 8] = b"NUCHLLOG";
+
+/// Current HyperLogLog format version.
+pub const HLL_VERSION: u32 = 1;
+
+/// Supported precisions (register index bits).
+pub const HLL_PRECISION: RangeInclusive<u8> = 4..=18;
+
+/// HyperLogLog estimator of the number of distinct canonical k-mers.
+///
+/// Uses `2^precision` one-byte registers; the relative standard error is
+/// about `1.04 / sqrt(2^precision)`. Estimators with the same parameters
+/// can be filled on separate threads or files and merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    k: usize,
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Creates an empty estimator.
+    ///
+    /// Panics if `k` is 0 or the precision is outside [`HLL_PRECISION`].
+    pub fn new(k: usize, precision: u8) -> Self {
+        assert!(k > 0, "k must be positive");
+        assert!(
+            HLL_PRECISION.contains(&precision),
+            "precision must be in {HLL_PRECISION:?}"
+        );
+        Self {
+            k,
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Returns the expected relative standard error of estimates.
+    pub fn relative_error(&self) -> f64 {
+        1.04 / (self.registers.len() as f64).sqrt()
+    }
+
+    /// Adds a k-mer by its ntHash value.
+    #[inline]
+    pub fn insert_hash(&mut self, hash: u64) {
+        // ntHash keeps the smaller strand, which skews the high bits
+        let hash = mix64(hash);
+        let p = self.precision as u32;
+        let index = (hash >> (64 - p)) as usize;
+        let rank = ((hash << p).leading_zeros() + 1).min(64 - p + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Adds every k-mer of a sequence.
+    pub fn insert(&mut self, seq: &Seq<Nuc4>) {
+        for hash in NtHash::new(seq, self.k) {
+            self.insert_hash(hash);
+        }
+    }
+
+    /// Estimates the number of distinct k-mers inserted so far.
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|&r| (-(r as f64)).exp2()).sum();
+        let raw = alpha * m * m / sum;
+
+        // Linear counting is more accurate while many registers are empty
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+
+    /// Folds another estimator into this one, as if all its k-mers had been
+    /// inserted here.
+    ///
+    /// Panics if the parameters differ.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert!(
+            self.k == other.k && self.precision == other.precision,
+            "estimators have different parameters"
+        );
+        for (a, &b) in self.registers.iter_mut().zip(&other.registers) {
+            *a = (*a).max(b);
+        }
+    }
+
+    // -- Serialization -------------------------------------------------------
+
+    /// Writes the estimator to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads an estimator written by [`HyperLogLog::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&HLL_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.k as u32).to_le_bytes())?;
+        writer.write_all(&[self.precision])?;
+        writer.write_all(&self.registers)
+    }
+
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 17];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid("not a HyperLogLog file"));
+        }
+        let u32_at = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+        if u32_at(8) != HLL_VERSION {
+            return Err(invalid("unsupported HyperLogLog version"));
+        }
+        let (k, precision) = (u32_at(12) as usize, header[16]);
+        if k == 0 || !HLL_PRECISION.contains(&precision) {
+            return Err(invalid("invalid HyperLogLog parameters"));
+        }
+        let mut registers = vec![0; 1 << precision];
+        reader.read_exact(&mut registers)?;
+        Ok(Self {
+            k,
+            precision,
+            registers,
+        })
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
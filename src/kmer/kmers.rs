@@ -0,0 +1,142 @@
+use crate::alphabet::Nuc4;
+use crate::seq::Seq;
+
+/// Longest k-mer that fits in a packed `u64` word.
+pub const MAX_K: usize = 32;
+
+/// Returns the mask covering the `2 * k` low bits of a k-mer word.
+#[inline]
+pub(crate) fn kmer_mask(k: usize) -> u64 {
+    if k == MAX_K {
+        u64::MAX
+    } else {
+        (1 << (2 * k)) - 1
+    }
+}
+
+/// Packs a sequence of at most 32 bases into a k-mer word, first base in the
+/// most significant position.
+///
+/// Panics if the sequence is longer than [`MAX_K`].
+pub fn kmer_from_seq(seq: &Seq<Nuc4>) -> u64 {
+    assert!(seq.len() <= MAX_K, "k-mers are limited to {MAX_K} bases");
+    (0..seq.len()).fold(0, |word, i| word << 2 | seq.get_bits(i) as u64)
+}
+
+/// Unpacks a k-mer word into a sequence.
+pub fn kmer_to_seq(word: u64, k: usize) -> Seq<Nuc4> {
+    let mut seq = Seq::new(k);
+    for i in 0..k {
+        seq.init_with(i, (word >> (2 * (k - 1 - i)) & 3) as u8);
+    }
+    seq
+}
+
+/// Returns the reverse complement of a k-mer word.
+#[inline]
+pub fn reverse_complement_kmer(word: u64, k: usize) -> u64 {
+    // Complementing 2-bit codes is `3 - x`; then reverse the 2-bit groups
+    let mut x = !word;
+    x = (x >> 2 & 0x3333333333333333) | (x & 0x3333333333333333) << 2;
+    x = (x >> 4 & 0x0f0f0f0f0f0f0f0f) | (x & 0x0f0f0f0f0f0f0f0f) << 4;
+    x = x.swap_bytes();
+    x >> (2 * (MAX_K - k))
+}
+
+/// Returns the smaller of a k-mer word and its reverse complement.
+#[inline]
+pub fn canonical_kmer(word: u64, k: usize) -> u64 {
+    word.min(reverse_complement_kmer(word, k))
+}
+
+/// Iterator over the packed k-mers of a `Seq<Nuc4>` and their reverse
+/// complements, see [`Seq::kmers`].
+#[derive(Debug, Clone)]
+pub struct Kmers<'a> {
+    seq: &'a Seq<Nuc4>,
+    k: usize,
+    pos: usize,
+    forward: u64,
+    reverse: u64,
+}
+
+impl<'a> Kmers<'a> {
+    fn new(seq: &'a Seq<Nuc4>, k: usize) -> Self {
+        assert!((1..=MAX_K).contains(&k), "k must be between 1 and {MAX_K}");
+        Self {
+            seq,
+            k,
+            pos: 0,
+            forward: 0,
+            reverse: 0,
+        }
+    }
+
+    /// Advances to the next window, returning its forward and reverse
+    /// complement words.
+    #[inline]
+    fn next_pair(&mut self) -> Option<(u64, u64)> {
+        while self.pos < self.seq.len() {
+            let bits = self.seq.get_bits(self.pos) as u64;
+            self.forward = (self.forward << 2 | bits) & kmer_mask(self.k);
+            self.reverse = self.reverse >> 2 | (3 - bits) << (2 * (self.k - 1));
+            self.pos += 1;
+            if self.pos >= self.k {
+                return Some((self.forward, self.reverse));
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for Kmers<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.next_pair().map(|(forward, _)| forward)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.seq.len() + 1).saturating_sub(self.pos.max(self.k - 1) + 1);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Kmers<'_> {}
+
+/// Iterator over canonical k-mer words, see [`Seq::canonical_kmers`].
+#[derive(Debug, Clone)]
+pub struct CanonicalKmers<'a>(Kmers<'a>);
+
+impl Iterator for CanonicalKmers<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.0
+            .next_pair()
+            .map(|(forward, reverse)| forward.min(reverse))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for CanonicalKmers<'_> {}
+
+impl Seq<Nuc4> {
+    /// Iterates over the packed k-mers of the sequence, left to right.
+    ///
+    /// Panics unless `1 <= k <= 32`.
+    pub fn kmers(&self, k: usize) -> Kmers<'_> {
+        Kmers::new(self, k)
+    }
+
+    /// Iterates over the canonical k-mers of the sequence: the smaller of
+    /// each k-mer and its reverse complement.
+    ///
+    /// Panics unless `1 <= k <= 32`.
+    pub fn canonical_kmers(&self, k: usize) -> CanonicalKmers<'_> {
+        CanonicalKmers(Kmers::new(self, k))
+    }
+}
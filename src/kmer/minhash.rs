@@ -0,0 +1,456 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::alphabet::Nuc4;
+use crate::hash::NtHash;
+use crate::seq::Seq;
+
+const MAGIC: &[u8; 8] = b"NUCMINHS";
+
+/// Smallest number of buffered scaled hashes worth compacting.
+const MIN_BUFFER: usize = 1024;
+
+/// Current sketch file format version.
+pub const SKETCH_VERSION: u32 = 1;
+
+/// How a [`Sketch`] selects the hashes it keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SketchMode {
+    /// The `n` smallest distinct hashes (classic MinHash, as in Mash).
+    BottomK(usize),
+    /// Every hash below `u64::MAX / scale` (FracMinHash, as in sourmash).
+    Scaled(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SketchError {
+    KMismatch { left: usize, right: usize },
+    ModeMismatch,
+}
+
+impl fmt::Display for SketchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SketchError::KMismatch { left, right } => {
+                write!(f, "sketches use different k-mer sizes ({left} and {right})")
+            }
+            SketchError::ModeMismatch => write!(f, "cannot compare bottom-k and scaled sketches"),
+        }
+    }
+}
+
+impl std::error::Error for SketchError {}
+
+/// MinHash sketch of the canonical k-mers of one or more sequences.
+///
+/// Hashes are canonical ntHash values, so sketches built from either strand
+/// agree. The kept hashes are sorted and distinct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sketch {
+    k: usize,
+    mode: SketchMode,
+    /// Total length of the sketched sequences.
+    bases: u64,
+    hashes: Vec<u64>,
+}
+
+impl Sketch {
+    /// Creates an empty bottom-k sketch keeping `size` hashes.
+    ///
+    /// Panics if `k` or `size` is 0.
+    pub fn bottom_k(k: usize, size: usize) -> Self {
+        assert!(size > 0, "sketch size must be positive");
+        Self::empty(k, SketchMode::BottomK(size))
+    }
+
+    /// Creates an empty FracMinHash sketch keeping about one in `scale`
+    /// hashes.
+    ///
+    /// Panics if `k` or `scale` is 0.
+    pub fn scaled(k: usize, scale: u64) -> Self {
+        assert!(scale > 0, "scale must be positive");
+        Self::empty(k, SketchMode::Scaled(scale))
+    }
+
+    fn empty(k: usize, mode: SketchMode) -> Self {
+        assert!(k > 0, "k must be positive");
+        Self {
+            k,
+            mode,
+            bases: 0,
+            hashes: Vec::new(),
+        }
+    }
+
+    /// Sketches a collection of sequences, keeping the `sketch_size`
+    /// smallest hashes.
+    pub fn from_seqs(seqs: &[Seq<Nuc4>], k: usize, sketch_size: usize) -> Self {
+        let mut sketch = Self::bottom_k(k, sketch_size);
+        sketch.extend(seqs);
+        sketch
+    }
+
+    /// Sketches a collection of sequences with FracMinHash.
+    pub fn scaled_from_seqs(seqs: &[Seq<Nuc4>], k: usize, scale: u64) -> Self {
+        let mut sketch = Self::scaled(k, scale);
+        sketch.extend(seqs);
+        sketch
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn mode(&self) -> SketchMode {
+        self.mode
+    }
+
+    /// Returns the total length of the sketched sequences.
+    pub fn bases(&self) -> u64 {
+        self.bases
+    }
+
+    /// Returns the number of kept hashes.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns the kept hashes in increasing order.
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// Adds the k-mers of a sequence.
+    pub fn insert(&mut self, seq: &Seq<Nuc4>) {
+        self.extend(std::slice::from_ref(seq));
+    }
+
+    /// Adds the k-mers of several sequences, compacting once at the end.
+    pub fn extend(&mut self, seqs: &[Seq<Nuc4>]) {
+        // Hashes before `sorted` are sorted and distinct, the rest buffered
+        let mut sorted = self.hashes.len();
+        for seq in seqs {
+            self.buffer(seq, &mut sorted);
+        }
+        if let SketchMode::Scaled(_) = self.mode {
+            self.merge_buffer(sorted);
+        }
+    }
+
+    /// Buffers the hashes of a sequence, compacting whenever the buffer
+    /// doubles. Bottom-k sketches are also compacted at the end, scaled ones
+    /// are left for [`Sketch::extend`] to merge.
+    fn buffer(&mut self, seq: &Seq<Nuc4>, sorted: &mut usize) {
+        self.bases += seq.len() as u64;
+        match self.mode {
+            SketchMode::BottomK(size) => {
+                // Only candidates below the current cut-off are buffered
+                let mut cutoff = self.cutoff(size);
+                for hash in NtHash::new(seq, self.k) {
+                    if hash < cutoff {
+                        self.hashes.push(hash);
+                        if self.hashes.len() >= 2 * size {
+                            self.compact(size);
+                            cutoff = self.cutoff(size);
+                        }
+                    }
+                }
+                // Cheap, since at most `2 * size` hashes are kept
+                self.compact(size);
+            }
+            SketchMode::Scaled(scale) => {
+                let max_hash = u64::MAX / scale;
+                for hash in NtHash::new(seq, self.k) {
+                    if hash <= max_hash {
+                        self.hashes.push(hash);
+                        if self.hashes.len() >= (2 * *sorted).max(MIN_BUFFER) {
+                            self.merge_buffer(*sorted);
+                            *sorted = self.hashes.len();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds the hashes of another sketch with the same parameters.
+    pub fn merge(&mut self, other: &Sketch) -> Result<(), SketchError> {
+        self.check(other)?;
+        if self.mode != other.mode {
+            return Err(SketchError::ModeMismatch);
+        }
+        self.bases += other.bases;
+        self.hashes.extend_from_slice(&other.hashes);
+        match self.mode {
+            SketchMode::BottomK(size) => self.compact(size),
+            SketchMode::Scaled(_) => self.compact(usize::MAX),
+        }
+        Ok(())
+    }
+
+    /// Estimates the Jaccard index of the two k-mer sets.
+    ///
+    /// Bottom-k sketches of different sizes are compared on the smaller
+    /// size; scaled sketches on the larger scale.
+    pub fn jaccard(&self, other: &Sketch) -> Result<f64, SketchError> {
+        let (common, total) = self.overlap(other)?;
+        Ok(if total == 0 {
+            0.0
+        } else {
+            common as f64 / total as f64
+        })
+    }
+
+    /// Returns the Mash distance, an estimate of the per-base mutation rate:
+    /// `-ln(2j / (1 + j)) / k`. Unrelated sketches have distance 1.
+    pub fn mash_distance(&self, other: &Sketch) -> Result<f64, SketchError> {
+        let j = self.jaccard(other)?;
+        if j == 0.0 {
+            return Ok(1.0);
+        }
+        Ok((-(2.0 * j / (1.0 + j)).ln() / self.k as f64).max(0.0))
+    }
+
+    /// Returns the probability of seeing at least the observed number of
+    /// shared hashes between two random sequences of the same lengths, as
+    /// computed by Mash.
+    pub fn p_value(&self, other: &Sketch) -> Result<f64, SketchError> {
+        let (common, total) = self.overlap(other)?;
+        if common == 0 {
+            return Ok(1.0);
+        }
+        let kmer_space = 4f64.powi(self.k as i32);
+        let px = 1.0 / (1.0 + kmer_space / self.bases.max(1) as f64);
+        let py = 1.0 / (1.0 + kmer_space / other.bases.max(1) as f64);
+        let r = px * py / (px + py - px * py);
+        Ok(binomial_tail(common, total, r))
+    }
+
+    /// Estimates the fraction of this sketch's k-mers contained in `other`.
+    ///
+    /// Exact on the kept hashes for scaled sketches; bottom-k sketches only
+    /// consider hashes below both sketches' largest hash.
+    pub fn containment(&self, other: &Sketch) -> Result<f64, SketchError> {
+        self.check(other)?;
+        let max_hash = match (self.mode, other.mode) {
+            (SketchMode::Scaled(a), SketchMode::Scaled(b)) => u64::MAX / a.max(b),
+            (SketchMode::BottomK(_), SketchMode::BottomK(_)) => {
+                let last = |s: &Sketch| s.hashes.last().copied().unwrap_or(0);
+                last(self).min(last(other))
+            }
+            _ => return Err(SketchError::ModeMismatch),
+        };
+        let mine: Vec<u64> = self
+            .hashes
+            .iter()
+            .copied()
+            .filter(|&h| h <= max_hash)
+            .collect();
+        if mine.is_empty() {
+            return Ok(0.0);
+        }
+        let common = mine
+            .iter()
+            .filter(|h| other.hashes.binary_search(h).is_ok())
+            .count();
+        Ok(common as f64 / mine.len() as f64)
+    }
+
+    /// Counts shared hashes and the size of the union they are drawn from.
+    fn overlap(&self, other: &Sketch) -> Result<(usize, usize), SketchError> {
+        self.check(other)?;
+        let (limit, max_hash) = match (self.mode, other.mode) {
+            (SketchMode::BottomK(a), SketchMode::BottomK(b)) => (a.min(b), u64::MAX),
+            (SketchMode::Scaled(a), SketchMode::Scaled(b)) => (usize::MAX, u64::MAX / a.max(b)),
+            _ => return Err(SketchError::ModeMismatch),
+        };
+
+        // Walk the union in increasing order
+        let (a, b) = (&self.hashes, &other.hashes);
+        let (mut i, mut j) = (0, 0);
+        let (mut common, mut total) = (0, 0);
+        while total < limit {
+            let (next, shared) = match (a.get(i), b.get(j)) {
+                (Some(&x), Some(&y)) if x == y => (x, true),
+                (Some(&x), Some(&y)) => (x.min(y), false),
+                (Some(&x), None) => (x, false),
+                (None, Some(&y)) => (y, false),
+                (None, None) => break,
+            };
+            if next > max_hash {
+                break;
+            }
+            i += (a.get(i) == Some(&next)) as usize;
+            j += (b.get(j) == Some(&next)) as usize;
+            common += shared as usize;
+            total += 1;
+        }
+        Ok((common, total))
+    }
+
+    fn check(&self, other: &Sketch) -> Result<(), SketchError> {
+        if self.k != other.k {
+            return Err(SketchError::KMismatch {
+                left: self.k,
+                right: other.k,
+            });
+        }
+        Ok(())
+    }
+
+    /// Largest hash still worth buffering.
+    fn cutoff(&self, size: usize) -> u64 {
+        if self.hashes.len() >= size {
+            self.hashes[size - 1]
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn compact(&mut self, size: usize) {
+        self.hashes.sort_unstable();
+        self.hashes.dedup();
+        self.hashes.truncate(size);
+    }
+
+    /// Sorts the hashes after `sorted` and merges them into the sorted
+    /// ones before it, dropping duplicates.
+    fn merge_buffer(&mut self, sorted: usize) {
+        let mut buffer = self.hashes.split_off(sorted);
+        buffer.sort_unstable();
+        buffer.dedup();
+        if buffer.is_empty() {
+            return;
+        }
+        let kept = std::mem::take(&mut self.hashes);
+        self.hashes = Vec::with_capacity(kept.len() + buffer.len());
+        let (mut i, mut j) = (0, 0);
+        while i < kept.len() && j < buffer.len() {
+            let hash = kept[i].min(buffer[j]);
+            i += (kept[i] == hash) as usize;
+            j += (buffer[j] == hash) as usize;
+            self.hashes.push(hash);
+        }
+        self.hashes.extend_from_slice(&kept[i..]);
+        self.hashes.extend_from_slice(&buffer[j..]);
+    }
+
+    // -- Sketch files --------------------------------------------------------
+
+    /// Writes the sketch to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a sketch written by [`Sketch::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let (mode, parameter) = match self.mode {
+            SketchMode::BottomK(size) => (0u8, size as u64),
+            SketchMode::Scaled(scale) => (1u8, scale),
+        };
+        writer.write_all(MAGIC)?;
+        writer.write_all(&SKETCH_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.k as u32).to_le_bytes())?;
+        writer.write_all(&[mode])?;
+        writer.write_all(&parameter.to_le_bytes())?;
+        writer.write_all(&self.bases.to_le_bytes())?;
+        writer.write_all(&(self.hashes.len() as u64).to_le_bytes())?;
+        for hash in &self.hashes {
+            writer.write_all(&hash.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 41];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid("not a sketch file"));
+        }
+        let u32_at = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+        let u64_at = |i: usize| u64::from_le_bytes(header[i..i + 8].try_into().unwrap());
+        if u32_at(8) != SKETCH_VERSION {
+            return Err(invalid("unsupported sketch version"));
+        }
+        let k = u32_at(12) as usize;
+        let parameter = u64_at(17);
+        if k == 0 || parameter == 0 {
+            return Err(invalid("sketch parameters must be positive"));
+        }
+        let mode = match header[16] {
+            0 => SketchMode::BottomK(
+                usize::try_from(parameter).map_err(|_| invalid("sketch size too large"))?,
+            ),
+            1 => SketchMode::Scaled(parameter),
+            _ => return Err(invalid("unknown sketch mode")),
+        };
+        let bases = u64_at(25);
+        let count = u64_at(33);
+
+        let mut hashes = Vec::new();
+        let mut buf = [0u8; 8];
+        for _ in 0..count {
+            reader.read_exact(&mut buf)?;
+            hashes.push(u64::from_le_bytes(buf));
+        }
+        if !hashes.is_sorted_by(|a, b| a < b) {
+            return Err(invalid("sketch hashes are not sorted"));
+        }
+        match mode {
+            SketchMode::BottomK(size) if hashes.len() > size => {
+                return Err(invalid("sketch holds more hashes than its size"));
+            }
+            SketchMode::Scaled(scale) if hashes.last().is_some_and(|&h| h > u64::MAX / scale) => {
+                return Err(invalid("sketch holds hashes above its scale"));
+            }
+            _ => {}
+        }
+        Ok(Self {
+            k,
+            mode,
+            bases,
+            hashes,
+        })
+    }
+}
+
+/// Returns `P(X >= x)` for `X ~ Binomial(n, p)`, summed in log space.
+fn binomial_tail(x: usize, n: usize, p: f64) -> f64 {
+    if p <= 0.0 {
+        return if x == 0 { 1.0 } else { 0.0 };
+    }
+    if p >= 1.0 {
+        return 1.0;
+    }
+    let (ln_p, ln_q) = (p.ln(), (1.0 - p).ln());
+    // ln C(n, i) built up incrementally
+    let mut ln_choose = 0.0;
+    let mut terms = Vec::with_capacity(n + 1);
+    for i in 0..=n {
+        if i > 0 {
+            ln_choose += ((n - i + 1) as f64).ln() - (i as f64).ln();
+        }
+        if i >= x {
+            terms.push(ln_choose + i as f64 * ln_p + (n - i) as f64 * ln_q);
+        }
+    }
+    let max = terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let sum: f64 = terms.iter().map(|t| (t - max).exp()).sum();
+    (max + sum.ln()).exp().min(1.0)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
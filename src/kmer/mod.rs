@@ -0,0 +1,13 @@
+mod bloom;
+mod count_min;
+mod counter;
+mod hll;
+mod kmers;
+mod minhash;
+
+pub use bloom::*;
+pub use count_min::*;
+pub use counter::*;
+pub use hll::*;
+pub use kmers::*;
+pub use minhash::*;
@@ -2,6 +2,7 @@
 //!
 //! It's goal is to provide the fastest and easiest way to work with DNA, RNA, and amino acid sequences.
 //!
+#![feature(portable_simd)]
 
 /// Defines core biological alphabets and their properties.
 pub mod alphabet;
@@ -9,8 +10,42 @@ pub mod alphabet;
 /// Core functionality for working with biological sequences.
 pub mod seq;
 
+/// Succinct bit vectors with rank and select.
+pub mod bitvec;
+
 /// Handles IO with FastA files.
 pub mod io;
 
 /// Handles hashing of DNA sequences.
 pub mod hash;
+
+/// Compact DNA storage with N runs kept in a sidecar.
+pub mod masked;
+
+/// Soft-masking (lowercase) information carried alongside sequences.
+pub mod softmask;
+
+/// Serde support for sequences and their elements.
+#[cfg(feature = "serde")]
+mod serialize;
+
+/// Distances between sequences.
+pub mod distance;
+
+/// Pattern search over packed sequences.
+pub mod search;
+
+/// Pairwise sequence alignment.
+pub mod align;
+
+/// Multiple sequence alignments.
+pub mod msa;
+
+/// Full-text indexes over sequences.
+pub mod index;
+
+/// K-mer iteration, counting and sketching.
+pub mod kmer;
+
+/// Sequence graphs.
+pub mod graph;
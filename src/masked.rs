@@ -0,0 +1,338 @@
+use std::fmt;
+use std::ops::{Range, RangeBounds};
+use std::str::FromStr;
+
+use crate::alphabet::{Alphabet, Nuc4, Nuc5, Nucleotide};
+use crate::seq::{resolve_range, Seq, SeqError};
+
+/// A DNA sequence stored as `Seq<Nuc4>` plus a sidecar of N runs.
+///
+/// This mirrors the UCSC 2bit layout: bases are packed at 2 bits per symbol
+/// and stretches of `N` are kept as a sorted list of non-overlapping,
+/// non-adjacent intervals. Positions covered by an N run hold `A` in the
+/// packed sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskedSeq {
+    seq: Seq<Nuc4>,
+    n_runs: Vec<Range<usize>>,
+}
+
+impl MaskedSeq {
+    /// Creates a masked sequence from packed bases and a list of N runs.
+    ///
+    /// The runs are sorted and merged; empty runs are dropped.
+    /// Panics if a run extends past the end of the sequence.
+    pub fn new(mut seq: Seq<Nuc4>, mut n_runs: Vec<Range<usize>>) -> Self {
+        n_runs.retain(|run| !run.is_empty());
+        n_runs.sort_by_key(|run| run.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(n_runs.len());
+        for run in n_runs {
+            assert!(
+                run.end <= seq.len(),
+                "N run {}..{} out of bounds for sequence of length {}",
+                run.start,
+                run.end,
+                seq.len()
+            );
+            match merged.last_mut() {
+                Some(last) if run.start <= last.end => last.end = last.end.max(run.end),
+                _ => merged.push(run),
+            }
+        }
+
+        // Keep the invariant that masked positions hold `A`
+        for run in &merged {
+            for i in run.clone() {
+                seq.set_bits(i, 0);
+            }
+        }
+
+        Self {
+            seq,
+            n_runs: merged,
+        }
+    }
+
+    /// Access the length of the sequence.
+    pub fn len(&self) -> usize {
+        self.seq.len()
+    }
+
+    /// Checks if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.seq.is_empty()
+    }
+
+    /// Returns the packed bases. Positions inside N runs read as `A`.
+    pub fn as_nuc4(&self) -> &Seq<Nuc4> {
+        &self.seq
+    }
+
+    /// Returns the sorted N runs.
+    pub fn n_runs(&self) -> &[Range<usize>] {
+        &self.n_runs
+    }
+
+    /// Returns the total number of `N` symbols.
+    pub fn n_count(&self) -> usize {
+        self.n_runs.iter().map(|run| run.len()).sum()
+    }
+
+    /// Splits the sequence into packed bases and N runs.
+    pub fn into_parts(self) -> (Seq<Nuc4>, Vec<Range<usize>>) {
+        (self.seq, self.n_runs)
+    }
+
+    /// Checks whether the symbol at the given index is an `N`.
+    pub fn is_n(&self, index: usize) -> bool {
+        let run = self.n_runs.partition_point(|run| run.end <= index);
+        run < self.n_runs.len() && self.n_runs[run].start <= index
+    }
+
+    /// Returns the decoded element at the given index.
+    #[inline]
+    pub fn get(&self, index: usize) -> Nucleotide {
+        if self.is_n(index) {
+            Nucleotide::N
+        } else {
+            self.seq.get(index)
+        }
+    }
+
+    /// Returns a new masked sequence holding the symbols in `range`.
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let Range { start, end } = resolve_range(range, self.len());
+        let first = self.n_runs.partition_point(|run| run.end <= start);
+        let n_runs = self.n_runs[first..]
+            .iter()
+            .take_while(|run| run.start < end)
+            .map(|run| run.start.max(start) - start..run.end.min(end) - start)
+            .collect();
+
+        Self {
+            seq: self.seq.slice(start..end),
+            n_runs,
+        }
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> MaskedSeqIter<'_> {
+        MaskedSeqIter {
+            masked: self,
+            pos: 0,
+            run: 0,
+        }
+    }
+}
+
+/// Appends position `pos` to the last run if adjacent, else opens a new run.
+fn push_n(runs: &mut Vec<Range<usize>>, pos: usize) {
+    match runs.last_mut() {
+        Some(last) if last.end == pos => last.end += 1,
+        _ => runs.push(pos..pos + 1),
+    }
+}
+
+// -- Conversions -------------------------------------------------------------
+
+/// Flag set in `NUC5_TO_NUC4` when the first symbol of the byte is `N`.
+const FIRST_IS_N: u8 = 0x20;
+
+/// Flag set in `NUC5_TO_NUC4` when the second symbol of the byte is `N`.
+const SECOND_IS_N: u8 = 0x10;
+
+/// Lookup table: packed `Nuc5` byte (2 symbols) → packed `Nuc4` nibble.
+///
+/// `N` is written as `A` and reported through the `*_IS_N` flags.
+const NUC5_TO_NUC4: [u8; 256] = {
+    let mut lut = [0u8; 256];
+    let mut b = 0;
+    while b < 256 {
+        let first = (b >> 3) & 0b111;
+        let second = b & 0b111;
+        let mut v = 0u8;
+        if first < 4 {
+            v |= (first as u8) << 2;
+        } else {
+            v |= FIRST_IS_N;
+        }
+        if second < 4 {
+            v |= second as u8;
+        } else {
+            v |= SECOND_IS_N;
+        }
+        lut[b] = v;
+        b += 1;
+    }
+    lut
+};
+
+/// Lookup table: packed `Nuc4` byte (4 symbols) → two packed `Nuc5` bytes.
+const NUC4_TO_NUC5: [[u8; 2]; 256] = {
+    let mut lut = [[0u8; 2]; 256];
+    let mut b = 0;
+    while b < 256 {
+        let s = b as u8;
+        lut[b] = [
+            ((s >> 6) & 0b11) << 3 | ((s >> 4) & 0b11),
+            ((s >> 2) & 0b11) << 3 | (s & 0b11),
+        ];
+        b += 1;
+    }
+    lut
+};
+
+impl From<&Seq<Nuc5>> for MaskedSeq {
+    fn from(seq: &Seq<Nuc5>) -> Self {
+        let len = seq.len();
+        let mut packed = Seq::<Nuc4>::new(len);
+        let mut n_runs = Vec::new();
+
+        // Two Nuc5 bytes fill one Nuc4 byte
+        for (i, &byte) in seq.data.iter().enumerate() {
+            let v = NUC5_TO_NUC4[byte as usize];
+            let shift = if i % 2 == 0 { 4 } else { 0 };
+            packed.data[i / 2] |= (v & 0x0F) << shift;
+
+            if v & FIRST_IS_N != 0 && 2 * i < len {
+                push_n(&mut n_runs, 2 * i);
+            }
+            if v & SECOND_IS_N != 0 && 2 * i + 1 < len {
+                push_n(&mut n_runs, 2 * i + 1);
+            }
+        }
+        packed.clear_padding();
+
+        Self {
+            seq: packed,
+            n_runs,
+        }
+    }
+}
+
+impl From<Seq<Nuc5>> for MaskedSeq {
+    fn from(seq: Seq<Nuc5>) -> Self {
+        Self::from(&seq)
+    }
+}
+
+impl From<&MaskedSeq> for Seq<Nuc5> {
+    fn from(masked: &MaskedSeq) -> Self {
+        let mut out = Seq::<Nuc5>::new(masked.len());
+        let out_bytes = out.data.len();
+
+        // One Nuc4 byte expands into two Nuc5 bytes
+        for (i, &byte) in masked.seq.data.iter().enumerate() {
+            let [first, second] = NUC4_TO_NUC5[byte as usize];
+            out.data[2 * i] = first;
+            if 2 * i + 1 < out_bytes {
+                out.data[2 * i + 1] = second;
+            }
+        }
+        out.clear_padding();
+
+        let n = u8::from(Nucleotide::N);
+        for run in &masked.n_runs {
+            for i in run.clone() {
+                out.set_bits(i, n);
+            }
+        }
+        out
+    }
+}
+
+impl From<MaskedSeq> for Seq<Nuc5> {
+    fn from(masked: MaskedSeq) -> Self {
+        Self::from(&masked)
+    }
+}
+
+impl From<Seq<Nuc4>> for MaskedSeq {
+    fn from(seq: Seq<Nuc4>) -> Self {
+        Self {
+            seq,
+            n_runs: Vec::new(),
+        }
+    }
+}
+
+impl FromStr for MaskedSeq {
+    type Err = SeqError;
+
+    fn from_str(ascii: &str) -> Result<Self, Self::Err> {
+        Seq::<Nuc5>::try_from(ascii).map(|seq| Self::from(&seq))
+    }
+}
+
+// -- Iterator ----------------------------------------------------------------
+
+pub struct MaskedSeqIter<'a> {
+    masked: &'a MaskedSeq,
+    pos: usize,
+    run: usize,
+}
+
+impl<'a> Iterator for MaskedSeqIter<'a> {
+    type Item = Nucleotide;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.masked.len() {
+            return None;
+        }
+
+        let runs = &self.masked.n_runs;
+        while self.run < runs.len() && runs[self.run].end <= self.pos {
+            self.run += 1;
+        }
+
+        let elem = if self.run < runs.len() && runs[self.run].start <= self.pos {
+            Nucleotide::N
+        } else {
+            self.masked.seq.get(self.pos)
+        };
+        self.pos += 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.masked.len() - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for MaskedSeqIter<'a> {}
+
+impl<'a> IntoIterator for &'a MaskedSeq {
+    type Item = Nucleotide;
+    type IntoIter = MaskedSeqIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// -- Display -----------------------------------------------------------------
+
+impl fmt::Display for MaskedSeq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0u8; 64];
+        let mut filled = 0;
+
+        for elem in self.iter() {
+            buf[filled] = Nuc5::to_byte(elem);
+            filled += 1;
+            if filled == buf.len() {
+                // SAFETY: buf contains valid ASCII bytes
+                f.write_str(unsafe { std::str::from_utf8_unchecked(&buf) })?;
+                filled = 0;
+            }
+        }
+
+        // SAFETY: buf[..filled] contains valid ASCII bytes
+        f.write_str(unsafe { std::str::from_utf8_unchecked(&buf[..filled]) })
+    }
+}
@@ -0,0 +1,457 @@
+use std::fmt;
+use std::io;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::alphabet::Alphabet;
+use crate::seq::{Seq, SeqError};
+
+#[derive(Debug)]
+pub enum MsaError {
+    Io(io::Error),
+    Seq(SeqError),
+    /// A row does not have as many columns as the alignment.
+    LengthMismatch {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    /// Malformed input on the given (1-based) line.
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for MsaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MsaError::Io(e) => write!(f, "I/O error: {e}"),
+            MsaError::Seq(e) => write!(f, "invalid sequence: {e:?}"),
+            MsaError::LengthMismatch {
+                name,
+                expected,
+                found,
+            } => write!(f, "row {name} has {found} columns, expected {expected}"),
+            MsaError::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for MsaError {}
+
+impl From<io::Error> for MsaError {
+    fn from(e: io::Error) -> Self {
+        MsaError::Io(e)
+    }
+}
+
+impl From<SeqError> for MsaError {
+    fn from(e: SeqError) -> Self {
+        MsaError::Seq(e)
+    }
+}
+
+// -- Gapped sequences --------------------------------------------------------
+
+/// One row of an alignment: the ungapped residues plus the gap runs.
+///
+/// Gaps are kept as sorted, non-overlapping column ranges next to the packed
+/// residues, so the alphabet does not need a gap symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GappedSeq<A: Alphabet> {
+    residues: Seq<A>,
+    gaps: Vec<Range<usize>>,
+    /// Total gap length of `gaps[..i]`, for column to residue mapping.
+    gaps_before: Vec<usize>,
+}
+
+impl<A: Alphabet> GappedSeq<A> {
+    /// Builds a row from its residues and the column ranges of its gaps.
+    ///
+    /// Gap ranges may be given in any order and may touch or overlap.
+    pub fn new(residues: Seq<A>, mut gaps: Vec<Range<usize>>) -> Self {
+        gaps.retain(|r| !r.is_empty());
+        gaps.sort_by_key(|r| r.start);
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(gaps.len());
+        for gap in gaps {
+            match merged.last_mut() {
+                Some(last) if gap.start <= last.end => last.end = last.end.max(gap.end),
+                _ => merged.push(gap),
+            }
+        }
+
+        let mut gaps_before = Vec::with_capacity(merged.len());
+        let mut total = 0;
+        for gap in &merged {
+            gaps_before.push(total);
+            total += gap.len();
+        }
+        let row = Self {
+            residues,
+            gaps: merged,
+            gaps_before,
+        };
+        assert!(
+            row.gaps.last().is_none_or(|g| g.end <= row.len()),
+            "gap runs extend past the end of the row"
+        );
+        row
+    }
+
+    /// Returns the number of columns, residues and gaps together.
+    pub fn len(&self) -> usize {
+        self.residues.len() + self.gap_count()
+    }
+
+    /// Checks if the row has no columns.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the residues without gaps.
+    pub fn residues(&self) -> &Seq<A> {
+        &self.residues
+    }
+
+    /// Returns the sorted, disjoint gap runs in column coordinates.
+    pub fn gaps(&self) -> &[Range<usize>] {
+        &self.gaps
+    }
+
+    /// Returns the number of gap columns.
+    pub fn gap_count(&self) -> usize {
+        match (self.gaps.last(), self.gaps_before.last()) {
+            (Some(gap), Some(before)) => before + gap.len(),
+            _ => 0,
+        }
+    }
+
+    /// Checks if `column` is a gap.
+    pub fn is_gap(&self, column: usize) -> bool {
+        self.residue_index(column).is_none()
+    }
+
+    /// Maps a column to the index of its residue, or `None` for a gap.
+    pub fn residue_index(&self, column: usize) -> Option<usize> {
+        assert!(column < self.len(), "column out of bounds");
+        // Number of gap runs starting at or before `column`
+        let runs = self.gaps.partition_point(|g| g.start <= column);
+        if runs == 0 {
+            return Some(column);
+        }
+        let gap = &self.gaps[runs - 1];
+        if column < gap.end {
+            None
+        } else {
+            Some(column - self.gaps_before[runs - 1] - gap.len())
+        }
+    }
+
+    /// Returns the symbol in `column`, or `None` for a gap.
+    pub fn get(&self, column: usize) -> Option<A::Elements> {
+        self.residue_index(column).map(|i| self.residues.get(i))
+    }
+
+    /// Returns an iterator over the columns, `None` marking gaps.
+    pub fn iter(&self) -> GappedIter<'_, A> {
+        GappedIter {
+            row: self,
+            len: self.len(),
+            column: 0,
+            residue: 0,
+            gap: 0,
+        }
+    }
+}
+
+impl<A: Alphabet> FromIterator<Option<A::Elements>> for GappedSeq<A> {
+    fn from_iter<I: IntoIterator<Item = Option<A::Elements>>>(iter: I) -> Self {
+        let mut symbols = Vec::new();
+        let mut gaps: Vec<Range<usize>> = Vec::new();
+        for (column, symbol) in iter.into_iter().enumerate() {
+            match symbol {
+                Some(s) => symbols.push(s.into()),
+                None => match gaps.last_mut() {
+                    Some(last) if last.end == column => last.end += 1,
+                    _ => gaps.push(column..column + 1),
+                },
+            }
+        }
+
+        let mut residues = Seq::new(symbols.len());
+        for (i, &bits) in symbols.iter().enumerate() {
+            residues.init_with(i, bits);
+        }
+        Self::new(residues, gaps)
+    }
+}
+
+impl<A: Alphabet> FromStr for GappedSeq<A> {
+    type Err = SeqError;
+
+    /// Parses an aligned row; `-` and `.` are gaps.
+    fn from_str(s: &str) -> Result<Self, SeqError> {
+        s.bytes()
+            .map(|b| match b {
+                b'-' | b'.' => Ok(None),
+                _ => match A::BYTE_TO_BITS[b as usize] {
+                    0xFF => Err(SeqError::InvalidSymbol),
+                    _ => Ok(Some(A::from_byte(b))),
+                },
+            })
+            .collect()
+    }
+}
+
+impl<A: Alphabet> fmt::Display for GappedSeq<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text: String = self
+            .iter()
+            .map(|s| s.map_or('-', |s| A::to_byte(s) as char))
+            .collect();
+        f.write_str(&text)
+    }
+}
+
+pub struct GappedIter<'a, A: Alphabet> {
+    row: &'a GappedSeq<A>,
+    len: usize,
+    column: usize,
+    residue: usize,
+    /// Index of the next gap run at or after `column`.
+    gap: usize,
+}
+
+impl<'a, A: Alphabet> Iterator for GappedIter<'a, A> {
+    type Item = Option<A::Elements>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.column >= self.len {
+            return None;
+        }
+        let column = self.column;
+        self.column += 1;
+
+        match self.row.gaps.get(self.gap) {
+            Some(gap) if gap.contains(&column) => {
+                if column + 1 == gap.end {
+                    self.gap += 1;
+                }
+                Some(None)
+            }
+            _ => {
+                self.residue += 1;
+                Some(Some(self.row.residues.get(self.residue - 1)))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.column;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, A: Alphabet> ExactSizeIterator for GappedIter<'a, A> {}
+
+// -- Alignments --------------------------------------------------------------
+
+/// A multiple sequence alignment: named rows of equal column count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Msa<A: Alphabet> {
+    names: Vec<String>,
+    rows: Vec<GappedSeq<A>>,
+}
+
+impl<A: Alphabet> Default for Msa<A> {
+    fn default() -> Self {
+        Self {
+            names: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl<A: Alphabet> Msa<A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a row, which must have as many columns as the existing ones.
+    pub fn push(&mut self, name: &str, row: GappedSeq<A>) -> Result<(), MsaError> {
+        if !self.rows.is_empty() && row.len() != self.columns() {
+            return Err(MsaError::LengthMismatch {
+                name: name.to_string(),
+                expected: self.columns(),
+                found: row.len(),
+            });
+        }
+        self.names.push(name.to_string());
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Checks if the alignment has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns the number of columns.
+    pub fn columns(&self) -> usize {
+        self.rows.first().map_or(0, |r| r.len())
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn rows(&self) -> &[GappedSeq<A>] {
+        &self.rows
+    }
+
+    /// Returns the row with the given name.
+    pub fn row(&self, name: &str) -> Option<&GappedSeq<A>> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .map(|i| &self.rows[i])
+    }
+
+    /// Returns one column, top to bottom, `None` marking gaps.
+    pub fn column(&self, column: usize) -> Vec<Option<A::Elements>> {
+        self.rows.iter().map(|r| r.get(column)).collect()
+    }
+
+    /// Returns an iterator over all columns.
+    pub fn iter_columns(&self) -> Columns<'_, A> {
+        Columns {
+            rows: self.rows.iter().map(|r| r.iter()).collect(),
+        }
+    }
+
+    /// Returns the most frequent residue of every column, or a gap where
+    /// gaps outnumber it. Ties go to the symbol that comes first in the
+    /// alphabet.
+    pub fn consensus(&self) -> GappedSeq<A> {
+        self.iter_columns()
+            .map(|column| {
+                let counts = symbol_counts::<A>(&column);
+                let (best, &count) = counts
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .max_by_key(|&(_, count)| count)
+                    .unwrap();
+                let gaps = column.len() - counts.iter().sum::<usize>();
+                (count > 0 && count >= gaps).then(|| A::ELEMENTS[best])
+            })
+            .collect()
+    }
+
+    /// Returns the fraction of rows sharing the most frequent residue, per
+    /// column. Gaps count as rows but never as the conserved residue.
+    pub fn conservation(&self) -> Vec<f64> {
+        self.iter_columns()
+            .map(|column| {
+                let top = symbol_counts::<A>(&column).into_iter().max().unwrap_or(0);
+                if column.is_empty() {
+                    0.0
+                } else {
+                    top as f64 / column.len() as f64
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the Shannon entropy (in bits) of the residues in each column,
+    /// ignoring gaps.
+    pub fn entropy(&self) -> Vec<f64> {
+        self.iter_columns()
+            .map(|column| {
+                let counts = symbol_counts::<A>(&column);
+                let total = counts.iter().sum::<usize>() as f64;
+                let sum = counts
+                    .iter()
+                    .filter(|&&c| c > 0)
+                    .map(|&c| {
+                        let p = c as f64 / total;
+                        p * p.log2()
+                    })
+                    .sum::<f64>();
+                // Negating an empty or certain column would give -0.0
+                if sum == 0.0 {
+                    0.0
+                } else {
+                    -sum
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the fraction of gaps per column.
+    pub fn gap_fraction(&self) -> Vec<f64> {
+        self.iter_columns()
+            .map(|column| {
+                let gaps = column.iter().filter(|s| s.is_none()).count();
+                gaps as f64 / column.len() as f64
+            })
+            .collect()
+    }
+
+    /// Keeps only the columns for which `keep(index, column)` returns true.
+    pub fn filter_columns<F>(&self, mut keep: F) -> Self
+    where
+        F: FnMut(usize, &[Option<A::Elements>]) -> bool,
+    {
+        let mut kept: Vec<Vec<Option<A::Elements>>> = vec![Vec::new(); self.len()];
+        for (index, column) in self.iter_columns().enumerate() {
+            if keep(index, &column) {
+                for (row, symbol) in kept.iter_mut().zip(column) {
+                    row.push(symbol);
+                }
+            }
+        }
+
+        Self {
+            names: self.names.clone(),
+            rows: kept.into_iter().map(GappedSeq::from_iter).collect(),
+        }
+    }
+
+    /// Drops columns with more than `max_fraction` gaps.
+    pub fn remove_gappy_columns(&self, max_fraction: f64) -> Self {
+        let gaps = self.gap_fraction();
+        self.filter_columns(|index, _| gaps[index] <= max_fraction)
+    }
+}
+
+/// Counts residues per packed symbol, ignoring gaps.
+fn symbol_counts<A: Alphabet>(column: &[Option<A::Elements>]) -> Vec<usize> {
+    let mut counts = vec![0; A::SIZE as usize];
+    for &symbol in column.iter().flatten() {
+        let bits: u8 = symbol.into();
+        counts[bits as usize] += 1;
+    }
+    counts
+}
+
+pub struct Columns<'a, A: Alphabet> {
+    rows: Vec<GappedIter<'a, A>>,
+}
+
+impl<'a, A: Alphabet> Iterator for Columns<'a, A> {
+    type Item = Vec<Option<A::Elements>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rows.is_empty() {
+            return None;
+        }
+        self.rows.iter_mut().map(|r| r.next()).collect()
+    }
+}
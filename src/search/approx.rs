@@ -0,0 +1,168 @@
+use crate::alphabet::{Alphabet, Nucleotide, IUPAC_TO_MASK};
+use crate::seq::{Seq, SeqError};
+
+/// Width of one block of the bit-parallel DP column.
+const WORD: usize = 64;
+
+/// An approximate occurrence of a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApproxMatch {
+    /// Exclusive end position of the match in the text.
+    pub end: usize,
+    /// Edit distance between the pattern and the best match ending at `end`.
+    pub distance: usize,
+}
+
+impl<A: Alphabet> Seq<A> {
+    /// Returns an iterator over all text positions where `pattern` ends with
+    /// at most `max_edits` substitutions, insertions or deletions.
+    ///
+    /// Uses Myers' bit-vector algorithm; patterns longer than 64 symbols are
+    /// split into 64-symbol blocks. Every qualifying end position is
+    /// reported, so neighbouring ends of the same hit appear together.
+    pub fn find_approx(&self, pattern: &Seq<A>, max_edits: usize) -> FindApprox<'_, A> {
+        let blocks = pattern.len().div_ceil(WORD);
+        let mut peq = vec![0u64; (1 << A::BITS) * blocks];
+        for i in 0..pattern.len() {
+            let symbol = pattern.get_bits(i) as usize;
+            peq[symbol * blocks + i / WORD] |= 1 << (i % WORD);
+        }
+        FindApprox::new(self, peq, pattern.len(), max_edits)
+    }
+}
+
+impl<A: Alphabet<Elements = Nucleotide>> Seq<A> {
+    /// Like [`Seq::find_approx`], but `pattern` is an IUPAC string whose
+    /// degenerate codes (`R`, `Y`, `N`, ...) match any of the bases they
+    /// stand for. A text `N` only matches a pattern `N`.
+    pub fn find_approx_iupac(
+        &self,
+        pattern: &str,
+        max_edits: usize,
+    ) -> Result<FindApprox<'_, A>, SeqError> {
+        let pattern = pattern.as_bytes();
+        let blocks = pattern.len().div_ceil(WORD);
+        let mut peq = vec![0u64; (1 << A::BITS) * blocks];
+        for (i, &code) in pattern.iter().enumerate() {
+            let mask = IUPAC_TO_MASK[code as usize];
+            if mask == 0 {
+                return Err(SeqError::InvalidSymbol);
+            }
+            for (symbol, &elem) in A::ELEMENTS.iter().enumerate() {
+                let matches = match elem {
+                    Nucleotide::N => mask == 0b1111,
+                    base => mask & (1 << base as u8) != 0,
+                };
+                if matches {
+                    peq[symbol * blocks + i / WORD] |= 1 << (i % WORD);
+                }
+            }
+        }
+        Ok(FindApprox::new(self, peq, pattern.len(), max_edits))
+    }
+}
+
+/// Advances one 64-row block of the DP column by one text symbol.
+///
+/// `eq` is the match mask of the block for the text symbol, `hin` the
+/// horizontal delta (-1, 0, +1) entering the block's top row. Returns the
+/// horizontal delta leaving the row selected by `out_bit`.
+#[inline(always)]
+pub(crate) fn advance_block(pv: &mut u64, mv: &mut u64, eq: u64, hin: i8, out_bit: u64) -> i8 {
+    let hin_neg = (hin < 0) as u64;
+    let hin_pos = (hin > 0) as u64;
+
+    let xv = eq | *mv;
+    let eq = eq | hin_neg;
+    let xh = (((eq & *pv).wrapping_add(*pv)) ^ *pv) | eq;
+    let mut ph = *mv | !(xh | *pv);
+    let mut mh = *pv & xh;
+
+    let hout = if ph & out_bit != 0 {
+        1
+    } else if mh & out_bit != 0 {
+        -1
+    } else {
+        0
+    };
+
+    ph = (ph << 1) | hin_pos;
+    mh = (mh << 1) | hin_neg;
+    *pv = mh | !(xv | ph);
+    *mv = ph & xv;
+    hout
+}
+
+pub struct FindApprox<'a, A: Alphabet> {
+    text: &'a Seq<A>,
+    /// Match masks indexed by `symbol * blocks + block`.
+    peq: Vec<u64>,
+    blocks: usize,
+    pv: Vec<u64>,
+    mv: Vec<u64>,
+    /// Edit distance at the last pattern row for the current column.
+    score: usize,
+    max_edits: usize,
+    pos: usize,
+    /// Bit of the last block holding the last pattern row.
+    last_bit: u64,
+}
+
+impl<'a, A: Alphabet> FindApprox<'a, A> {
+    fn new(text: &'a Seq<A>, peq: Vec<u64>, len: usize, max_edits: usize) -> Self {
+        let blocks = len.div_ceil(WORD);
+        Self {
+            text,
+            peq,
+            blocks,
+            pv: vec![!0; blocks],
+            mv: vec![0; blocks],
+            score: len,
+            max_edits,
+            pos: 0,
+            last_bit: 1 << ((len + WORD - 1) % WORD),
+        }
+    }
+}
+
+impl<'a, A: Alphabet> Iterator for FindApprox<'a, A> {
+    type Item = ApproxMatch;
+
+    fn next(&mut self) -> Option<ApproxMatch> {
+        // An empty pattern matches with distance 0 at every position
+        if self.blocks == 0 {
+            let end = self.pos;
+            self.pos += 1;
+            return (end <= self.text.len()).then_some(ApproxMatch { end, distance: 0 });
+        }
+
+        while self.pos < self.text.len() {
+            let symbol = self.text.get_bits(self.pos) as usize;
+            let eq = &self.peq[symbol * self.blocks..(symbol + 1) * self.blocks];
+            self.pos += 1;
+
+            // The top row is all zeros in search mode, so nothing enters block 0
+            let mut hin = 0;
+            let last = self.blocks - 1;
+            for b in 0..last {
+                hin = advance_block(&mut self.pv[b], &mut self.mv[b], eq[b], hin, 1 << 63);
+            }
+            let hout = advance_block(
+                &mut self.pv[last],
+                &mut self.mv[last],
+                eq[last],
+                hin,
+                self.last_bit,
+            );
+            self.score = self.score.wrapping_add_signed(hout as isize);
+
+            if self.score <= self.max_edits {
+                return Some(ApproxMatch {
+                    end: self.pos,
+                    distance: self.score,
+                });
+            }
+        }
+        None
+    }
+}
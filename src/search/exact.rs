@@ -0,0 +1,234 @@
+use std::iter::Peekable;
+
+use crate::alphabet::{Alphabet, Nucleotide, Strand};
+use crate::seq::Seq;
+
+/// Longest pattern handled by the bit-parallel matchers (one machine word).
+const WORD: usize = 64;
+
+/// Patterns shorter than this use Shift-And; BNDM's window skipping needs
+/// a few symbols before it pays off.
+const BNDM_MIN_LEN: usize = 4;
+
+impl<A: Alphabet> Seq<A> {
+    /// Returns an iterator over the start positions of all (possibly
+    /// overlapping) occurrences of `pattern`.
+    ///
+    /// Patterns of up to 64 symbols use bit-parallel matching (Shift-And for
+    /// very short patterns, BNDM otherwise); longer patterns are compared
+    /// against the packed text byte by byte. An empty pattern matches at
+    /// every position, including the end.
+    pub fn find_iter(&self, pattern: &Seq<A>) -> FindIter<'_, A> {
+        let len = pattern.len();
+        let matcher = if len == 0 {
+            Matcher::Empty
+        } else if len < BNDM_MIN_LEN {
+            Matcher::ShiftAnd {
+                masks: symbol_masks(pattern, false),
+                accept: 1 << (len - 1),
+                state: 0,
+            }
+        } else if len <= WORD {
+            Matcher::Bndm {
+                masks: symbol_masks(pattern, true),
+            }
+        } else {
+            Matcher::Packed {
+                phases: (0..Seq::<A>::SYMBOLS_PER_BYTE)
+                    .map(|phase| PhasePattern::new(pattern, phase))
+                    .collect(),
+            }
+        };
+
+        FindIter {
+            text: self,
+            len,
+            matcher,
+            pos: 0,
+        }
+    }
+}
+
+impl<A: Alphabet<Elements = Nucleotide>> Seq<A> {
+    /// Returns an iterator over occurrences of `pattern` on either strand.
+    ///
+    /// Reverse-strand hits are occurrences of the reverse complement of
+    /// `pattern`, reported in forward coordinates. Results are ordered by
+    /// position, forward strand first on ties.
+    pub fn find_iter_both_strands(&self, pattern: &Seq<A>) -> BothStrands<'_, A> {
+        BothStrands {
+            forward: self.find_iter(pattern).peekable(),
+            reverse: self.find_iter(&pattern.reverse_complement()).peekable(),
+        }
+    }
+}
+
+/// Builds per-symbol match masks: bit `i` of `masks[c]` is set if the
+/// pattern has symbol `c` at position `i` (or `len - 1 - i` if `reversed`).
+fn symbol_masks<A: Alphabet>(pattern: &Seq<A>, reversed: bool) -> Vec<u64> {
+    let len = pattern.len();
+    let mut masks = vec![0u64; 1 << A::BITS];
+    for i in 0..len {
+        let bit = if reversed { len - 1 - i } else { i };
+        masks[pattern.get_bits(i) as usize] |= 1 << bit;
+    }
+    masks
+}
+
+/// A long pattern re-packed to start at a given symbol offset within a byte.
+struct PhasePattern {
+    bytes: Vec<u8>,
+    first_mask: u8,
+    last_mask: u8,
+}
+
+impl PhasePattern {
+    fn new<A: Alphabet>(pattern: &Seq<A>, phase: usize) -> Self {
+        let spb = Seq::<A>::SYMBOLS_PER_BYTE;
+        let mut shifted = Seq::<A>::new(phase + pattern.len());
+        let mut used = Seq::<A>::new(phase + pattern.len());
+        for i in 0..pattern.len() {
+            shifted.init_with(phase + i, pattern.get_bits(i));
+            used.init_with(phase + i, Seq::<A>::MASK);
+        }
+        debug_assert!(shifted.data.len() > 1 && phase < spb);
+
+        Self {
+            first_mask: used.data[0],
+            last_mask: used.data[used.data.len() - 1],
+            bytes: shifted.data,
+        }
+    }
+
+    /// Checks whether the pattern occurs at `pos`, whose phase this is.
+    #[inline]
+    fn matches<A: Alphabet>(&self, text: &Seq<A>, pos: usize) -> bool {
+        let start = pos / Seq::<A>::SYMBOLS_PER_BYTE;
+        let window = &text.data[start..start + self.bytes.len()];
+        let last = self.bytes.len() - 1;
+
+        (window[0] ^ self.bytes[0]) & self.first_mask == 0
+            && (window[last] ^ self.bytes[last]) & self.last_mask == 0
+            && window[1..last] == self.bytes[1..last]
+    }
+}
+
+enum Matcher {
+    Empty,
+    ShiftAnd {
+        masks: Vec<u64>,
+        accept: u64,
+        state: u64,
+    },
+    Bndm {
+        masks: Vec<u64>,
+    },
+    Packed {
+        phases: Vec<PhasePattern>,
+    },
+}
+
+// -- Iterators ---------------------------------------------------------------
+
+pub struct FindIter<'a, A: Alphabet> {
+    text: &'a Seq<A>,
+    len: usize,
+    matcher: Matcher,
+    pos: usize,
+}
+
+impl<'a, A: Alphabet> Iterator for FindIter<'a, A> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let text = self.text;
+        let n = text.len();
+        let m = self.len;
+
+        match &mut self.matcher {
+            Matcher::Empty => {
+                let pos = self.pos;
+                self.pos += 1;
+                (pos <= n).then_some(pos)
+            }
+            Matcher::ShiftAnd {
+                masks,
+                accept,
+                state,
+            } => {
+                // `pos` is the next text symbol to feed
+                while self.pos < n {
+                    let symbol = text.get_bits(self.pos) as usize;
+                    *state = ((*state << 1) | 1) & masks[symbol];
+                    self.pos += 1;
+                    if *state & *accept != 0 {
+                        return Some(self.pos - m);
+                    }
+                }
+                None
+            }
+            Matcher::Bndm { masks } => {
+                // `pos` is the start of the current window
+                let window_mask = if m == WORD { !0 } else { (1u64 << m) - 1 };
+                let accept = 1u64 << (m - 1);
+                while self.pos + m <= n {
+                    let start = self.pos;
+                    let mut j = m;
+                    let mut last = m;
+                    let mut state = window_mask;
+                    let mut found = false;
+
+                    // Scan the window right to left; `state` tracks which
+                    // pattern factors still match the suffix read so far
+                    while state != 0 {
+                        state &= masks[text.get_bits(start + j - 1) as usize];
+                        j -= 1;
+                        if state & accept != 0 {
+                            if j > 0 {
+                                last = j;
+                            } else {
+                                found = true;
+                            }
+                        }
+                        state = (state << 1) & window_mask;
+                    }
+
+                    self.pos += last;
+                    if found {
+                        return Some(start);
+                    }
+                }
+                None
+            }
+            Matcher::Packed { phases } => {
+                let spb = Seq::<A>::SYMBOLS_PER_BYTE;
+                while self.pos + m <= n {
+                    let pos = self.pos;
+                    self.pos += 1;
+                    if phases[pos % spb].matches(text, pos) {
+                        return Some(pos);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Merges forward and reverse-strand matches by position.
+pub struct BothStrands<'a, A: Alphabet<Elements = Nucleotide>> {
+    forward: Peekable<FindIter<'a, A>>,
+    reverse: Peekable<FindIter<'a, A>>,
+}
+
+impl<'a, A: Alphabet<Elements = Nucleotide>> Iterator for BothStrands<'a, A> {
+    type Item = (usize, Strand);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.forward.peek(), self.reverse.peek()) {
+            (Some(&f), Some(&r)) if r < f => self.reverse.next().map(|p| (p, Strand::Reverse)),
+            (Some(_), _) => self.forward.next().map(|p| (p, Strand::Forward)),
+            (None, _) => self.reverse.next().map(|p| (p, Strand::Reverse)),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod approx;
+mod exact;
+
+pub use approx::*;
+pub use exact::*;
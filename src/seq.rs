@@ -1,6 +1,7 @@
 use std::fmt;
+use std::ops::{Bound, Range, RangeBounds};
 
-use crate::alphabet::{Alphabet, Promote};
+use crate::alphabet::{Alphabet, Nucleotide, Promote};
 
 #[derive(Debug, PartialEq)]
 pub enum SeqError {
@@ -24,10 +25,10 @@ where
 
 impl<A: Alphabet> Seq<A> {
     /// Symbols that fit in a single byte.
-    const SYMBOLS_PER_BYTE: usize = 8 / A::BITS as usize;
+    pub(crate) const SYMBOLS_PER_BYTE: usize = 8 / A::BITS as usize;
 
     /// Bitmask for a single symbol.
-    const MASK: u8 = (1 << A::BITS) - 1;
+    pub(crate) const MASK: u8 = (1 << A::BITS) - 1;
 
     /// Creates a new sequence with the given length.
     ///
@@ -104,6 +105,24 @@ impl<A: Alphabet> Seq<A> {
         unsafe { *self.data.get_unchecked_mut(block) |= bits << bit };
     }
 
+    /// Overwrites the symbol at the given index (0-based).
+    #[inline(always)]
+    pub(crate) fn set_bits(&mut self, index: usize, bits: u8) {
+        let (block, bit) = self.address(index);
+        self.data[block] = (self.data[block] & !(Self::MASK << bit)) | (bits << bit);
+    }
+
+    /// Zeroes the unused bits following the last symbol.
+    pub(crate) fn clear_padding(&mut self) {
+        let used = self.length % Self::SYMBOLS_PER_BYTE;
+        if used > 0 {
+            let keep = !0u8 << ((Self::SYMBOLS_PER_BYTE - used) * A::BITS as usize);
+            if let Some(last) = self.data.last_mut() {
+                *last &= keep;
+            }
+        }
+    }
+
     /// Returns the raw bit value at the given index.
     #[inline(always)]
     pub fn get_bits(&self, index: usize) -> u8 {
@@ -171,6 +190,30 @@ impl<A: Alphabet> Seq<A> {
         self.data.truncate(Self::bytes_to_store(size));
     }
 
+    /// Returns a new sequence holding the symbols in `range`.
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let Range { start, end } = resolve_range(range, self.length);
+        let mut result = Self::new(end - start);
+
+        // Fast path: memcpy when the slice starts on a byte boundary
+        if start.is_multiple_of(Self::SYMBOLS_PER_BYTE) {
+            let first = start / Self::SYMBOLS_PER_BYTE;
+            let count = result.data.len();
+            result
+                .data
+                .copy_from_slice(&self.data[first..first + count]);
+            result.clear_padding();
+        } else {
+            for i in start..end {
+                result.init_with(i - start, self.get_bits(i));
+            }
+        }
+
+        result
+    }
+
     /// Computes the number of bytes needed to store `length` symbols.
     pub fn bytes_to_store(length: usize) -> usize {
         length.div_ceil(Self::SYMBOLS_PER_BYTE)
@@ -182,6 +225,50 @@ impl<A: Alphabet> Seq<A> {
     }
 }
 
+impl<A: Alphabet<Elements = Nucleotide>> Seq<A> {
+    /// Returns the reverse complement of a nucleotide sequence.
+    pub fn reverse_complement(&self) -> Self {
+        let mut result = Self::new(self.length);
+        for (i, elem) in self.iter().enumerate() {
+            result.init_with(self.length - 1 - i, elem.complement().into());
+        }
+        result
+    }
+}
+
+/// Resolves `range` against a sequence of length `len`.
+///
+/// Panics if the range is out of bounds.
+pub(crate) fn resolve_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let (start, end) = bounds(&range, len);
+    assert!(
+        start <= end && end <= len,
+        "range {start}..{end} out of bounds for sequence of length {len}"
+    );
+    start..end
+}
+
+/// Resolves `range` against a sequence of length `len`, returning `None` if
+/// it is out of bounds.
+pub(crate) fn checked_range<R: RangeBounds<usize>>(range: R, len: usize) -> Option<Range<usize>> {
+    let (start, end) = bounds(&range, len);
+    (start <= end && end <= len).then_some(start..end)
+}
+
+fn bounds<R: RangeBounds<usize>>(range: &R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e.saturating_add(1),
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    (start, end)
+}
+
 /// Compile-time assert that two types are equal. Optimized away entirely.
 fn type_assert_eq<T, U>()
 where
@@ -328,6 +415,16 @@ impl<A: Alphabet> fmt::Display for Seq<A> {
 
 // -- Trait impls -------------------------------------------------------------
 
+impl<A: Alphabet> Clone for Seq<A> {
+    fn clone(&self) -> Self {
+        Self {
+            length: self.length,
+            data: self.data.clone(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
 impl<A: Alphabet> PartialEq for Seq<A> {
     fn eq(&self, other: &Self) -> bool {
         self.data == other.data && self.length == other.length
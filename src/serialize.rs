@@ -0,0 +1,228 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::alphabet::{Alphabet, AminoAcid, Nuc5, Nucleotide, AA20};
+use crate::seq::Seq;
+
+// -- Seq ---------------------------------------------------------------------
+//
+// Human-readable formats get the ASCII string; binary formats get the length
+// followed by the packed bytes.
+
+/// Serializes a byte slice with `serialize_bytes` instead of as a sequence.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Accepts packed bytes either as a byte string or as a sequence of `u8`.
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("packed sequence bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v))
+            }
+
+            fn visit_seq<V: SeqAccess<'de>>(self, mut seq: V) -> Result<ByteBuf, V::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(b) = seq.next_element()? {
+                    bytes.push(b);
+                }
+                Ok(ByteBuf(bytes))
+            }
+        }
+
+        deserializer.deserialize_bytes(ByteBufVisitor)
+    }
+}
+
+/// Returns the alphabet's type name without its module path, for messages.
+fn alphabet_name<A: Alphabet>() -> &'static str {
+    let name = std::any::type_name::<A>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+impl<A: Alphabet> Serialize for Seq<A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            let mut tuple = serializer.serialize_tuple(2)?;
+            tuple.serialize_element(&(self.len() as u64))?;
+            tuple.serialize_element(&Bytes(self.as_bytes()))?;
+            tuple.end()
+        }
+    }
+}
+
+impl<'de, A: Alphabet> Deserialize<'de> for Seq<A> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SeqVisitor<A>(PhantomData<A>);
+
+        impl<'de, A: Alphabet> Visitor<'de> for SeqVisitor<A> {
+            type Value = Seq<A>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a {} sequence", alphabet_name::<A>())
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Seq<A>, E> {
+                Seq::try_from(v).map_err(|_| {
+                    E::custom(format!(
+                        "invalid {} symbol in sequence",
+                        alphabet_name::<A>()
+                    ))
+                })
+            }
+
+            fn visit_seq<V: SeqAccess<'de>>(self, mut seq: V) -> Result<Seq<A>, V::Error> {
+                let length: u64 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let ByteBuf(data) = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+                let length = length as usize;
+                if data.len() != Seq::<A>::bytes_to_store(length) {
+                    return Err(de::Error::custom("packed data does not match length"));
+                }
+                let mut decoded = Seq::<A>::from_bytes(&data);
+                decoded.trim(length);
+                // Repacks the symbols so unused bits are known to be zero
+                let mut packed = Seq::<A>::new(length);
+                for i in 0..length {
+                    let bits = decoded.get_bits(i);
+                    if bits >= A::SIZE {
+                        return Err(de::Error::custom(format!(
+                            "invalid {} symbol in packed data",
+                            alphabet_name::<A>()
+                        )));
+                    }
+                    packed.init_with(i, bits);
+                }
+                if packed.as_bytes() != decoded.as_bytes() {
+                    return Err(de::Error::custom("packed data has padding bits set"));
+                }
+                Ok(packed)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(SeqVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_tuple(2, SeqVisitor(PhantomData))
+        }
+    }
+}
+
+// -- Elements ----------------------------------------------------------------
+//
+// Elements are single-character strings in human-readable formats and their
+// discriminant (the bit encoding) in binary formats.
+
+fn serialize_element<A, S>(elem: A::Elements, serializer: S) -> Result<S::Ok, S::Error>
+where
+    A: Alphabet,
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.serialize_char(A::to_byte(elem) as char)
+    } else {
+        serializer.serialize_u8(elem.into())
+    }
+}
+
+fn deserialize_element<'de, A, D>(deserializer: D) -> Result<A::Elements, D::Error>
+where
+    A: Alphabet,
+    D: Deserializer<'de>,
+{
+    struct ElementVisitor<A>(PhantomData<A>);
+
+    impl<'de, A: Alphabet> Visitor<'de> for ElementVisitor<A> {
+        type Value = A::Elements;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a {} symbol", alphabet_name::<A>())
+        }
+
+        fn visit_char<E: de::Error>(self, v: char) -> Result<A::Elements, E> {
+            let bits = if v.is_ascii() {
+                A::BYTE_TO_BITS[v as usize]
+            } else {
+                0xFF
+            };
+            A::ELEMENTS
+                .get(bits as usize)
+                .copied()
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Char(v), &self))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<A::Elements, E> {
+            let mut chars = v.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => self.visit_char(c),
+                _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+            }
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<A::Elements, E> {
+            A::ELEMENTS
+                .get(v as usize)
+                .copied()
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+        }
+    }
+
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_char(ElementVisitor::<A>(PhantomData))
+    } else {
+        deserializer.deserialize_u8(ElementVisitor::<A>(PhantomData))
+    }
+}
+
+impl Serialize for Nucleotide {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_element::<Nuc5, S>(*self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Nucleotide {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_element::<Nuc5, D>(deserializer)
+    }
+}
+
+impl Serialize for AminoAcid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_element::<AA20, S>(*self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AminoAcid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_element::<AA20, D>(deserializer)
+    }
+}
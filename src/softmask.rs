@@ -0,0 +1,161 @@
+use std::ops::{Range, RangeBounds};
+
+use crate::bitvec::BitVecBuilder;
+use crate::seq::resolve_range;
+
+/// Case mask for a soft-masked sequence.
+///
+/// One bit per position; a set bit means the symbol was lowercase in the
+/// source (e.g. a repeat flagged by RepeatMasker). The mask is carried next to
+/// a `Seq`, which itself is always case-insensitive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaseMask {
+    bits: BitVecBuilder,
+}
+
+impl CaseMask {
+    /// Creates an all-uppercase mask for `len` positions.
+    pub fn new(len: usize) -> Self {
+        Self {
+            bits: BitVecBuilder::new(len),
+        }
+    }
+
+    /// Builds a mask from ASCII symbols, marking every lowercase byte.
+    pub fn from_ascii(ascii: &[u8]) -> Self {
+        let mut mask = Self::new(0);
+        mask.extend_from_ascii(ascii);
+        mask
+    }
+
+    /// Builds a mask of length `len` with the given intervals set.
+    ///
+    /// Panics if an interval extends past `len`.
+    pub fn from_intervals<I>(len: usize, intervals: I) -> Self
+    where
+        I: IntoIterator<Item = Range<usize>>,
+    {
+        let mut mask = Self::new(len);
+        for range in intervals {
+            mask.set_range(range);
+        }
+        mask
+    }
+
+    /// Appends the case of `ascii` to the end of the mask.
+    pub fn extend_from_ascii(&mut self, ascii: &[u8]) {
+        let start = self.len();
+        self.bits.resize(start + ascii.len());
+        for (i, b) in ascii.iter().enumerate() {
+            if b.is_ascii_lowercase() {
+                self.set(start + i);
+            }
+        }
+    }
+
+    /// Access the number of positions covered by the mask.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Checks if the mask covers no positions.
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Checks whether the symbol at `index` is soft-masked.
+    #[inline]
+    pub fn is_masked(&self, index: usize) -> bool {
+        self.bits.get(index)
+    }
+
+    /// Marks the symbol at `index` as soft-masked.
+    #[inline]
+    pub fn set(&mut self, index: usize) {
+        self.bits.set(index, true);
+    }
+
+    /// Marks every symbol in `range` as soft-masked.
+    pub fn set_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        for i in resolve_range(range, self.len()) {
+            self.bits.set(i, true);
+        }
+    }
+
+    /// Returns the number of soft-masked positions.
+    pub fn count_masked(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    /// Returns a new mask covering `range`.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let Range { start, end } = resolve_range(range, self.len());
+        let mut mask = Self::new(end - start);
+        for interval in self.intervals() {
+            if interval.end <= start || interval.start >= end {
+                continue;
+            }
+            mask.set_range(interval.start.max(start) - start..interval.end.min(end) - start);
+        }
+        mask
+    }
+
+    /// Returns an iterator over the maximal soft-masked intervals.
+    pub fn intervals(&self) -> Intervals<'_> {
+        Intervals { mask: self, pos: 0 }
+    }
+
+    /// Lowercases every soft-masked byte of `ascii`.
+    ///
+    /// Panics if `ascii` and the mask differ in length.
+    pub fn apply(&self, ascii: &mut [u8]) {
+        assert_eq!(
+            ascii.len(),
+            self.len(),
+            "mask of length {} applied to {} bytes",
+            self.len(),
+            ascii.len()
+        );
+        for interval in self.intervals() {
+            ascii[interval].make_ascii_lowercase();
+        }
+    }
+
+    /// Finds the first position `>= from` whose bit equals `value`.
+    fn next_with(&self, from: usize, value: bool) -> Option<usize> {
+        if from >= self.len() {
+            return None;
+        }
+        let words = self.bits.as_words();
+        let flip = if value { 0 } else { !0 };
+        let mut idx = from / 64;
+        let mut word = (words[idx] ^ flip) & (!0u64 << (from % 64));
+        while word == 0 {
+            idx += 1;
+            if idx == words.len() {
+                return None;
+            }
+            word = words[idx] ^ flip;
+        }
+        let pos = idx * 64 + word.trailing_zeros() as usize;
+        (pos < self.len()).then_some(pos)
+    }
+}
+
+// -- Iterator ----------------------------------------------------------------
+
+pub struct Intervals<'a> {
+    mask: &'a CaseMask,
+    pos: usize,
+}
+
+impl<'a> Iterator for Intervals<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.mask.next_with(self.pos, true)?;
+        let end = self.mask.next_with(start, false).unwrap_or(self.mask.len());
+        self.pos = end;
+        Some(start..end)
+    }
+}
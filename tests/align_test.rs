@@ -0,0 +1,486 @@
+use nuc::{
+    align::{
+        Aligner, Alignment, AlignmentMode, Cigar, CigarError, CigarOp, MatchMismatch, MatrixError,
+        NucleotideMatrix, ProteinMatrix, QueryProfile, Scoring, SubstitutionMatrix, BLOSUM45,
+        BLOSUM50, BLOSUM62, BLOSUM80, BLOSUM90, EDNAFULL, PAM250, PAM30, PAM70,
+    },
+    alphabet::{Alphabet, AminoAcid, Nuc4, Nuc5, Nucleotide, AA20},
+    distance::EditOp,
+    seq::Seq,
+};
+
+/// Recomputes the score of an alignment from its CIGAR.
+fn rescore<A: Alphabet, M: SubstitutionMatrix<A>>(
+    aln: &Alignment,
+    x: &Seq<A>,
+    y: &Seq<A>,
+    sc: &Scoring<M>,
+) -> i32 {
+    let (mut i, mut j) = (aln.x_start, aln.y_start);
+    let mut score = 0;
+    for &(op, n) in aln.cigar.ops() {
+        match op {
+            EditOp::Match | EditOp::Mismatch => {
+                for _ in 0..n {
+                    let same = x.get_bits(i) == y.get_bits(j);
+                    assert_eq!(same, op == EditOp::Match);
+                    score += sc.matrix.score_bits(x.get_bits(i), y.get_bits(j));
+                    i += 1;
+                    j += 1;
+                }
+            }
+            EditOp::Insertion => {
+                score += sc.gap(n);
+                i += n;
+            }
+            EditOp::Deletion => {
+                score += sc.gap(n);
+                j += n;
+            }
+        }
+    }
+    assert_eq!((i, j), (aln.x_end, aln.y_end));
+    score
+}
+
+/// Best score by enumerating all end cells of a plain cubic-time DP.
+fn naive_score(x: &[u8], y: &[u8], sc: &Scoring, mode: AlignmentMode) -> i32 {
+    let (m, n) = (x.len(), y.len());
+    let free_y = mode != AlignmentMode::Global;
+    let free_x = matches!(mode, AlignmentMode::Local | AlignmentMode::Overlap);
+    let mut h = vec![vec![i32::MIN / 4; n + 1]; m + 1];
+    for i in 0..=m {
+        for j in 0..=n {
+            let mut best = i32::MIN / 4;
+            if i == 0 && j == 0 {
+                best = 0;
+            }
+            if i == 0 && j > 0 {
+                best = if free_y { 0 } else { sc.gap(j) };
+            }
+            if j == 0 && i > 0 {
+                best = if free_x { 0 } else { sc.gap(i) };
+            }
+            if mode == AlignmentMode::Local {
+                best = best.max(0);
+            }
+            if i > 0 && j > 0 {
+                let s = if x[i - 1] == y[j - 1] {
+                    sc.matrix.match_score
+                } else {
+                    sc.matrix.mismatch_score
+                };
+                best = best.max(h[i - 1][j - 1] + s);
+            }
+            for k in 1..=i {
+                best = best.max(h[i - k][j] + sc.gap(k));
+            }
+            for k in 1..=j {
+                best = best.max(h[i][j - k] + sc.gap(k));
+            }
+            h[i][j] = best;
+        }
+    }
+    match mode {
+        AlignmentMode::Global => h[m][n],
+        AlignmentMode::Local => h.iter().flatten().copied().max().unwrap(),
+        AlignmentMode::SemiGlobal => h[m].iter().copied().max().unwrap(),
+        AlignmentMode::Overlap => h[m]
+            .iter()
+            .copied()
+            .chain(h.iter().map(|row| row[n]))
+            .max()
+            .unwrap(),
+    }
+}
+
+fn nuc4(s: &str) -> Seq<Nuc4> {
+    Seq::try_from(s).unwrap()
+}
+
+#[test]
+fn global_alignment_opens_a_single_gap() {
+    // Equally good gap positions resolve to the leftmost one
+    let x = nuc4("ACGTACGTTTTACGT");
+    let y = nuc4("ACGTACGTACGT");
+    let aln = Aligner::global(Scoring::new(2, -3, -5, -1)).align(&x, &y);
+    assert_eq!(aln.cigar.to_string(), "7=3I5=");
+    assert_eq!(aln.score, 12 * 2 - 8);
+    assert_eq!(
+        (aln.x_start, aln.x_end, aln.y_start, aln.y_end),
+        (0, 15, 0, 12)
+    );
+}
+
+#[test]
+fn local_alignment_finds_shared_core() {
+    let x = nuc4("TTTTTGATTACATTTTT");
+    let y = nuc4("CCCGATTACACCC");
+    let aln = Aligner::local(Scoring::default()).align(&x, &y);
+    assert_eq!(aln.score, 7);
+    assert_eq!((aln.x_start, aln.x_end), (5, 12));
+    assert_eq!((aln.y_start, aln.y_end), (3, 10));
+    assert_eq!(aln.identity(), 1.0);
+}
+
+#[test]
+fn semi_global_places_query_inside_reference() {
+    let x = nuc4("GATTACA");
+    let y = nuc4("CCCCCGATTTACACCCCC");
+    let aln = Aligner::semi_global(Scoring::new(1, -1, -2, -1)).align(&x, &y);
+    assert_eq!((aln.x_start, aln.x_end), (0, 7));
+    assert_eq!((aln.y_start, aln.y_end), (5, 13));
+    assert_eq!(aln.cigar.to_string(), "2=1D5=");
+}
+
+#[test]
+fn overlap_joins_suffix_and_prefix() {
+    let x = nuc4("AAAAAAAACGTACGT");
+    let y = nuc4("ACGTACGTCCCCCCC");
+    let aln = Aligner::overlap(Scoring::default()).align(&x, &y);
+    assert_eq!(aln.score, 8);
+    assert_eq!(
+        (aln.x_start, aln.x_end, aln.y_start, aln.y_end),
+        (7, 15, 0, 8)
+    );
+}
+
+#[test]
+fn pretty_prints_blocks() {
+    let x = nuc4("ACGTTACGT");
+    let y = nuc4("ACGACCT");
+    let aln = Aligner::global(Scoring::new(1, -1, -1, -1)).align(&x, &y);
+    assert_eq!(aln.cigar.to_string(), "3=2I2=1X1=");
+    assert_eq!(
+        aln.pretty(&x, &y),
+        "x 1 ACGTTACGT 9\n  \
+         \x20 |||  ||.|\n\
+         y 1 ACG--ACCT 7\n"
+    );
+}
+
+#[test]
+fn empty_sequences_align() {
+    let x = nuc4("");
+    let y = nuc4("ACG");
+    let sc = Scoring::default();
+    assert_eq!(Aligner::global(sc).align(&x, &y).score, sc.gap(3));
+    assert_eq!(Aligner::global(sc).align(&x, &y).cigar.to_string(), "3D");
+    assert!(Aligner::local(sc).align(&x, &y).is_empty());
+}
+
+// -- Substitution matrices --
+
+const NCBI_BLOSUM62: &str = r"#  Matrix made by matblas from blosum62.iij
+#  * column uses minimum score
+#  BLOSUM Clustered Scoring Matrix in 1/2 Bit Units
+#  Blocks Database = /data/blocks_5.0/blocks.dat
+#  Cluster Percentage: >= 62
+#  Entropy =   0.6979, Expected =  -0.5209
+   A  R  N  D  C  Q  E  G  H  I  L  K  M  F  P  S  T  W  Y  V  B  Z  X  *
+A  4 -1 -2 -2  0 -1 -1  0 -2 -1 -1 -1 -1 -2 -1  1  0 -3 -2  0 -2 -1  0 -4 
+R -1  5  0 -2 -3  1  0 -2  0 -3 -2  2 -1 -3 -2 -1 -1 -3 -2 -3 -1  0 -1 -4 
+N -2  0  6  1 -3  0  0  0  1 -3 -3  0 -2 -3 -2  1  0 -4 -2 -3  3  0 -1 -4 
+D -2 -2  1  6 -3  0  2 -1 -1 -3 -4 -1 -3 -3 -1  0 -1 -4 -3 -3  4  1 -1 -4 
+C  0 -3 -3 -3  9 -3 -4 -3 -3 -1 -1 -3 -1 -2 -3 -1 -1 -2 -2 -1 -3 -3 -2 -4 
+Q -1  1  0  0 -3  5  2 -2  0 -3 -2  1  0 -3 -1  0 -1 -2 -1 -2  0  3 -1 -4 
+E -1  0  0  2 -4  2  5 -2  0 -3 -3  1 -2 -3 -1  0 -1 -3 -2 -2  1  4 -1 -4 
+G  0 -2  0 -1 -3 -2 -2  6 -2 -4 -4 -2 -3 -3 -2  0 -2 -2 -3 -3 -1 -2 -1 -4 
+H -2  0  1 -1 -3  0  0 -2  8 -3 -3 -1 -2 -1 -2 -1 -2 -2  2 -3  0  0 -1 -4 
+I -1 -3 -3 -3 -1 -3 -3 -4 -3  4  2 -3  1  0 -3 -2 -1 -3 -1  3 -3 -3 -1 -4 
+L -1 -2 -3 -4 -1 -2 -3 -4 -3  2  4 -2  2  0 -3 -2 -1 -2 -1  1 -4 -3 -1 -4 
+K -1  2  0 -1 -3  1  1 -2 -1 -3 -2  5 -1 -3 -1  0 -1 -3 -2 -2  0  1 -1 -4 
+M -1 -1 -2 -3 -1  0 -2 -3 -2  1  2 -1  5  0 -2 -1 -1 -1 -1  1 -3 -1 -1 -4 
+F -2 -3 -3 -3 -2 -3 -3 -3 -1  0  0 -3  0  6 -4 -2 -2  1  3 -1 -3 -3 -1 -4 
+P -1 -2 -2 -1 -3 -1 -1 -2 -2 -3 -3 -1 -2 -4  7 -1 -1 -4 -3 -2 -2 -1 -2 -4 
+S  1 -1  1  0 -1  0  0  0 -1 -2 -2  0 -1 -2 -1  4  1 -3 -2 -2  0  0  0 -4 
+T  0 -1  0 -1 -1 -1 -1 -2 -2 -1 -1 -1 -1 -2 -1  1  5 -2 -2  0 -1 -1  0 -4 
+W -3 -3 -4 -4 -2 -2 -3 -2 -2 -3 -2 -3 -1  1 -4 -3 -2 11  2 -3 -4 -3 -2 -4 
+Y -2 -2 -2 -3 -2 -1 -2 -3  2 -1 -1 -2 -1  3 -3 -2 -2  2  7 -1 -3 -2 -1 -4 
+V  0 -3 -3 -3 -1 -2 -2 -3 -3  3  1 -2  1 -1 -2 -2  0 -3 -1  4 -3 -2 -1 -4 
+B -2 -1  3  4 -3  0  1 -1  0 -3 -4  0 -3 -3 -2  0 -1 -4 -3 -3  4  1 -1 -4 
+Z -1  0  0  1 -3  3  4 -2  0 -3 -3  1 -1 -3 -1  0 -1 -3 -2 -2  1  4 -1 -4 
+X  0 -1 -1 -1 -2 -1 -1 -1 -1 -1 -1 -1 -1 -1 -2  0  0 -2 -1 -1 -1 -1 -1 -4 
+* -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4 -4  1 
+";
+
+#[test]
+fn protein_tables_are_symmetric() {
+    for matrix in [
+        BLOSUM45, BLOSUM50, BLOSUM62, BLOSUM80, BLOSUM90, PAM30, PAM70, PAM250,
+    ] {
+        let scores = matrix.scores();
+        for (i, row) in scores.iter().enumerate() {
+            for (j, &score) in row.iter().enumerate() {
+                assert_eq!(score, scores[j][i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn protein_tables_are_indexed_by_discriminant() {
+    let score = |m: &ProteinMatrix, a, b| SubstitutionMatrix::<AA20>::score(m, a, b);
+    assert_eq!(score(&BLOSUM62, AminoAcid::W, AminoAcid::W), 11);
+    assert_eq!(score(&BLOSUM62, AminoAcid::C, AminoAcid::C), 9);
+    assert_eq!(score(&BLOSUM62, AminoAcid::D, AminoAcid::E), 2);
+    assert_eq!(score(&PAM250, AminoAcid::W, AminoAcid::W), 17);
+    assert_eq!(score(&PAM30, AminoAcid::W, AminoAcid::C), -15);
+    assert_eq!(score(&BLOSUM45, AminoAcid::W, AminoAcid::W), 15);
+}
+
+#[test]
+fn parses_ncbi_protein_matrix() {
+    assert_eq!(NCBI_BLOSUM62.parse::<ProteinMatrix>().unwrap(), BLOSUM62);
+}
+
+#[test]
+fn parses_ncbi_nucleotide_matrix() {
+    let text = "\
+# Minimal RNA matrix
+   A  C  G  U  N
+A  2 -1 -1 -1  0
+C -1  2 -1 -1  0
+G -1 -1  2 -1  0
+U -1 -1 -1  2  0
+N  0  0  0  0  0
+";
+    let matrix = text.parse::<NucleotideMatrix>().unwrap();
+    assert_eq!(
+        SubstitutionMatrix::<Nuc4>::score(&matrix, Nucleotide::T, Nucleotide::T),
+        2
+    );
+    assert_eq!(
+        SubstitutionMatrix::<Nuc5>::score(&matrix, Nucleotide::N, Nucleotide::A),
+        0
+    );
+
+    assert_eq!(
+        EDNAFULL.scores()[Nucleotide::N as usize][Nucleotide::N as usize],
+        -1
+    );
+    assert_eq!(
+        "   A  C  G\nA  1  0  0\n".parse::<NucleotideMatrix>(),
+        Err(MatrixError::MissingSymbol('T'))
+    );
+    assert_eq!(
+        "   A  C\nA  1  x\n".parse::<NucleotideMatrix>(),
+        Err(MatrixError::InvalidScore { line: 2 })
+    );
+}
+
+#[test]
+fn aligns_with_substitution_matrices() {
+    let x = Seq::<AA20>::try_from("HEAGAWGHEE").unwrap();
+    let y = Seq::<AA20>::try_from("PAWHEAE").unwrap();
+    let aln = Aligner::local(Scoring::with_matrix(BLOSUM50, -8, 0)).align(&x, &y);
+    // The textbook example of Durbin et al.: AWGHE against AW-HE
+    assert_eq!(aln.score, 28);
+    assert_eq!(aln.cigar.to_string(), "2=1I2=");
+
+    let x = Seq::<Nuc5>::try_from("ACGTNACGT").unwrap();
+    let y = Seq::<Nuc5>::try_from("ACGTAACGT").unwrap();
+    let aln = Aligner::global(Scoring::with_matrix(EDNAFULL, -10, -1)).align(&x, &y);
+    assert_eq!(aln.score, 8 * 5 - 2);
+}
+
+// -- Striped Smith-Waterman --
+
+fn aa20(s: &str) -> Seq<AA20> {
+    Seq::try_from(s).unwrap()
+}
+
+#[test]
+fn striped_finds_local_hit() {
+    let query = aa20("HEAGAWGHEE");
+    let profile = QueryProfile::new(&query, &Scoring::with_matrix(BLOSUM50, -8, 0));
+    let hit = profile.score(&aa20("PAWHEAE"));
+    assert_eq!(hit.score, 28);
+    assert_eq!((hit.query_end, hit.target_end), (9, 5));
+
+    let aln = profile.align(&aa20("PAWHEAE"));
+    assert_eq!(aln.score, 28);
+    assert_eq!(
+        (aln.x_start, aln.x_end, aln.y_start, aln.y_end),
+        (4, 9, 1, 5)
+    );
+    assert_eq!(aln.cigar.to_string(), "2=1I2=");
+}
+
+#[test]
+fn striped_handles_empty_inputs() {
+    let scoring = Scoring::with_matrix(BLOSUM62, -11, -1);
+    assert_eq!(
+        QueryProfile::new(&aa20(""), &scoring)
+            .score(&aa20("ACDE"))
+            .score,
+        0
+    );
+    let profile = QueryProfile::new(&aa20("ACDE"), &scoring);
+    assert_eq!(profile.score(&aa20("")).score, 0);
+    assert!(profile.align(&aa20("")).is_empty());
+}
+
+#[test]
+fn striped_widens_lanes_on_overflow() {
+    // 300 * 11 saturates 8-bit lanes but fits 16 bits
+    let query = aa20(&"W".repeat(300));
+    let profile = QueryProfile::new(&query, &Scoring::with_matrix(BLOSUM62, -11, -1));
+    assert_eq!(profile.score(&query).score, 3300);
+
+    // Beyond 16 bits the scalar aligner takes over
+    let query = nuc4(&"ACGT".repeat(100));
+    let scoring = Scoring::with_matrix(MatchMismatch::new(100, -100), -100, -10);
+    let profile = QueryProfile::new(&query, &scoring);
+    let hit = profile.score(&query);
+    assert_eq!(
+        (hit.score, hit.query_end, hit.target_end),
+        (40_000, 400, 400)
+    );
+    assert_eq!(profile.align(&query).cigar.to_string(), "400=");
+}
+
+#[test]
+fn cigars_round_trip_text() {
+    let cigar: Cigar = "3S8M2I4M1D3N3=1X2H".parse().unwrap();
+    assert_eq!(cigar.ops().len(), 9);
+    assert_eq!(cigar.ops()[4], (CigarOp::Deletion, 1));
+    assert_eq!(cigar.to_string(), "3S8M2I4M1D3N3=1X2H");
+    assert_eq!(cigar.query_len(), 3 + 8 + 2 + 4 + 3 + 1);
+    assert_eq!(cigar.reference_len(), 8 + 4 + 1 + 3 + 3 + 1);
+    assert_eq!(cigar.collapse_matches().to_string(), "3S8M2I4M1D3N4M2H");
+
+    let none: Cigar = "*".parse().unwrap();
+    assert!(none.is_empty());
+    assert_eq!(none.to_string(), "*");
+
+    let error = |text: &str| text.parse::<Cigar>().unwrap_err();
+    assert_eq!(error(""), CigarError::Empty);
+    assert_eq!(error("4M3"), CigarError::TrailingLength);
+    assert_eq!(error("4MI"), CigarError::MissingLength('I'));
+    assert_eq!(error("4Q"), CigarError::InvalidOperation('Q'));
+    assert_eq!(error("5000000000M"), CigarError::LengthOverflow);
+}
+
+#[test]
+fn cigars_map_query_to_reference() {
+    let cigar: Cigar = "2S3M2I2M3D1M1H".parse().unwrap();
+    let mapped: Vec<Option<usize>> = (0..cigar.query_len())
+        .map(|i| cigar.reference_pos(i))
+        .collect();
+    let expected = [
+        None,
+        None,
+        Some(0),
+        Some(1),
+        Some(2),
+        None,
+        None,
+        Some(3),
+        Some(4),
+        Some(8),
+    ];
+    assert_eq!(mapped, expected);
+    assert_eq!(cigar.reference_pos(10), None);
+
+    let pairs: Vec<_> = cigar.aligned_pairs().skip(5).take(6).collect();
+    assert_eq!(
+        pairs,
+        vec![
+            (Some(5), None),
+            (Some(6), None),
+            (Some(7), Some(3)),
+            (Some(8), Some(4)),
+            (None, Some(5)),
+            (None, Some(6)),
+        ]
+    );
+}
+
+#[test]
+fn cigars_from_alignments_clip_the_query() {
+    let x = nuc4("TTTTACGTAGCATGCCCC");
+    let y = nuc4("GGACGTAGTCATGGG");
+    let aln = Aligner::local(Scoring::new(2, -3, -5, -1)).align(&x, &y);
+    assert_eq!(aln.cigar.to_string(), "6=1D4=");
+    let cigar = Cigar::from_alignment(&aln, &x, &y);
+    assert_eq!(cigar.to_string(), "4S6=1D4=4S");
+    assert_eq!(cigar.query_len(), x.len());
+    assert_eq!(cigar.reference_len(), aln.y_end - aln.y_start);
+    assert_eq!(Cigar::from(&aln.cigar).to_string(), "6=1D4=");
+    assert_eq!(aln.to_cigar(), Cigar::from(&aln.cigar));
+    assert_eq!(cigar.collapse_matches().to_string(), "4S6M1D4M4S");
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn scores_match_naive_dp(x in "[ACGT]{0,25}", y in "[ACGT]{0,25}", open in -6i32..=0, extend in -3i32..=-1) {
+        let sc = Scoring::new(2, -3, open, extend);
+        let (sx, sy) = (nuc4(&x), nuc4(&y));
+        for mode in [AlignmentMode::Global, AlignmentMode::Local, AlignmentMode::SemiGlobal, AlignmentMode::Overlap] {
+            let aln = Aligner::new(mode, sc).align(&sx, &sy);
+            assert_eq!(aln.score, naive_score(x.as_bytes(), y.as_bytes(), &sc, mode), "{mode:?}");
+            assert_eq!(rescore(&aln, &sx, &sy, &sc), aln.score, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn protein_global_alignment_is_consistent(x in "[ACDEFGHIKLMNPQRSTVWY]{0,60}", y in "[ACDEFGHIKLMNPQRSTVWY]{0,60}") {
+        let sc = Scoring::new(5, -4, -10, -1);
+        let (sx, sy) = (Seq::<AA20>::try_from(x.as_str()).unwrap(), Seq::<AA20>::try_from(y.as_str()).unwrap());
+        let aln = Aligner::global(sc).align(&sx, &sy);
+        assert_eq!((aln.x_start, aln.x_end, aln.y_start, aln.y_end), (0, x.len(), 0, y.len()));
+        assert_eq!(rescore(&aln, &sx, &sy, &sc), aln.score);
+    }
+
+    #[test]
+    fn striped_matches_scalar_local(x in "[ACDEFGHIKLMNPQRSTVWY]{0,70}", y in "[ACDEFGHIKLMNPQRSTVWY]{0,70}", open in -12i32..=0, extend in -3i32..=0) {
+        let scoring = Scoring::with_matrix(BLOSUM62, open, extend);
+        let (sx, sy) = (aa20(&x), aa20(&y));
+        let expected = Aligner::local(scoring).align(&sx, &sy).score;
+        let profile = QueryProfile::new(&sx, &scoring);
+        let hit = profile.score(&sy);
+        assert_eq!(hit.score, expected);
+
+        let aln = profile.align(&sy);
+        assert_eq!(aln.score, expected);
+        assert_eq!(rescore(&aln, &sx, &sy, &scoring), expected);
+        if expected > 0 {
+            assert_eq!((aln.x_end, aln.y_end), (hit.query_end, hit.target_end));
+        }
+    }
+
+    #[test]
+    fn striped_matches_scalar_on_repeats(x in "[AC]{0,150}", y in "[AC]{0,150}") {
+        // Long, similar sequences exercise the lazy F loop and 16-bit lanes
+        let scoring = Scoring::with_matrix(EDNAFULL, -2, -1);
+        let (sx, sy) = (nuc4(&x), nuc4(&y));
+        let expected = Aligner::local(scoring).align(&sx, &sy).score;
+        let profile = QueryProfile::new(&sx, &scoring);
+        assert_eq!(profile.score(&sy).score, expected);
+        assert_eq!(rescore(&profile.align(&sy), &sx, &sy, &scoring), expected);
+    }
+
+    #[test]
+    fn alignment_cigars_follow_the_sequences(x in "[ACGT]{0,40}", y in "[ACGT]{1,40}") {
+        let (sx, sy) = (nuc4(&x), nuc4(&y));
+        for aligner in [Aligner::local(Scoring::new(2, -3, -5, -1)), Aligner::global(Scoring::new(2, -3, -5, -1))] {
+            let aln = aligner.align(&sx, &sy);
+            let cigar = Cigar::from_alignment(&aln, &sx, &sy);
+            assert_eq!(cigar.query_len(), x.len());
+            assert_eq!(cigar.reference_len(), aln.y_end - aln.y_start);
+            assert_eq!(cigar.to_string().parse::<Cigar>().unwrap(), cigar.clone());
+            for (q, r) in cigar.aligned_pairs() {
+                if let Some(q) = q {
+                    assert_eq!(cigar.reference_pos(q), r);
+                }
+                if let (Some(q), Some(r)) = (q, r) {
+                    let op = cigar.iter().filter(|op| op.consumes_query()).nth(q).unwrap();
+                    assert_eq!(op == CigarOp::Equal, x.as_bytes()[q] == y.as_bytes()[aln.y_start + r]);
+                }
+            }
+        }
+    }
+
+}
@@ -0,0 +1,104 @@
+use nuc::bitvec::{BitVec, BitVecBuilder};
+
+fn naive_select(bits: &[bool], value: bool, k: usize) -> Option<usize> {
+    bits.iter()
+        .enumerate()
+        .filter(|&(_, &b)| b == value)
+        .nth(k)
+        .map(|(i, _)| i)
+}
+
+fn check(bits: &[bool]) {
+    let bv: BitVec = bits.iter().copied().collect();
+    assert_eq!(bv.len(), bits.len());
+    assert_eq!(bv.iter().collect::<Vec<_>>(), bits);
+
+    let mut ones = 0;
+    for (i, &bit) in bits.iter().enumerate() {
+        assert_eq!(bv.rank1(i), ones, "rank1({i})");
+        assert_eq!(bv.rank0(i), i - ones, "rank0({i})");
+        ones += bit as usize;
+    }
+    assert_eq!(bv.rank1(bits.len()), ones);
+    assert_eq!(bv.count_ones(), ones);
+    assert_eq!(bv.count_zeros(), bits.len() - ones);
+
+    for k in 0..=ones {
+        assert_eq!(bv.select1(k), naive_select(bits, true, k), "select1({k})");
+    }
+    for k in 0..=bits.len() - ones {
+        assert_eq!(bv.select0(k), naive_select(bits, false, k), "select0({k})");
+    }
+    let expected: Vec<usize> = (0..bits.len()).filter(|&i| bits[i]).collect();
+    assert_eq!(bv.ones().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn empty() {
+    let bv = BitVec::default();
+    assert!(bv.is_empty());
+    assert_eq!(bv.rank1(0), 0);
+    assert_eq!(bv.select1(0), None);
+    assert_eq!(bv.select0(0), None);
+}
+
+#[test]
+fn superblock_boundaries() {
+    for len in [63, 64, 65, 511, 512, 513, 1024, 1500] {
+        check(&vec![true; len]);
+        check(&vec![false; len]);
+        check(&(0..len).map(|i| i % 3 == 0).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn select_across_samples() {
+    // Dense and sparse stretches so select samples fall in varied blocks
+    let bits: Vec<bool> = (0..40_000)
+        .map(|i| if i < 20_000 { i % 2 == 0 } else { i % 997 == 0 })
+        .collect();
+    check(&bits);
+}
+
+#[test]
+fn builder_and_words() {
+    let mut builder = BitVecBuilder::new(70);
+    builder.set(3, true);
+    builder.set(69, true);
+    builder.push(true);
+    builder.set(3, false);
+    assert_eq!(builder.len(), 71);
+    assert!(builder.get(70));
+    assert_eq!(builder.count_ones(), 2);
+    // Shrinking clears the dropped bits, growing appends unset ones
+    let mut resized = builder.clone();
+    resized.resize(65);
+    resized.resize(128);
+    assert_eq!(resized.as_words(), &[0, 0]);
+    let bv = builder.build();
+    assert_eq!(bv.ones().collect::<Vec<_>>(), vec![69, 70]);
+
+    assert_eq!(BitVec::from_positions(71, [69, 70]), bv);
+    assert_eq!(BitVec::from_words(bv.as_words().to_vec(), 71), bv);
+    // Bits past the length are dropped
+    assert_eq!(BitVec::from_words(vec![u64::MAX], 4).count_ones(), 4);
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn matches_naive(bits in proptest::collection::vec(proptest::bool::ANY, 0..2000)) {
+        check(&bits);
+    }
+
+    #[test]
+    fn sparse_matches_naive(ones in proptest::collection::btree_set(0usize..20_000, 0..50)) {
+        let bits: Vec<bool> = (0..20_000).map(|i| ones.contains(&i)).collect();
+        let bv = BitVec::from_positions(bits.len(), ones.iter().copied());
+        for (k, &pos) in ones.iter().enumerate() {
+            assert_eq!(bv.select1(k), Some(pos));
+            assert_eq!(bv.rank1(pos), k);
+        }
+        assert_eq!(bv.select0(19_000), naive_select(&bits, false, 19_000));
+    }
+}
@@ -0,0 +1,184 @@
+use nuc::{
+    alphabet::{Nuc4, Nuc5, AA20},
+    distance::{EditDistance, EditOp, EditScript},
+    seq::Seq,
+};
+
+fn naive(a: &str, b: &str) -> usize {
+    a.bytes().zip(b.bytes()).filter(|(x, y)| x != y).count()
+}
+
+#[test]
+fn hamming_of_empty_sequences() {
+    let a = Seq::<Nuc4>::try_from("").unwrap();
+    assert_eq!(a.hamming(&a), 0);
+    assert_eq!(a.hamming_bounded(&a, 0), Some(0));
+}
+
+#[test]
+fn hamming_counts_symbols_not_bits() {
+    // A=00 vs T=11 differs in two bits but is a single mismatch
+    let a = Seq::<Nuc4>::try_from("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA").unwrap();
+    let b = Seq::<Nuc4>::try_from("TAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAT").unwrap();
+    assert_eq!(a.hamming(&b), 2);
+}
+
+#[test]
+fn hamming_bounded_exits_early() {
+    let a = Seq::<Nuc5>::try_from("ACGTNACGTN").unwrap();
+    let b = Seq::<Nuc5>::try_from("TGCANACGTN").unwrap();
+    assert_eq!(a.hamming_bounded(&b, 4), Some(4));
+    assert_eq!(a.hamming_bounded(&b, 3), None);
+}
+
+#[test]
+#[should_panic]
+fn hamming_requires_equal_lengths() {
+    let a = Seq::<Nuc4>::try_from("ACGT").unwrap();
+    let b = Seq::<Nuc4>::try_from("ACG").unwrap();
+    a.hamming(&b);
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn nuc4_hamming_matches_naive(a in "[ACGT]{0,150}", b in "[ACGT]{150}") {
+        let b = &b[..a.len()];
+        let sa = Seq::<Nuc4>::try_from(a.as_str()).unwrap();
+        let sb = Seq::<Nuc4>::try_from(b).unwrap();
+        assert_eq!(sa.hamming(&sb), naive(&a, b));
+    }
+
+    #[test]
+    fn nuc5_hamming_matches_naive(a in "[ACGTN]{0,150}", b in "[ACGTN]{150}") {
+        let b = &b[..a.len()];
+        let sa = Seq::<Nuc5>::try_from(a.as_str()).unwrap();
+        let sb = Seq::<Nuc5>::try_from(b).unwrap();
+        assert_eq!(sa.hamming(&sb), naive(&a, b));
+    }
+
+    #[test]
+    fn aa20_hamming_bounded_matches_naive(a in "[ACDEFGHIKLMNPQRSTVWY]{0,150}", b in "[ACDEFGHIKLMNPQRSTVWY]{150}", max in 0usize..150) {
+        let b = &b[..a.len()];
+        let sa = Seq::<AA20>::try_from(a.as_str()).unwrap();
+        let sb = Seq::<AA20>::try_from(b).unwrap();
+        let expected = naive(&a, b);
+        assert_eq!(sa.hamming_bounded(&sb, max), (expected <= max).then_some(expected));
+    }
+
+}
+
+// -- Edit distance --
+
+fn naive_levenshtein(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.bytes().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for j in 1..=b.len() {
+            let up = row[j];
+            row[j] = (diagonal + (x != b[j - 1]) as usize)
+                .min(up + 1)
+                .min(row[j - 1] + 1);
+            diagonal = up;
+        }
+    }
+    row[b.len()]
+}
+
+/// Replays an edit script, checking it turns `a` into `b`.
+fn apply(script: &EditScript, a: &str, b: &str) {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+    for op in script.iter() {
+        match op {
+            EditOp::Match => {
+                assert_eq!(a[i], b[j]);
+                i += 1;
+                j += 1;
+            }
+            EditOp::Mismatch => {
+                assert_ne!(a[i], b[j]);
+                i += 1;
+                j += 1;
+            }
+            EditOp::Insertion => i += 1,
+            EditOp::Deletion => j += 1,
+        }
+    }
+    assert_eq!((i, j), (a.len(), b.len()));
+}
+
+#[test]
+fn levenshtein_of_classic_example() {
+    let a = Seq::<Nuc4>::try_from("GATTACA").unwrap();
+    let b = Seq::<Nuc4>::try_from("GCATGCT").unwrap();
+    assert_eq!(a.levenshtein(&b), 4);
+    assert_eq!(a.levenshtein(&Seq::<Nuc4>::new(0)), 7);
+}
+
+#[test]
+fn traceback_prints_as_cigar() {
+    let a = Seq::<Nuc4>::try_from("GGGGTCCCCA").unwrap();
+    let b = Seq::<Nuc4>::try_from("GGGGCCCCT").unwrap();
+    let script = EditDistance::new().traceback(&a, &b).unwrap();
+    assert_eq!(script.distance(), 2);
+    assert_eq!(script.to_string(), "4=1I4=1X");
+
+    let script = EditDistance::new().traceback(&b, &a).unwrap();
+    assert_eq!(script.to_string(), "4=1D4=1X");
+}
+
+#[test]
+fn band_and_cutoff_reject_distant_pairs() {
+    let a = Seq::<Nuc5>::try_from("ACGTACGTAC").unwrap();
+    let b = Seq::<Nuc5>::try_from("ACGTAC").unwrap();
+    assert_eq!(EditDistance::new().with_band(3).distance(&a, &b), None);
+    assert_eq!(EditDistance::new().with_band(4).distance(&a, &b), Some(4));
+    assert_eq!(
+        EditDistance::new().with_max_distance(3).distance(&a, &b),
+        None
+    );
+    assert_eq!(
+        EditDistance::new().with_max_distance(3).traceback(&a, &b),
+        None
+    );
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn levenshtein_matches_naive(a in "[ACGT]{0,200}", b in "[ACGT]{0,200}") {
+        let (x, y) = (Seq::<Nuc4>::try_from(a.as_str()).unwrap(), Seq::<Nuc4>::try_from(b.as_str()).unwrap());
+        assert_eq!(x.levenshtein(&y), naive_levenshtein(&a, &b));
+    }
+
+    #[test]
+    fn traceback_is_optimal(a in "[ACDEFGHIKLMNPQRSTVWY]{0,80}", b in "[ACDEFGHIKLMNPQRSTVWY]{0,80}") {
+        let (x, y) = (Seq::<AA20>::try_from(a.as_str()).unwrap(), Seq::<AA20>::try_from(b.as_str()).unwrap());
+        let script = EditDistance::new().traceback(&x, &y).unwrap();
+        assert_eq!(script.distance(), naive_levenshtein(&a, &b));
+        apply(&script, &a, &b);
+    }
+
+    #[test]
+    fn cutoff_agrees_between_paths(a in "[ACGTN]{0,100}", b in "[ACGTN]{0,100}", max in 0usize..60) {
+        let (x, y) = (Seq::<Nuc5>::try_from(a.as_str()).unwrap(), Seq::<Nuc5>::try_from(b.as_str()).unwrap());
+        let expected = Some(naive_levenshtein(&a, &b)).filter(|&d| d <= max);
+        let bounded = EditDistance::new().with_max_distance(max);
+        assert_eq!(bounded.distance(&x, &y), expected);
+        assert_eq!(bounded.traceback(&x, &y).map(|s| s.distance()), expected);
+    }
+
+    #[test]
+    fn wide_band_is_exact(a in "[ACGT]{0,60}", b in "[ACGT]{0,60}") {
+        let (x, y) = (Seq::<Nuc4>::try_from(a.as_str()).unwrap(), Seq::<Nuc4>::try_from(b.as_str()).unwrap());
+        assert_eq!(EditDistance::new().with_band(60).distance(&x, &y), Some(naive_levenshtein(&a, &b)));
+        // A narrow band can only overestimate
+        if let Some(d) = EditDistance::new().with_band(2).distance(&x, &y) {
+            assert!(d >= naive_levenshtein(&a, &b));
+        }
+    }
+
+}
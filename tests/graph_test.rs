@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+
+use nuc::{
+    alphabet::{Nuc4, Strand},
+    graph::{DeBruijnGraph, Handle, UnitigGraph, UnitigLink},
+    kmer::{kmer_from_seq, KmerCounter},
+    seq::Seq,
+};
+
+fn seq(text: &str) -> Seq<Nuc4> {
+    Seq::try_from(text).unwrap()
+}
+
+fn revcomp(text: &str) -> String {
+    seq(text).reverse_complement().to_string()
+}
+
+/// Returns the sequence of a unitig read along a strand.
+fn oriented(graph: &UnitigGraph, id: usize, strand: Strand) -> String {
+    let text = graph.unitigs()[id].seq.to_string();
+    match strand {
+        Strand::Forward => text,
+        Strand::Reverse => revcomp(&text),
+    }
+}
+
+fn gfa(graph: &UnitigGraph) -> String {
+    let mut out = Vec::new();
+    graph.write_gfa(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn handles_spell_both_strands() {
+    let graph = DeBruijnGraph::from_seqs(&[seq("ACGTTG")], 3);
+    // ACG/CGT collapse: ACG, CGT, GTT, TTG -> ACG, AAC (GTT), CAA (TTG)
+    assert_eq!(graph.len(), 3);
+
+    let h = graph.find_seq(&seq("GTT")).unwrap();
+    assert_eq!(h.strand, Strand::Reverse);
+    assert_eq!(graph.spell(h).to_string(), "GTT");
+    assert_eq!(graph.spell(h.flip()).to_string(), "AAC");
+    assert_eq!(graph.count(graph.find_seq(&seq("ACG")).unwrap().node), 2);
+    assert_eq!(graph.find_seq(&seq("AAA")), None);
+}
+
+#[test]
+fn traverses_neighbors() {
+    // GAC is followed by ACG and ACT
+    let graph = DeBruijnGraph::from_seqs(&[seq("GACG"), seq("GACT")], 3);
+    let gac = graph.find_seq(&seq("GAC")).unwrap();
+    let mut next: Vec<String> = graph
+        .successors(gac)
+        .map(|h| graph.spell(h).to_string())
+        .collect();
+    next.sort();
+    assert_eq!(next, vec!["ACG", "ACT"]);
+    assert_eq!(graph.out_degree(gac), 2);
+    assert_eq!(graph.in_degree(gac), 0);
+
+    let act = graph.find_seq(&seq("ACT")).unwrap();
+    let prev: Vec<Handle> = graph.predecessors(act).collect();
+    assert_eq!(prev, vec![gac]);
+    // Read backwards, ACT (AGT) leads into GAC (GTC)
+    assert_eq!(
+        graph.successors(act.flip()).collect::<Vec<_>>(),
+        vec![gac.flip()]
+    );
+}
+
+#[test]
+fn compacts_a_single_read() {
+    let read = "GATTCCAGAGTTCGTC";
+    let graph = DeBruijnGraph::from_seqs(&[seq(read)], 5).compact();
+    assert_eq!(graph.len(), 1);
+    let unitig = graph.unitigs()[0].seq.to_string();
+    assert!(unitig == read || unitig == revcomp(read));
+    assert_eq!(graph.unitigs()[0].nodes.len(), read.len() - 4);
+    assert_eq!(graph.unitigs()[0].count, 12);
+    assert!(graph.links().is_empty());
+}
+
+/// Two reads differing in one base share both flanks.
+fn bubble() -> UnitigGraph {
+    let reads = [seq("GATTCCAGAGTTCGTC"), seq("GATTCCATAGTTCGTC")];
+    DeBruijnGraph::from_seqs(&reads, 5).compact()
+}
+
+#[test]
+fn compacts_a_bubble() {
+    let graph = bubble();
+    let mut spelled: Vec<String> = graph
+        .unitigs()
+        .iter()
+        .map(|u| {
+            let text = u.seq.to_string();
+            text.clone().min(revcomp(&text))
+        })
+        .collect();
+    spelled.sort();
+    assert_eq!(
+        spelled,
+        vec!["AACTATGGA", "AACTCTGGA", "AGTTCGTC", "GATTCCA"]
+    );
+    // Each branch joins both flanks
+    assert_eq!(graph.links().len(), 4);
+}
+
+#[test]
+fn writes_gfa() {
+    assert_eq!(
+        gfa(&bubble()),
+        "H\tVN:Z:1.0\n\
+         S\t0\tAACTATGGA\tLN:i:9\tKC:i:5\n\
+         S\t1\tAACTCTGGA\tLN:i:9\tKC:i:5\n\
+         S\t2\tGACGAACT\tLN:i:8\tKC:i:8\n\
+         S\t3\tGATTCCA\tLN:i:7\tKC:i:6\n\
+         L\t0\t+\t3\t-\t4M\n\
+         L\t0\t-\t2\t-\t4M\n\
+         L\t1\t+\t3\t-\t4M\n\
+         L\t1\t-\t2\t-\t4M\n"
+    );
+}
+
+#[test]
+fn palindromes_form_their_own_unitigs() {
+    for read in ["ACGTACGT", "ACGTTGCA"] {
+        let graph = DeBruijnGraph::from_seqs(&[seq(read)], 4).compact();
+        let text = gfa(&graph);
+        let segments: Vec<&str> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("S\t"))
+            .map(|line| line.split('\t').nth(1).unwrap())
+            .collect();
+        let mut links = 0;
+        for line in text.lines().filter(|line| line.starts_with("L\t")) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            assert_eq!(fields[5], "3M");
+            let spell = |id: &str, sign: &str| {
+                let text = segments[id.parse::<usize>().unwrap()];
+                if sign == "+" {
+                    text.to_string()
+                } else {
+                    revcomp(text)
+                }
+            };
+            let (from, to) = (spell(fields[1], fields[2]), spell(fields[3], fields[4]));
+            assert_eq!(&from[from.len() - 3..], &to[..3], "{read}: {line}");
+            links += 1;
+        }
+        assert!(links > 0);
+        for unitig in graph.unitigs() {
+            let text = unitig.seq.to_string();
+            if text.len() == 4 && text == revcomp(&text) {
+                continue;
+            }
+            // No unitig runs through a palindrome
+            assert!((0..=text.len() - 4).all(|i| text[i..i + 4] != revcomp(&text[i..i + 4])));
+        }
+    }
+}
+
+#[test]
+fn links_cycles_to_themselves() {
+    // A circular sequence compacts into one unitig linked to itself
+    let circle = "ACGGTCAATC";
+    let wrapped = format!("{circle}{}", &circle[..4]);
+    let graph = DeBruijnGraph::from_seqs(&[seq(&wrapped)], 5).compact();
+    assert_eq!(graph.len(), 1);
+    assert_eq!(graph.unitigs()[0].nodes.len(), circle.len());
+    assert_eq!(
+        graph.links(),
+        &[UnitigLink::new(0, Strand::Forward, 0, Strand::Forward)]
+    );
+}
+
+#[test]
+fn builds_from_kmer_sets() {
+    let reads = [seq("ACGTTGCATTAG"), seq("GGCATTACC")];
+    let counts = KmerCounter::new(5).count(&reads);
+    let from_counts = DeBruijnGraph::from_counts(&counts);
+    let words = reads.iter().flat_map(|r| r.kmers(5).collect::<Vec<_>>());
+    let from_words = DeBruijnGraph::from_kmers(5, words);
+    assert_eq!(from_counts, from_words);
+    assert_eq!(from_counts, DeBruijnGraph::from_seqs(&reads, 5));
+    assert_eq!(
+        from_words
+            .find(kmer_from_seq(&seq("CATTA")))
+            .map(|h| from_words.count(h.node)),
+        Some(2)
+    );
+}
+
+proptest! {
+    #[test]
+    fn unitigs_partition_the_kmers(
+        reads in prop::collection::vec("[ACGT]{1,40}", 1..6),
+        k in 2usize..8,
+    ) {
+        let seqs: Vec<Seq<Nuc4>> = reads.iter().map(|r| seq(r)).collect();
+        let graph = DeBruijnGraph::from_seqs(&seqs, k);
+        let compacted = graph.compact();
+
+        let mut seen = HashSet::new();
+        for unitig in compacted.unitigs() {
+            let text = unitig.seq.to_string();
+            prop_assert_eq!(text.len(), k + unitig.nodes.len() - 1);
+            for (i, &handle) in unitig.nodes.iter().enumerate() {
+                prop_assert_eq!(graph.spell(handle).to_string(), &text[i..i + k]);
+                prop_assert!(seen.insert(handle.node));
+            }
+            // Inner edges do not branch
+            for pair in unitig.nodes.windows(2) {
+                prop_assert_eq!(graph.out_degree(pair[0]), 1);
+                prop_assert_eq!(graph.in_degree(pair[1]), 1);
+            }
+        }
+        prop_assert_eq!(seen.len(), graph.len());
+    }
+
+    #[test]
+    fn links_overlap_by_k_minus_one(
+        reads in prop::collection::vec("[ACGT]{1,40}", 1..6),
+        k in 2usize..8,
+    ) {
+        let seqs: Vec<Seq<Nuc4>> = reads.iter().map(|r| seq(r)).collect();
+        let graph = DeBruijnGraph::from_seqs(&seqs, k);
+        let compacted = graph.compact();
+        let unitig_count = compacted.len();
+
+        for link in compacted.links() {
+            let from = oriented(&compacted, link.from, link.from_strand);
+            let to = oriented(&compacted, link.to, link.to_strand);
+            prop_assert_eq!(&from[from.len() - (k - 1)..], &to[..k - 1]);
+            prop_assert_eq!(*link, link.canonical());
+        }
+
+        // Every overlap between unitig ends is an edge of the graph
+        let mut expected = HashSet::new();
+        for id in 0..unitig_count {
+            for strand in [Strand::Forward, Strand::Reverse] {
+                let from = oriented(&compacted, id, strand);
+                for to in 0..unitig_count {
+                    for to_strand in [Strand::Forward, Strand::Reverse] {
+                        let target = oriented(&compacted, to, to_strand);
+                        if from[from.len() - (k - 1)..] == target[..k - 1] {
+                            expected.insert(UnitigLink::new(id, strand, to, to_strand).canonical());
+                        }
+                    }
+                }
+            }
+        }
+        let links: HashSet<UnitigLink> = compacted.links().iter().copied().collect();
+        prop_assert_eq!(links, expected);
+    }
+}
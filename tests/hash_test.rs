@@ -23,3 +23,53 @@ fn test_hash_chars_litte_endian() {
     assert_eq!(hash_chars_le(b"GCAA"), 6);
     assert_eq!(hash_chars_le(b"TCAA"), 7);
 }
+
+#[test]
+fn test_crc32_check_value() {
+    assert_eq!(nuc::hash::crc32(b""), 0);
+    assert_eq!(nuc::hash::crc32(b"123456789"), 0xCBF4_3926);
+}
+
+#[test]
+fn test_crc32_incremental() {
+    let mut crc = nuc::hash::Crc32::new();
+    crc.update(b"1234");
+    crc.update(b"56789");
+    assert_eq!(crc.finish(), nuc::hash::crc32(b"123456789"));
+}
+
+#[test]
+fn test_nthash_single_base_is_min_of_strands() {
+    use nuc::{alphabet::Nuc4, hash::NtHash, seq::Seq};
+    let seq = Seq::<Nuc4>::try_from("AC").unwrap();
+    let hashes: Vec<u64> = NtHash::new(&seq, 1).collect();
+    assert_eq!(hashes, vec![0x295549f54be24456, 0x20323ed082572324]);
+    assert_eq!(NtHash::new(&seq, 3).count(), 0);
+}
+
+#[test]
+fn test_nthash_multi_keeps_base_hash() {
+    assert_eq!(nuc::hash::nthash_multi(42, 0, 21), 42);
+    assert_ne!(nuc::hash::nthash_multi(42, 1, 21), 42);
+    assert_ne!(
+        nuc::hash::nthash_multi(42, 1, 21),
+        nuc::hash::nthash_multi(42, 2, 21)
+    );
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn test_nthash_rolling_matches_fresh(text in "[ACGT]{1,120}", k in 1usize..70) {
+        use nuc::{alphabet::Nuc4, hash::NtHash, seq::Seq};
+        let seq = Seq::<Nuc4>::try_from(text.as_str()).unwrap();
+        let rolled: Vec<u64> = NtHash::new(&seq, k).collect();
+        assert_eq!(rolled.len(), (text.len() + 1).saturating_sub(k));
+        for (i, &hash) in rolled.iter().enumerate() {
+            let window = seq.slice(i..i + k);
+            assert_eq!(NtHash::new(&window, k).next(), Some(hash));
+            let rc = window.reverse_complement();
+            assert_eq!(NtHash::new(&rc, k).next(), Some(hash));
+        }
+    }
+}
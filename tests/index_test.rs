@@ -0,0 +1,335 @@
+use nuc::{
+    alphabet::{Alphabet, NamedAlphabet, Nuc4, Nuc5, AA20},
+    index::{FmIndex, Position, SuffixArray, WaveletTree},
+    seq::Seq,
+};
+
+fn seqs<A: Alphabet>(texts: &[&str]) -> Vec<Seq<A>> {
+    texts.iter().map(|t| Seq::try_from(*t).unwrap()).collect()
+}
+
+/// Sorts all suffixes (sentinel suffixes included) by string comparison.
+/// Only valid for alphabets whose packed order matches ASCII order.
+fn naive_sa(texts: &[&str]) -> Vec<usize> {
+    let mut suffixes = Vec::new();
+    let mut start = 0;
+    for (i, text) in texts.iter().enumerate() {
+        for offset in 0..=text.len() {
+            suffixes.push((&text[offset..], i, start + offset));
+        }
+        start += text.len() + 1;
+    }
+    suffixes.sort();
+    suffixes.into_iter().map(|(_, _, pos)| pos).collect()
+}
+
+fn naive_locate(texts: &[&str], pattern: &str) -> Vec<Position> {
+    let mut hits = Vec::new();
+    for (seq, text) in texts.iter().enumerate() {
+        for offset in 0..=text.len() {
+            if text[offset..].starts_with(pattern) {
+                hits.push(Position { seq, offset });
+            }
+        }
+    }
+    hits
+}
+
+fn naive_lcp(texts: &[&str], sa: &[usize]) -> Vec<usize> {
+    let mut suffix_at = Vec::new();
+    for text in texts {
+        for offset in 0..=text.len() {
+            suffix_at.push(&text[offset..]);
+        }
+    }
+    let mut lcp = vec![0; sa.len()];
+    for i in 1..sa.len() {
+        let (a, b) = (suffix_at[sa[i - 1]], suffix_at[sa[i]]);
+        lcp[i] = a.bytes().zip(b.bytes()).take_while(|(x, y)| x == y).count();
+    }
+    lcp
+}
+
+#[test]
+fn sorts_suffixes_of_single_sequence() {
+    let sa = SuffixArray::new(&Seq::<Nuc4>::try_from("GATTACA").unwrap());
+    // $, A$, ACA$, ATTACA$, CA$, GATTACA$, TACA$, TTACA$
+    assert_eq!(sa.as_slice(), &[7, 6, 4, 1, 5, 0, 3, 2]);
+    assert_eq!(sa.lcp(), vec![0, 0, 1, 1, 0, 0, 0, 1]);
+}
+
+#[test]
+fn empty_inputs() {
+    let sa = SuffixArray::<Nuc4>::from_seqs(&[]);
+    assert!(sa.is_empty());
+    assert_eq!(sa.seq_count(), 0);
+    assert!(sa.locate(&Seq::try_from("A").unwrap()).is_empty());
+
+    let sa = SuffixArray::new(&Seq::<Nuc4>::new(0));
+    assert_eq!(sa.as_slice(), &[0]);
+    assert_eq!(sa.count(&Seq::try_from("A").unwrap()), 0);
+}
+
+#[test]
+fn matches_do_not_cross_sequence_boundaries() {
+    let texts = ["ACGT", "TTAC", "GTAC"];
+    let sa = SuffixArray::from_seqs(&seqs::<Nuc4>(&texts));
+    let pattern = Seq::try_from("AC").unwrap();
+    assert_eq!(
+        sa.locate(&pattern),
+        vec![
+            Position { seq: 0, offset: 0 },
+            Position { seq: 1, offset: 2 },
+            Position { seq: 2, offset: 2 },
+        ]
+    );
+    // "GTTT" would span the end of the first sequence
+    assert_eq!(sa.count(&Seq::try_from("GTTT").unwrap()), 0);
+    assert_eq!(sa.count(&Seq::try_from("CGT").unwrap()), 1);
+}
+
+#[test]
+fn identical_sequences_order_by_index() {
+    let texts = ["ACG", "ACG"];
+    let sa = SuffixArray::from_seqs(&seqs::<Nuc4>(&texts));
+    assert_eq!(sa.as_slice(), naive_sa(&texts).as_slice());
+    assert_eq!(
+        sa.position(sa.as_slice()[2]),
+        Position { seq: 0, offset: 0 }
+    );
+    assert_eq!(
+        sa.position(sa.as_slice()[3]),
+        Position { seq: 1, offset: 0 }
+    );
+}
+
+#[test]
+fn nuc5_locate() {
+    let texts = ["NNACGTN", "ACGNNN"];
+    let sa = SuffixArray::from_seqs(&seqs::<Nuc5>(&texts));
+    let pattern = Seq::try_from("NN").unwrap();
+    assert_eq!(sa.locate(&pattern), naive_locate(&texts, "NN"));
+    let pattern = Seq::try_from("ACG").unwrap();
+    assert_eq!(sa.locate(&pattern), naive_locate(&texts, "ACG"));
+}
+
+#[test]
+fn fm_index_bwt_and_backward_search() {
+    let fm = FmIndex::new(&Seq::<Nuc4>::try_from("GATTACA").unwrap());
+    // ACTGA$TA with the sentinel read as A
+    assert_eq!(fm.bwt().to_string(), "ACTGAATA");
+    assert_eq!(fm.occ(0, 8), 3);
+    assert_eq!(fm.count(&Seq::try_from("A").unwrap()), 3);
+    assert_eq!(fm.count(&Seq::try_from("TA").unwrap()), 1);
+    assert_eq!(fm.count(&Seq::try_from("AG").unwrap()), 0);
+    assert_eq!(
+        fm.locate(&Seq::try_from("A").unwrap()),
+        [1, 4, 6].map(|offset| Position { seq: 0, offset })
+    );
+}
+
+#[test]
+fn fm_index_serialization_round_trip() {
+    let texts = [
+        "ACGTTGCA",
+        "",
+        "GGGACGT",
+        "TTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTT",
+    ];
+    let sa = SuffixArray::from_seqs(&seqs::<Nuc4>(&texts));
+    let fm = FmIndex::from_suffix_array(&sa, 4);
+
+    let mut bytes = Vec::new();
+    fm.write_to(&mut bytes).unwrap();
+    let loaded = FmIndex::<Nuc4>::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(loaded.len(), fm.len());
+    assert_eq!(loaded.seq_count(), 4);
+    assert_eq!(loaded.sample_rate(), 4);
+    assert_eq!(loaded.bwt(), fm.bwt());
+    for pattern in ["ACGT", "T", "TTTT", "GA"] {
+        let pattern = Seq::try_from(pattern).unwrap();
+        assert_eq!(loaded.locate(&pattern), sa.locate(&pattern));
+    }
+
+    let path = std::env::temp_dir().join(format!("nuc-fm-{}.idx", std::process::id()));
+    fm.save(&path).unwrap();
+    let loaded = FmIndex::<Nuc4>::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.count(&Seq::try_from("ACGT").unwrap()), 2);
+}
+
+#[test]
+fn fm_index_rejects_invalid_input() {
+    let fm = FmIndex::new(&Seq::<Nuc4>::try_from("ACGT").unwrap());
+    let mut bytes = Vec::new();
+    fm.write_to(&mut bytes).unwrap();
+
+    let err = FmIndex::<Nuc5>::read_from(bytes.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(FmIndex::<Nuc4>::read_from(&bytes[..bytes.len() - 1]).is_err());
+    bytes[0] = b'X';
+    let err = FmIndex::<Nuc4>::read_from(bytes.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+fn serialized<A: NamedAlphabet>(fm: &FmIndex<A>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    fm.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn fm_index_rejects_corrupt_files() {
+    let error = |result: std::io::Result<()>| result.unwrap_err().to_string();
+    // Fields follow the magic, version, alphabet and its name
+    let body = |bytes: &[u8]| 14 + bytes[13] as usize;
+    let set = |bytes: &mut Vec<u8>, at: usize, value: u64| {
+        bytes[at..at + 8].copy_from_slice(&value.to_le_bytes())
+    };
+
+    // A Nuc5 symbol code past the alphabet
+    let fm = FmIndex::new(&Seq::<Nuc5>::try_from("ACGTNACGTNACGTN").unwrap());
+    let mut corrupt = serialized(&fm);
+    *corrupt.last_mut().unwrap() = 0b111_111;
+    let message = error(FmIndex::<Nuc5>::read_from(corrupt.as_slice()).map(drop));
+    assert!(message.contains("outside the alphabet"), "{message}");
+
+    // Sentinels listed out of order
+    let fm = FmIndex::from_seqs(&seqs::<Nuc4>(&["ACGT", "GGA"]));
+    let mut corrupt = serialized(&fm);
+    let sentinels = body(&corrupt) + 24 + 16;
+    corrupt[sentinels..sentinels + 32].rotate_left(16);
+    let message = error(FmIndex::<Nuc4>::read_from(corrupt.as_slice()).map(drop));
+    assert!(message.contains("sentinel rows"), "{message}");
+
+    // Sequence ends out of order
+    let mut corrupt = serialized(&fm);
+    let ends = body(&corrupt) + 24;
+    set(&mut corrupt, ends, 8);
+    let message = error(FmIndex::<Nuc4>::read_from(corrupt.as_slice()).map(drop));
+    assert!(message.contains("sequence ends"), "{message}");
+
+    // A huge length fails on the missing data instead of allocating it
+    let mut corrupt = serialized(&FmIndex::new(&Seq::<Nuc4>::try_from("ACGT").unwrap()));
+    let (start, n) = (body(&corrupt), 1u64 << 40);
+    set(&mut corrupt, start, n);
+    set(&mut corrupt, start + 24, n - 1);
+    assert!(FmIndex::<Nuc4>::read_from(corrupt.as_slice()).is_err());
+
+    // "CA" without samples: as built, every LF walk reaches the sentinel,
+    // but swapping the two symbols makes row 1 map onto itself
+    let header = serialized(&FmIndex::new(&Seq::<Nuc4>::try_from("CA").unwrap()));
+    let unsampled = |bwt: u8| {
+        let mut bytes = header[..body(&header)].to_vec();
+        for value in [3u64, 4, 1, 2, 2, 0, 0] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.push(bwt);
+        bytes
+    };
+    let fm = FmIndex::<Nuc4>::read_from(unsampled(0b00_01_00_00).as_slice()).unwrap();
+    assert_eq!(fm.count(&Seq::try_from("CA").unwrap()), 1);
+    let message = error(FmIndex::<Nuc4>::read_from(unsampled(0b01_00_00_00).as_slice()).map(drop));
+    assert!(message.contains("LF cycle"), "{message}");
+}
+
+#[test]
+fn wavelet_tree_over_amino_acids() {
+    let seq = Seq::<AA20>::try_from("MKVLAAGIVGLLLAW").unwrap();
+    let wt = WaveletTree::new(&seq);
+    assert_eq!(wt.len(), 15);
+    assert_eq!(wt.get(0), seq.get(0));
+    assert_eq!(wt.rank(seq.get(3), 15), 4);
+    assert_eq!(wt.select(seq.get(3), 1), Some(10));
+    assert_eq!(wt.select(seq.get(3), 4), None);
+    assert_eq!(wt.rank(seq.get(14), 15), 1);
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn wavelet_tree_matches_naive(text in "[ACDEFGHIKLMNPQRSTVWY]{0,300}") {
+        let seq = Seq::<AA20>::try_from(text.as_str()).unwrap();
+        let wt = WaveletTree::new(&seq);
+        for i in 0..seq.len() {
+            assert_eq!(wt.get_bits(i), seq.get_bits(i));
+        }
+        for c in 0..20u8 {
+            let positions: Vec<usize> = (0..seq.len()).filter(|&i| seq.get_bits(i) == c).collect();
+            for i in (0..=seq.len()).step_by(7) {
+                assert_eq!(wt.rank_bits(c, i), positions.iter().filter(|&&p| p < i).count());
+            }
+            for (k, &pos) in positions.iter().enumerate() {
+                assert_eq!(wt.select_bits(c, k), Some(pos));
+            }
+            assert_eq!(wt.select_bits(c, positions.len()), None);
+        }
+    }
+
+    #[test]
+    fn wavelet_tree_nuc5(text in "[ACGTN]{0,200}") {
+        let seq = Seq::<Nuc5>::try_from(text.as_str()).unwrap();
+        let wt = WaveletTree::new(&seq);
+        for i in 0..seq.len() {
+            assert_eq!(wt.get(i), seq.get(i));
+        }
+        let n = seq.len();
+        let count = text.bytes().filter(|&b| b == b'N').count();
+        assert_eq!(wt.rank_bits(4, n), count);
+    }
+
+    #[test]
+    fn fm_index_matches_naive(
+        texts in proptest::collection::vec("[ACGT]{0,150}", 1..4),
+        pattern in "[ACGT]{0,5}",
+        sample_rate in 1usize..20,
+    ) {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let sa = SuffixArray::from_seqs(&seqs::<Nuc4>(&texts));
+        let fm = FmIndex::from_suffix_array(&sa, sample_rate);
+        let expected = naive_locate(&texts, &pattern);
+        let pattern = Seq::try_from(pattern.as_str()).unwrap();
+        assert_eq!(fm.count(&pattern), sa.count(&pattern));
+        assert_eq!(fm.locate(&pattern), expected);
+    }
+
+    #[test]
+    fn fm_index_aa20(text in "[ACDEFGHIKLMNPQRSTVWY]{1,200}", pattern in "[ACDE]{1,3}") {
+        let fm = FmIndex::new(&Seq::<AA20>::try_from(text.as_str()).unwrap());
+        let expected = naive_locate(&[&text], &pattern);
+        assert_eq!(fm.locate(&Seq::try_from(pattern.as_str()).unwrap()), expected);
+    }
+
+    #[test]
+    fn nuc4_matches_naive_sort(texts in proptest::collection::vec("[ACGT]{0,60}", 0..5)) {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let sa = SuffixArray::from_seqs(&seqs::<Nuc4>(&texts));
+        let expected = naive_sa(&texts);
+        assert_eq!(sa.as_slice(), expected.as_slice());
+        assert_eq!(sa.lcp(), naive_lcp(&texts, &expected));
+    }
+
+    #[test]
+    fn repetitive_text_matches_naive_sort(unit in "[AC]{1,4}", copies in 1usize..40) {
+        let text = unit.repeat(copies);
+        let sa = SuffixArray::new(&Seq::<Nuc4>::try_from(text.as_str()).unwrap());
+        assert_eq!(sa.as_slice(), naive_sa(&[&text]).as_slice());
+    }
+
+    #[test]
+    fn aa20_matches_naive_sort(texts in proptest::collection::vec("[ACDEFGHIKLMNPQRSTVWY]{0,80}", 1..4)) {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let sa = SuffixArray::from_seqs(&seqs::<AA20>(&texts));
+        assert_eq!(sa.as_slice(), naive_sa(&texts).as_slice());
+    }
+
+    #[test]
+    fn locate_matches_naive(texts in proptest::collection::vec("[ACGT]{0,100}", 1..4), pattern in "[ACGT]{0,4}") {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let sa = SuffixArray::from_seqs(&seqs::<Nuc4>(&texts));
+        let expected = naive_locate(&texts, &pattern);
+        let pattern = Seq::try_from(pattern.as_str()).unwrap();
+        assert_eq!(sa.count(&pattern), expected.len());
+        assert_eq!(sa.locate(&pattern), expected);
+    }
+}
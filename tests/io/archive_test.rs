@@ -0,0 +1,133 @@
+use nuc::{
+    alphabet::{Nuc4, Nuc5, AA20},
+    hash::crc32,
+    io::archive::{ArchiveError, ArchiveReader, ArchiveWriter},
+    seq::Seq,
+};
+
+fn archive<A: nuc::alphabet::NamedAlphabet>(records: &[(&str, &Seq<A>)]) -> Vec<u8> {
+    let mut writer = ArchiveWriter::<_, A>::new(Vec::new()).unwrap();
+    for (name, seq) in records {
+        writer.write(name, seq).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn empty_archive() {
+    let bytes = archive::<Nuc4>(&[]);
+    let reader = ArchiveReader::<_, Nuc4>::new(bytes).unwrap();
+    assert!(reader.is_empty());
+    assert_eq!(reader.names().count(), 0);
+}
+
+#[test]
+fn records_are_read_by_name() {
+    let chr1 = Seq::<Nuc5>::try_from("ACGTNNACG").unwrap();
+    let chr2 = Seq::<Nuc5>::try_from("GGN").unwrap();
+    let bytes = archive(&[("chr1", &chr1), ("chr2", &chr2)]);
+    let reader = ArchiveReader::<_, Nuc5>::new(bytes.as_slice()).unwrap();
+
+    assert_eq!(reader.names().collect::<Vec<_>>(), vec!["chr1", "chr2"]);
+    assert_eq!(reader.seq_len("chr1").unwrap(), 9);
+    assert_eq!(reader.get("chr2").unwrap(), chr2);
+    assert_eq!(reader.packed("chr1").unwrap(), chr1.as_bytes());
+    assert!(matches!(reader.get("chr3"), Err(ArchiveError::NotFound(_))));
+}
+
+#[test]
+fn alphabet_is_checked() {
+    let seq = Seq::<Nuc4>::try_from("ACGT").unwrap();
+    let bytes = archive(&[("s", &seq)]);
+    assert!(matches!(
+        ArchiveReader::<_, Nuc5>::new(bytes.as_slice()),
+        Err(ArchiveError::AlphabetMismatch { .. })
+    ));
+}
+
+#[test]
+fn names_are_unique() {
+    let seq = Seq::<Nuc4>::try_from("ACGT").unwrap();
+    let mut writer = ArchiveWriter::<_, Nuc4>::new(Vec::new()).unwrap();
+    writer.write("a", &seq).unwrap();
+    let err = writer.write("a", &seq).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    writer.write("b", &seq).unwrap();
+    let reader = ArchiveReader::<_, Nuc4>::new(writer.finish().unwrap()).unwrap();
+    assert_eq!(reader.names().collect::<Vec<_>>(), vec!["a", "b"]);
+
+    // Rename "b" to "a" in the index and fix up its checksum
+    let mut bytes = archive(&[("a", &seq), ("b", &seq)]);
+    let len = bytes.len();
+    let index = u64::from_le_bytes(bytes[len - 20..len - 12].try_into().unwrap()) as usize;
+    bytes[index + 37] = b'a';
+    let crc = crc32(&bytes[index..len - 20]);
+    bytes[len - 12..len - 8].copy_from_slice(&crc.to_le_bytes());
+    assert!(matches!(
+        ArchiveReader::<_, Nuc4>::new(bytes),
+        Err(ArchiveError::DuplicateName(name)) if name == "a"
+    ));
+}
+
+#[test]
+fn corrupted_data_is_detected() {
+    let seq = Seq::<AA20>::try_from("MKVLAAGIW").unwrap();
+    let mut bytes = archive(&[("p", &seq)]);
+    bytes[20] ^= 0xFF;
+    let reader = ArchiveReader::<_, AA20>::new(bytes).unwrap();
+    assert!(matches!(
+        reader.get("p"),
+        Err(ArchiveError::ChecksumMismatch)
+    ));
+}
+
+#[test]
+fn corrupted_index_is_detected() {
+    let seq = Seq::<Nuc4>::try_from("ACGT").unwrap();
+    let mut bytes = archive(&[("s", &seq)]);
+    let len = bytes.len();
+    bytes[len - 25] ^= 0xFF;
+    assert!(matches!(
+        ArchiveReader::<_, Nuc4>::new(bytes),
+        Err(ArchiveError::ChecksumMismatch)
+    ));
+}
+
+#[test]
+fn truncated_archive_is_rejected() {
+    let seq = Seq::<Nuc4>::try_from("ACGT").unwrap();
+    let bytes = archive(&[("s", &seq)]);
+    assert!(ArchiveReader::<_, Nuc4>::new(&bytes[..bytes.len() - 1]).is_err());
+    assert!(matches!(
+        ArchiveReader::<_, Nuc4>::new(&b"FASTA"[..]),
+        Err(ArchiveError::Truncated)
+    ));
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn roundtrip(seqs in proptest::collection::vec("[ACGTN]{0,100}", 0..10)) {
+        let seqs: Vec<Seq<Nuc5>> = seqs.iter().map(|s| Seq::try_from(s.as_str()).unwrap()).collect();
+        let names: Vec<String> = (0..seqs.len()).map(|i| format!("seq{i}")).collect();
+        let records: Vec<(&str, &Seq<Nuc5>)> = names.iter().map(String::as_str).zip(&seqs).collect();
+        let reader = ArchiveReader::<_, Nuc5>::new(archive(&records)).unwrap();
+        let read: Vec<Seq<Nuc5>> = reader.iter().map(|r| r.unwrap().1).collect();
+        assert_eq!(read, seqs);
+    }
+
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn memory_mapped_archive() {
+    let seq = Seq::<Nuc4>::try_from("ACGTTGCA").unwrap();
+    let path = std::env::temp_dir().join(format!("nuc-archive-{}.bin", std::process::id()));
+    std::fs::write(&path, archive(&[("s", &seq)])).unwrap();
+
+    let reader = ArchiveReader::<_, Nuc4>::open(&path).unwrap();
+    assert_eq!(reader.get("s").unwrap(), seq);
+
+    drop(reader);
+    std::fs::remove_file(path).unwrap();
+}
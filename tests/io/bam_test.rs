@@ -0,0 +1,519 @@
+use std::io::{Cursor, Read};
+
+use proptest::prelude::*;
+
+use nuc::{
+    hash::crc32,
+    io::{
+        bam::{reg2bin, BamIndex, BamReader},
+        bgzf::BgzfReader,
+        sam::{CigarOp, Flags, SamRecord, TagArray, TagValue},
+    },
+};
+
+/// Raw DEFLATE data, fixed Huffman codes with back-references.
+const FIXED: [u8; 75] = [
+    115, 240, 112, 225, 12, 243, 179, 50, 212, 51, 227, 12, 246, 183, 74, 206, 207, 47, 74, 201,
+    204, 75, 44, 73, 229, 114, 32, 89, 162, 36, 35, 85, 161, 176, 52, 51, 57, 91, 33, 169, 40, 191,
+    60, 79, 33, 45, 191, 66, 33, 171, 52, 183, 160, 88, 33, 191, 44, 181, 72, 1, 36, 157, 147, 88,
+    85, 169, 144, 146, 159, 174, 48, 24, 212, 2, 0,
+];
+
+/// Raw DEFLATE data, dynamic Huffman codes over [`lcg_bases`] twice.
+const DYNAMIC: [u8; 134] = [
+    237, 144, 217, 21, 196, 48, 8, 3, 107, 211, 227, 131, 6, 212, 127, 45, 59, 67, 82, 64, 10, 216,
+    28, 182, 65, 66, 8, 103, 179, 157, 221, 153, 41, 43, 239, 38, 237, 78, 46, 187, 132, 109, 72,
+    109, 46, 128, 216, 17, 106, 100, 39, 124, 156, 89, 193, 249, 1, 123, 201, 65, 86, 174, 89, 24,
+    214, 69, 213, 170, 83, 249, 181, 35, 113, 173, 90, 123, 66, 51, 0, 157, 167, 47, 241, 3, 189,
+    14, 52, 9, 54, 102, 108, 158, 219, 78, 71, 219, 231, 89, 6, 181, 143, 208, 185, 92, 247, 147,
+    226, 121, 203, 57, 48, 158, 147, 81, 4, 120, 69, 163, 113, 165, 101, 71, 233, 27, 233, 108, 58,
+    200, 255, 174, 62, 223, 213, 15,
+];
+
+fn fixed_text() -> Vec<u8> {
+    let mut text = "@HD\tVN:1.6\tSO:coordinate\n".repeat(3);
+    text.push_str(&"the quick brown fox jumps over the lazy dog ".repeat(4));
+    text.into_bytes()
+}
+
+fn lcg_bases(n: usize) -> Vec<u8> {
+    let mut x: u64 = 7;
+    (0..n)
+        .map(|_| {
+            x = (x * 1103515245 + 12345) % (1 << 31);
+            b"ACGT"[((x >> 16) & 3) as usize]
+        })
+        .collect()
+}
+
+// -- Writing BGZF and BAM ----------------------------------------------------
+
+/// Wraps compressed data into a BGZF block.
+fn block(deflated: &[u8], plain: &[u8]) -> Vec<u8> {
+    let mut out = vec![31, 139, 8, 4, 0, 0, 0, 0, 0, 255, 6, 0, b'B', b'C', 2, 0];
+    out.extend(((deflated.len() + 25) as u16).to_le_bytes());
+    out.extend(deflated);
+    out.extend(crc32(plain).to_le_bytes());
+    out.extend((plain.len() as u32).to_le_bytes());
+    out
+}
+
+/// A BGZF block holding `plain` in a stored DEFLATE block.
+fn stored(plain: &[u8]) -> Vec<u8> {
+    let len = plain.len() as u16;
+    let mut deflated = vec![1];
+    deflated.extend(len.to_le_bytes());
+    deflated.extend((!len).to_le_bytes());
+    deflated.extend(plain);
+    block(&deflated, plain)
+}
+
+/// The empty block ending BGZF files.
+fn eof() -> Vec<u8> {
+    block(&[3, 0], b"")
+}
+
+fn bam_header(text: &str, references: &[(&str, u32)]) -> Vec<u8> {
+    let mut out = b"BAM\x01".to_vec();
+    out.extend((text.len() as i32).to_le_bytes());
+    out.extend(text.as_bytes());
+    out.extend((references.len() as i32).to_le_bytes());
+    for (name, length) in references {
+        out.extend((name.len() as i32 + 1).to_le_bytes());
+        out.extend(name.as_bytes());
+        out.push(0);
+        out.extend(length.to_le_bytes());
+    }
+    out
+}
+
+struct Record<'a> {
+    name: &'a str,
+    flags: u16,
+    reference: i32,
+    pos: i32,
+    mapq: u8,
+    cigar: Vec<(u32, u32)>,
+    seq: &'a str,
+    qual: Option<Vec<u8>>,
+    tags: Vec<u8>,
+}
+
+impl Record<'_> {
+    fn new(name: &str, reference: i32, pos: i32, cigar: Vec<(u32, u32)>) -> Record<'_> {
+        Record {
+            name,
+            flags: 0,
+            reference,
+            pos,
+            mapq: 60,
+            cigar,
+            seq: "",
+            qual: None,
+            tags: Vec::new(),
+        }
+    }
+
+    fn span(&self) -> usize {
+        self.cigar
+            .iter()
+            .filter(|(op, _)| [0, 2, 3, 7, 8].contains(op))
+            .map(|&(_, n)| n as usize)
+            .sum()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let start = self.pos.max(0) as usize;
+        let end = start + self.span().max(1);
+        let mut data = Vec::new();
+        data.extend(self.reference.to_le_bytes());
+        data.extend(self.pos.to_le_bytes());
+        data.push(self.name.len() as u8 + 1);
+        data.push(self.mapq);
+        data.extend((reg2bin(start, end) as u16).to_le_bytes());
+        data.extend((self.cigar.len() as u16).to_le_bytes());
+        data.extend(self.flags.to_le_bytes());
+        data.extend((self.seq.len() as u32).to_le_bytes());
+        data.extend((-1i32).to_le_bytes());
+        data.extend((-1i32).to_le_bytes());
+        data.extend(0i32.to_le_bytes());
+        data.extend(self.name.as_bytes());
+        data.push(0);
+        for &(op, n) in &self.cigar {
+            data.extend((n << 4 | op).to_le_bytes());
+        }
+        let codes: Vec<u8> = self
+            .seq
+            .bytes()
+            .map(|b| "=ACMGRSVTWYHKDBN".bytes().position(|c| c == b).unwrap() as u8)
+            .collect();
+        for pair in codes.chunks(2) {
+            data.push(pair[0] << 4 | pair.get(1).copied().unwrap_or(0));
+        }
+        match &self.qual {
+            Some(qual) => data.extend(qual),
+            None => data.extend(std::iter::repeat_n(0xFF, self.seq.len())),
+        }
+        data.extend(&self.tags);
+
+        let mut out = (data.len() as u32).to_le_bytes().to_vec();
+        out.extend(data);
+        out
+    }
+}
+
+fn read_all(bam: Vec<u8>) -> Vec<SamRecord> {
+    BamReader::new(Cursor::new(bam))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+// -- BGZF --------------------------------------------------------------------
+
+#[test]
+fn inflates_all_block_types() {
+    let bases = lcg_bases(300).repeat(2);
+    let mut file = stored(b"stored ");
+    file.extend(block(&FIXED, &fixed_text()));
+    file.extend(eof());
+    file.extend(block(&DYNAMIC, &bases));
+    file.extend(eof());
+
+    let mut text = Vec::new();
+    BgzfReader::new(Cursor::new(file))
+        .read_to_end(&mut text)
+        .unwrap();
+    let mut expected = b"stored ".to_vec();
+    expected.extend(fixed_text());
+    expected.extend(&bases);
+    assert_eq!(text, expected);
+}
+
+#[test]
+fn seeks_to_virtual_offsets() {
+    let first = stored(b"0123456789");
+    let mut file = first.clone();
+    file.extend(stored(b"abcdef"));
+    file.extend(eof());
+
+    let mut reader = BgzfReader::new(Cursor::new(file));
+    let mut buf = [0u8; 10];
+    reader.read_exact(&mut buf).unwrap();
+    // The end of a block is the start of the next one
+    let next = (first.len() as u64) << 16;
+    assert_eq!(reader.virtual_offset(), next);
+
+    reader.seek_virtual(next | 2).unwrap();
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "cdef");
+
+    reader.seek_virtual(7).unwrap();
+    reader.read_exact(&mut buf[..3]).unwrap();
+    assert_eq!(&buf[..3], b"789");
+    assert!(reader.seek_virtual(11).is_err());
+}
+
+#[test]
+fn rejects_corrupt_blocks() {
+    let mut file = stored(b"ACGT");
+    let crc = file.len() - 8;
+    file[crc] ^= 1;
+    let mut text = Vec::new();
+    let error = BgzfReader::new(Cursor::new(file)).read_to_end(&mut text);
+    assert!(error.is_err());
+
+    let mut truncated = block(&DYNAMIC, &lcg_bases(300).repeat(2));
+    truncated.truncate(60);
+    let error = BgzfReader::new(Cursor::new(truncated)).read_to_end(&mut text);
+    assert!(error.is_err());
+
+    assert!(BamReader::new(Cursor::new(stored(b"BAM\x02"))).is_err());
+
+    // ISIZE smaller than the inflated data stops decompression early
+    let bases = lcg_bases(300).repeat(2);
+    let understated = block(&DYNAMIC, &bases[..10]);
+    let error = BgzfReader::new(Cursor::new(understated))
+        .read_to_end(&mut text)
+        .unwrap_err();
+    assert!(error.to_string().contains("exceeds its expected size"));
+
+    // Huge header lengths fail at the end of the data instead of allocating
+    for header in [
+        [b"BAM\x01".as_slice(), &i32::MAX.to_le_bytes()].concat(),
+        [
+            b"BAM\x01\0\0\0\0\x01\0\0\0".as_slice(),
+            &i32::MAX.to_le_bytes(),
+        ]
+        .concat(),
+    ] {
+        let mut file = stored(&header);
+        file.extend(eof());
+        let Err(error) = BamReader::new(Cursor::new(file)) else {
+            panic!("header lengths past the end of the data were accepted");
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+}
+
+// -- Records -----------------------------------------------------------------
+
+#[test]
+fn reads_header_and_records() {
+    let mut data = bam_header(
+        "@HD\tVN:1.6\tSO:coordinate\n@RG\tID:rg1\tSM:s\n\0\0",
+        &[("chr1", 1000), ("chr2", 500)],
+    );
+    let mut first = Record::new("r1", 0, 9, vec![(4, 2), (0, 5), (1, 1), (0, 2), (2, 3)]);
+    first.flags = 0x10;
+    first.seq = "ACGTNRYACG";
+    first.qual = Some((20..30).collect());
+    first.tags = b"RGZrg1\0NMC\x04".to_vec();
+    data.extend(first.encode());
+    let mut second = Record::new("r2", 1, 0, vec![(7, 3)]);
+    second.mapq = 255;
+    second.seq = "=TG";
+    data.extend(second.encode());
+    data.extend(Record::new("u", -1, -1, Vec::new()).encode());
+
+    let mut file = stored(&data);
+    file.extend(eof());
+    let reader = BamReader::new(Cursor::new(file.clone())).unwrap();
+    let header = reader.header();
+    assert_eq!(header.sort_order.as_deref(), Some("coordinate"));
+    assert_eq!(header.read_group("rg1").unwrap().get("SM"), Some("s"));
+    // References come from the binary list when the text has no @SQ
+    assert_eq!(header.references.len(), 2);
+    assert_eq!(header.references[1].name, "chr2");
+    assert_eq!(header.references[1].length, 500);
+
+    let records = read_all(file);
+    assert_eq!(records.len(), 3);
+    let r = &records[0];
+    assert_eq!(r.name, "r1");
+    assert!(r.flags.contains(Flags::REVERSE));
+    assert_eq!(
+        (r.reference.as_deref(), r.pos, r.mapq),
+        (Some("chr1"), Some(9), Some(60))
+    );
+    assert_eq!(
+        r.cigar.ops(),
+        [
+            (CigarOp::SoftClip, 2),
+            (CigarOp::Match, 5),
+            (CigarOp::Insertion, 1),
+            (CigarOp::Match, 2),
+            (CigarOp::Deletion, 3),
+        ]
+    );
+    // Ambiguity codes and `=` read as N
+    assert_eq!(r.seq.to_string(), "ACGTNNNACG");
+    assert_eq!(r.qual, Some((20..30).collect::<Vec<u8>>()));
+    assert_eq!(r.tag(b"RG"), Some(&TagValue::String("rg1".to_string())));
+    assert_eq!(r.tag(b"NM"), Some(&TagValue::Int(4)));
+
+    let r = &records[1];
+    assert_eq!(
+        (r.reference.as_deref(), r.mapq, r.qual.as_ref()),
+        (Some("chr2"), None, None)
+    );
+    assert_eq!(r.seq.to_string(), "NTG");
+
+    let r = &records[2];
+    assert_eq!((r.reference.as_ref(), r.pos), (None, None));
+    assert!(r.seq.is_empty() && r.cigar.is_empty());
+}
+
+#[test]
+fn decodes_binary_tags() {
+    let mut tags = Vec::new();
+    tags.extend(b"XAAx");
+    tags.extend(b"Xcc\xFE");
+    tags.extend(b"XssXX");
+    tags.extend(b"XSS\xFF\xFF");
+    tags.extend(b"XIi");
+    tags.extend((-70000i32).to_le_bytes());
+    tags.extend(b"XUI");
+    tags.extend(u32::MAX.to_le_bytes());
+    tags.extend(b"XFf");
+    tags.extend(1.5f32.to_le_bytes());
+    tags.extend(b"XHH1AE3\0");
+    tags.extend(b"XBBs");
+    tags.extend(2u32.to_le_bytes());
+    tags.extend((-2i16).to_le_bytes());
+    tags.extend(300i16.to_le_bytes());
+    tags.extend(b"XGBf");
+    tags.extend(1u32.to_le_bytes());
+    tags.extend(0.25f32.to_le_bytes());
+    let mut record = Record::new("t", -1, -1, Vec::new());
+    record.tags = tags;
+
+    let mut data = bam_header("", &[]);
+    data.extend(record.encode());
+    let r = &read_all(stored(&data))[0];
+    assert_eq!(r.tag(b"XA"), Some(&TagValue::Char('x')));
+    assert_eq!(r.tag(b"Xc"), Some(&TagValue::Int(-2)));
+    assert_eq!(r.tag(b"Xs"), Some(&TagValue::Int(0x5858)));
+    assert_eq!(r.tag(b"XS"), Some(&TagValue::Int(65535)));
+    assert_eq!(r.tag(b"XI"), Some(&TagValue::Int(-70000)));
+    assert_eq!(r.tag(b"XU"), Some(&TagValue::Int(u32::MAX as i64)));
+    assert_eq!(r.tag(b"XF"), Some(&TagValue::Float(1.5)));
+    assert_eq!(r.tag(b"XH"), Some(&TagValue::Hex(vec![0x1A, 0xE3])));
+    assert_eq!(
+        r.tag(b"XB"),
+        Some(&TagValue::Array(TagArray::Int16(vec![-2, 300])))
+    );
+    assert_eq!(
+        r.tag(b"XG"),
+        Some(&TagValue::Array(TagArray::Float(vec![0.25])))
+    );
+}
+
+#[test]
+fn restores_long_cigars_from_tags() {
+    // A placeholder CIGAR `<len>S<span>N` points to the CG tag
+    let mut record = Record::new("long", 0, 0, vec![(4, 4), (3, 6)]);
+    record.seq = "ACGT";
+    record.tags = b"CGBI".to_vec();
+    record.tags.extend(2u32.to_le_bytes());
+    record.tags.extend((3u32 << 4).to_le_bytes());
+    record.tags.extend((1u32 << 4 | 1).to_le_bytes());
+
+    let mut data = bam_header("", &[("chr1", 100)]);
+    data.extend(record.encode());
+    let r = &read_all(stored(&data))[0];
+    assert_eq!(r.cigar.to_string(), "3M1I");
+    assert_eq!(r.tag(b"CG"), None);
+}
+
+// -- Index -------------------------------------------------------------------
+
+/// Writes each record into its own block and indexes the file.
+fn indexed(references: &[(&str, u32)], records: &[Record]) -> (Vec<u8>, Vec<u8>) {
+    let mut file = stored(&bam_header("", references));
+    let mut bins =
+        vec![std::collections::BTreeMap::<u32, Vec<(u64, u64)>>::new(); references.len()];
+    let mut intervals = vec![Vec::<u64>::new(); references.len()];
+    for record in records {
+        let start = (file.len() as u64) << 16;
+        file.extend(stored(&record.encode()));
+        let end = (file.len() as u64) << 16;
+
+        let reference = record.reference as usize;
+        let (pos, span) = (record.pos as usize, record.span().max(1));
+        bins[reference]
+            .entry(reg2bin(pos, pos + span))
+            .or_default()
+            .push((start, end));
+        let windows = &mut intervals[reference];
+        for window in pos >> 14..=(pos + span - 1) >> 14 {
+            if windows.len() <= window {
+                windows.resize(window + 1, 0);
+            }
+            if windows[window] == 0 {
+                windows[window] = start;
+            }
+        }
+    }
+    file.extend(eof());
+
+    let mut index = b"BAI\x01".to_vec();
+    index.extend((references.len() as i32).to_le_bytes());
+    for (reference, bins) in bins.iter().enumerate() {
+        index.extend((bins.len() as i32 + 1).to_le_bytes());
+        for (bin, chunks) in bins {
+            index.extend(bin.to_le_bytes());
+            index.extend((chunks.len() as i32).to_le_bytes());
+            for (start, end) in chunks {
+                index.extend(start.to_le_bytes());
+                index.extend(end.to_le_bytes());
+            }
+        }
+        let mapped = records
+            .iter()
+            .filter(|r| r.reference == reference as i32)
+            .count();
+        index.extend(37450u32.to_le_bytes());
+        index.extend(2i32.to_le_bytes());
+        for value in [0, 0, mapped as u64, 0] {
+            index.extend(value.to_le_bytes());
+        }
+        index.extend((intervals[reference].len() as i32).to_le_bytes());
+        for offset in &intervals[reference] {
+            index.extend(offset.to_le_bytes());
+        }
+    }
+    index.extend(0u64.to_le_bytes());
+    (file, index)
+}
+
+#[test]
+fn parses_index_metadata() {
+    let records = [
+        Record::new("a", 0, 5, vec![(0, 10)]),
+        Record::new("b", 1, 20000, vec![(0, 10)]),
+        Record::new("c", 1, 40000, vec![(0, 10)]),
+    ];
+    let (_, index) = indexed(&[("chr1", 100), ("chr2", 50000)], &records);
+    let index = BamIndex::read_from(Cursor::new(index)).unwrap();
+    assert_eq!(index.reference_count(), 2);
+    assert_eq!(index.read_counts(1), Some((2, 0)));
+    assert_eq!(index.unplaced_count(), Some(0));
+    assert_eq!(index.chunks(0, 50..60).len(), 1);
+    assert!(index.chunks(0, 20000..20010).is_empty());
+    assert_eq!(index.chunks(1, 30000..30001).len(), 1);
+    assert!(BamIndex::read_from(Cursor::new(b"BAM\x01")).is_err());
+}
+
+#[test]
+fn queries_unknown_references() {
+    let (file, index) = indexed(&[("chr1", 100)], &[]);
+    let index = BamIndex::read_from(Cursor::new(index)).unwrap();
+    let mut reader = BamReader::new(Cursor::new(file)).unwrap();
+    assert!(reader.query(&index, "chrX", 0..10).is_err());
+    assert_eq!(reader.query(&index, "chr1", 0..10).unwrap().count(), 0);
+}
+
+proptest! {
+    #[test]
+    fn queries_match_a_scan(
+        mut reads in prop::collection::vec((0i32..2, 0i32..300_000, 1u32..40_000, any::<bool>()), 0..40),
+        reference in 0usize..2,
+        start in 0usize..300_000,
+        len in 1usize..60_000,
+    ) {
+        reads.sort();
+        let names: Vec<String> = (0..reads.len()).map(|i| format!("r{i}")).collect();
+        let records: Vec<Record> = reads
+            .iter()
+            .zip(&names)
+            .map(|(&(reference, pos, len, gapped), name)| {
+                let cigar = if gapped {
+                    vec![(4, 5), (0, 20), (3, len), (0, 30)]
+                } else {
+                    vec![(0, len)]
+                };
+                Record::new(name, reference, pos, cigar)
+            })
+            .collect();
+        let (file, index) = indexed(&[("chr1", 400_000), ("chr2", 400_000)], &records);
+        let index = BamIndex::read_from(Cursor::new(index)).unwrap();
+        let mut reader = BamReader::new(Cursor::new(file)).unwrap();
+
+        let range = start..start + len;
+        let name = ["chr1", "chr2"][reference];
+        let found: Vec<String> = reader
+            .query(&index, name, range.clone())
+            .unwrap()
+            .map(|r| r.unwrap().name)
+            .collect();
+        let expected: Vec<String> = records
+            .iter()
+            .filter(|r| r.reference == reference as i32)
+            .filter(|r| (r.pos as usize) < range.end && r.pos as usize + r.span() > range.start)
+            .map(|r| r.name.to_string())
+            .collect();
+        prop_assert_eq!(found, expected);
+    }
+}
@@ -1,42 +1,117 @@
 use nuc::{
-    dna::Dna,
-    io::fasta::{FastaDna, FastaReader},
+    alphabet::{Nuc4, Nuc5},
+    io::fasta::{FastaReader, FastaRecord, FastaWriter},
+    seq::Seq,
+    softmask::CaseMask,
 };
 
+fn read_all<A: nuc::alphabet::Alphabet>(input: &str, soft_mask: bool) -> Vec<FastaRecord<A>> {
+    FastaReader::new(input.as_bytes())
+        .with_soft_mask(soft_mask)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
 #[test]
 fn can_read_an_example_fasta_file() {
-    let reader = FastaReader::new(
+    let records = read_all::<Nuc4>(
         ">Some Identifier\n\
                          ATGCCGTA\n\
                          >Another Identifier\n\
                          CTAACGAA\n\
                          >Yet Another Identifier\n\
                          ATAC\n\
-                         ATAAGTAGGG"
-            .as_bytes()
+                         ATAAGTAGGG",
+        false,
     );
 
     assert_eq!(
-        reader.into_iter().collect::<Vec<_>>(),
+        records,
         vec![
-            FastaDna {
-                id: "Some Identifier".to_string(),
-                sequence: Dna::from_ascii("ATGCCGTA")
-            },
-            FastaDna {
-                id: "Another Identifier".to_string(),
-                sequence: Dna::from_ascii("CTAACGAA")
-            },
-            FastaDna {
-                id: "Yet Another Identifier".to_string(),
-                sequence: Dna::from_ascii("ATACATAAGTAGGG")
-            },
+            FastaRecord::new(
+                "Some Identifier".to_string(),
+                Seq::try_from("ATGCCGTA").unwrap()
+            ),
+            FastaRecord::new(
+                "Another Identifier".to_string(),
+                Seq::try_from("CTAACGAA").unwrap()
+            ),
+            FastaRecord::new(
+                "Yet Another Identifier".to_string(),
+                Seq::try_from("ATACATAAGTAGGG").unwrap()
+            ),
         ]
     );
 }
 
 #[test]
 fn can_read_an_empty_fasta_file() {
-    let records = FastaReader::new("".as_bytes()).into_iter().collect::<Vec<_>>();
+    let records = read_all::<Nuc4>("", false);
     assert_eq!(records, vec![]);
 }
+
+#[test]
+fn invalid_symbols_are_reported() {
+    let mut reader = FastaReader::<_, Nuc4>::new(">id\nACGN\n".as_bytes());
+    assert!(reader.next().unwrap().is_err());
+}
+
+#[test]
+fn soft_mask_is_recorded_while_parsing() {
+    let records = read_all::<Nuc5>(">chr\nACgtn\nnnAC\n>plain\nACGT\n", true);
+
+    assert_eq!(records[0].sequence.to_string(), "ACGTNNNAC");
+    let mask = records[0].mask.as_ref().unwrap();
+    assert_eq!(mask.intervals().collect::<Vec<_>>(), vec![2..7]);
+    assert_eq!(mask.count_masked(), 5);
+
+    let mask = records[1].mask.as_ref().unwrap();
+    assert_eq!(mask.len(), 4);
+    assert_eq!(mask.intervals().count(), 0);
+}
+
+#[test]
+fn soft_mask_is_dropped_by_default() {
+    let records = read_all::<Nuc4>(">chr\nacgt\n", false);
+    assert_eq!(records[0].mask, None);
+}
+
+#[test]
+fn writer_wraps_lines() {
+    let record = FastaRecord::new(
+        "id".to_string(),
+        Seq::<Nuc4>::try_from("ACGTACGTAC").unwrap(),
+    );
+    let mut writer = FastaWriter::new(Vec::new()).with_line_width(4);
+    writer.write_record(&record).unwrap();
+    let out = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    assert_eq!(out, ">id\nACGT\nACGT\nAC\n");
+}
+
+#[test]
+fn writer_restores_soft_mask() {
+    let mut record = FastaRecord::new(
+        "id".to_string(),
+        Seq::<Nuc5>::try_from("ACGTNACGT").unwrap(),
+    );
+    record.mask = Some(CaseMask::from_intervals(9, [0..2, 4..6]));
+    let mut writer = FastaWriter::new(Vec::new());
+    writer.write_record(&record).unwrap();
+    let out = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    assert_eq!(out, ">id\nacGTnaCGT\n");
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn soft_masked_roundtrip(s in "[ACGTNacgtn]{1,200}", width in 1usize..80) {
+        let input = format!(">seq\n{s}\n");
+        let records = read_all::<Nuc5>(&input, true);
+        let mut writer = FastaWriter::new(Vec::new()).with_line_width(width);
+        writer.write_record(&records[0]).unwrap();
+        let out = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let body: String = out.lines().skip(1).collect();
+        assert_eq!(body, s);
+    }
+
+}
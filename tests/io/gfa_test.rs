@@ -0,0 +1,351 @@
+use std::io::Cursor;
+
+use proptest::prelude::*;
+
+use nuc::{
+    align::CigarError,
+    alphabet::{Nuc4, Nuc5, Strand},
+    graph::{Containment, DeBruijnGraph, Gfa, GfaError, Handle, Link, Path, Segment},
+    io::gfa::{read, write, GfaVersion},
+    seq::Seq,
+};
+
+const GFA1: &str = "\
+H\tVN:Z:1.0
+S\ts1\tACGTA
+S\ts2\tTACCG\tRC:i:4
+S\ts3\tGGTTC
+L\ts1\t+\ts2\t+\t2M
+L\ts2\t+\ts3\t-\t1M\tID:Z:e2
+C\ts1\t+\ts4\t+\t1\t3M
+P\tp1\ts1+,s2+,s3-\t2M,1M
+S\ts4\tCGT
+";
+
+const GFA2: &str = "\
+H\tVN:Z:2.0
+S\ts1\t5\tACGTA
+S\ts2\t5\tTACCG\tRC:i:4
+S\ts3\t5\tGGTTC
+S\ts4\t3\tCGT
+E\t*\ts1+\ts2+\t3\t5$\t0\t2\t2M
+E\t*\ts2+\ts3-\t4\t5$\t4\t5$\t1M\tID:Z:e2
+E\t*\ts1+\ts4+\t1\t4\t0\t3$\t3M
+O\tp1\ts1+ s2+ s3-
+";
+
+fn parse(text: &str) -> Gfa {
+    read(Cursor::new(text)).unwrap()
+}
+
+fn handle(gfa: &Gfa, step: &str) -> Handle {
+    let (name, sign) = step.split_at(step.len() - 1);
+    let strand = if sign == "+" {
+        Strand::Forward
+    } else {
+        Strand::Reverse
+    };
+    Handle::new(gfa.segment_id(name).unwrap(), strand)
+}
+
+fn walk(gfa: &Gfa, steps: &[&str]) -> Result<String, GfaError> {
+    let steps: Vec<Handle> = steps.iter().map(|s| handle(gfa, s)).collect();
+    gfa.spell(&steps).map(|s| s.to_string())
+}
+
+fn to_text(gfa: &Gfa, version: GfaVersion) -> String {
+    let mut out = Vec::new();
+    write(&mut out, gfa, version).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn reads_gfa1_records() {
+    let gfa = parse(GFA1);
+    assert_eq!(gfa.segments().len(), 4);
+    let s2 = gfa.segment("s2").unwrap();
+    assert_eq!(s2.seq.as_ref().unwrap().to_string(), "TACCG");
+    assert_eq!(s2.tags, vec!["RC:i:4"]);
+
+    assert_eq!(gfa.links().len(), 2);
+    let link = &gfa.links()[1];
+    assert_eq!(link.from, handle(&gfa, "s2+"));
+    assert_eq!(link.to, handle(&gfa, "s3-"));
+    assert_eq!(link.overlap, "1M");
+    assert_eq!(link.tags, vec!["ID:Z:e2"]);
+
+    // s4 is defined after the containment refers to it
+    assert_eq!(
+        gfa.containments(),
+        &[Containment {
+            container: handle(&gfa, "s1+"),
+            contained: handle(&gfa, "s4+"),
+            pos: 1,
+            overlap: "3M".to_string(),
+        }]
+    );
+
+    let path = gfa.path("p1").unwrap();
+    assert_eq!(path.steps.len(), 3);
+    assert_eq!(path.overlaps, vec!["2M", "1M"]);
+}
+
+#[test]
+fn reads_gfa2_records() {
+    let gfa1 = parse(GFA1);
+    let gfa2 = parse(GFA2);
+    assert_eq!(gfa2.links(), gfa1.links());
+    assert_eq!(gfa2.containments(), gfa1.containments());
+    assert_eq!(
+        gfa2.path("p1").unwrap().steps,
+        gfa1.path("p1").unwrap().steps
+    );
+    assert!(gfa2.path("p1").unwrap().overlaps.is_empty());
+}
+
+#[test]
+fn segments_may_omit_sequences() {
+    let gfa = parse("S\ta\t*\tLN:i:12\nS\tb\t7\t*\n");
+    assert_eq!(gfa.segment("a").unwrap().length, 12);
+    assert_eq!(gfa.segment("b").unwrap().length, 7);
+    assert!(gfa.segment("b").unwrap().seq.is_none());
+    assert!(matches!(
+        walk(&gfa, &["a+"]),
+        Err(GfaError::MissingSequence(name)) if name == "a"
+    ));
+    // The length is kept as a tag when writing GFA 1
+    assert!(to_text(&gfa, GfaVersion::V1).contains("S\tb\t*\tLN:i:7\n"));
+}
+
+#[test]
+fn gfa2_edges_without_cigars_overlap_by_position() {
+    let text =
+        "S\ta\t10\tACGTACGTAA\nS\tb\t8\tGTAAGGGG\nE\t*\ta+\tb+\t6\t10$\t0\t4\t*\nO\tp\ta+ b+\n";
+    let gfa = parse(text);
+    assert_eq!(gfa.links()[0].overlap, "4M");
+    assert_eq!(
+        gfa.spell_path("p").unwrap().unwrap().to_string(),
+        "ACGTACGTAAGGGG"
+    );
+
+    // Traces stand in for CIGARs too; unequal spans add an indel
+    let traced = parse("S\ta\t10\tACGTACGTAA\nS\tb\t8\tGTAAGGGG\nE\t*\tb-\ta-\t3\t8$\t0\t4\t2,2\n");
+    let link = &traced.links()[0];
+    assert_eq!(
+        (link.from, link.to),
+        (handle(&traced, "a-"), handle(&traced, "b-"))
+    );
+    assert_eq!(link.overlap, "4M1I");
+}
+
+#[test]
+fn spells_paths_through_both_strands() {
+    let gfa = parse(GFA1);
+    let forward = gfa.spell_path("p1").unwrap().unwrap().to_string();
+    assert_eq!(forward, "ACGTACCGAACC");
+    // Walking the path backwards uses the links in reverse
+    let backward = walk(&gfa, &["s3+", "s2-", "s1-"]).unwrap();
+    assert_eq!(backward, "GGTTCGGTACGT");
+    assert_eq!(
+        Seq::<Nuc5>::try_from(backward.as_str())
+            .unwrap()
+            .reverse_complement()
+            .to_string(),
+        forward
+    );
+    assert!(gfa.spell_path("p2").is_none());
+}
+
+#[test]
+fn walks_require_links() {
+    let gfa = parse(GFA1);
+    assert!(matches!(
+        walk(&gfa, &["s1+", "s3+"]),
+        Err(GfaError::MissingLink { from, to }) if from == "s1+" && to == "s3+"
+    ));
+    assert_eq!(walk(&gfa, &[]).unwrap(), "");
+}
+
+#[test]
+fn follows_links_in_either_direction() {
+    let gfa = parse(GFA1);
+    let next = |step| {
+        gfa.successors(handle(&gfa, step))
+            .map(|(h, _)| h)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(next("s2+"), vec![handle(&gfa, "s3-")]);
+    assert_eq!(next("s3+"), vec![handle(&gfa, "s2-")]);
+    assert_eq!(next("s1-"), vec![]);
+    let previous: Vec<Handle> = gfa
+        .predecessors(handle(&gfa, "s2+"))
+        .map(|(h, _)| h)
+        .collect();
+    assert_eq!(previous, vec![handle(&gfa, "s1+")]);
+}
+
+#[test]
+fn reports_bad_records() {
+    let unknown = read(Cursor::new("S\ta\tACGT\nL\ta\t+\tb\t+\t*\n"));
+    assert!(matches!(unknown, Err(GfaError::UnknownSegment(name)) if name == "b"));
+
+    let sign = read(Cursor::new("S\ta\tACGT\nL\ta\t+\ta\tx\t*\n"));
+    assert!(matches!(sign, Err(GfaError::Parse { line: 2, .. })));
+
+    let short = read(Cursor::new("S\ta\n"));
+    assert!(matches!(short, Err(GfaError::Parse { line: 1, .. })));
+
+    let duplicate = read(Cursor::new("S\ta\tACGT\nS\ta\tGG\n"));
+    assert!(matches!(duplicate, Err(GfaError::DuplicateSegment(_))));
+
+    assert!(matches!(
+        read(Cursor::new("S\ta\tAC-T\n")),
+        Err(GfaError::Seq(_))
+    ));
+}
+
+#[test]
+fn reports_bad_overlaps() {
+    for text in [
+        "S\ta\tACGT\nS\tb\tGT\nL\ta\t+\tb\t+\t2Q\n",
+        "S\ta\tACGT\nS\tb\tGT\nC\ta\t+\tb\t+\t1\tM\n",
+        "S\ta\tACGT\nS\tb\tGT\nP\tp\ta+,b+\t2M3\n",
+        "S\ta\t4\tACGT\nS\tb\t2\tGT\nE\t*\ta+\tb+\t2\t4$\t0\t2\t2Y\n",
+    ] {
+        assert!(matches!(
+            read(Cursor::new(text)),
+            Err(GfaError::Parse { line: 3, .. })
+        ));
+    }
+
+    // Overlaps built in code are checked when they are used
+    let mut gfa = parse("S\ta\tACGT\nS\tb\tGT\n");
+    gfa.add_link(Link::new(handle(&gfa, "a+"), handle(&gfa, "b+"), "2"));
+    assert!(matches!(
+        walk(&gfa, &["a+", "b+"]),
+        Err(GfaError::Cigar(CigarError::TrailingLength))
+    ));
+    let error = write(Vec::new(), &gfa, GfaVersion::V2).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn round_trips_both_versions() {
+    let gfa = parse(GFA1);
+    assert_eq!(parse(&to_text(&gfa, GfaVersion::V1)), gfa);
+
+    // Ordered groups carry no overlaps
+    let mut expected = Gfa::new();
+    for segment in gfa.segments() {
+        expected.add_segment(segment.clone()).unwrap();
+    }
+    for link in gfa.links() {
+        expected.add_link(link.clone());
+    }
+    for containment in gfa.containments() {
+        expected.add_containment(containment.clone());
+    }
+    for path in gfa.paths() {
+        expected.add_path(Path {
+            overlaps: Vec::new(),
+            ..path.clone()
+        });
+    }
+    assert_eq!(parse(&to_text(&gfa, GfaVersion::V2)), expected);
+}
+
+#[test]
+fn writes_gfa1() {
+    let mut gfa = Gfa::new();
+    let a = gfa
+        .add_segment(Segment::new("a", Seq::try_from("ACGN").unwrap()))
+        .unwrap();
+    let b = gfa
+        .add_segment(Segment::new("b", Seq::try_from("GTT").unwrap()))
+        .unwrap();
+    gfa.add_link(Link::new(
+        Handle::new(a, Strand::Forward),
+        Handle::new(b, Strand::Reverse),
+        "*",
+    ));
+    gfa.add_path(Path {
+        name: "p".to_string(),
+        steps: vec![
+            Handle::new(a, Strand::Forward),
+            Handle::new(b, Strand::Reverse),
+        ],
+        overlaps: Vec::new(),
+    });
+    assert_eq!(
+        to_text(&gfa, GfaVersion::V1),
+        "H\tVN:Z:1.0\nS\ta\tACGN\nS\tb\tGTT\nL\ta\t+\tb\t-\t*\nP\tp\ta+,b-\t*\n"
+    );
+    assert_eq!(gfa.spell_path("p").unwrap().unwrap().to_string(), "ACGNAAC");
+}
+
+#[test]
+fn reads_unitig_graphs() {
+    let reads: Vec<Seq<Nuc4>> = ["GATTCCAGAGTTCGTC", "GATTCCATAGTTCGTC"]
+        .iter()
+        .map(|r| Seq::try_from(*r).unwrap())
+        .collect();
+    let unitigs = DeBruijnGraph::from_seqs(&reads, 5).compact();
+    let mut out = Vec::new();
+    unitigs.write_gfa(&mut out).unwrap();
+    let gfa = read(Cursor::new(out)).unwrap();
+
+    assert_eq!(gfa.segments().len(), unitigs.len());
+    for link in gfa.links() {
+        let spelled = gfa.spell(&[link.from, link.to]).unwrap();
+        let lengths = gfa.segments()[link.from.node].length + gfa.segments()[link.to.node].length;
+        assert_eq!(spelled.len(), lengths - 4);
+    }
+}
+
+proptest! {
+    #[test]
+    fn spells_overlapping_pieces(
+        genome in "[ACGTN]{30,80}",
+        cuts in prop::collection::vec((3usize..12, 0usize..3, any::<bool>()), 1..8),
+    ) {
+        // Cut the genome into overlapping pieces, storing some reversed
+        let mut gfa = Gfa::new();
+        let mut steps = Vec::new();
+        let mut overlaps = Vec::new();
+        let (mut start, mut previous_overlap) = (0, 0);
+        for (i, &(len, overlap, reversed)) in cuts.iter().enumerate() {
+            let start_here = start - previous_overlap;
+            let end = (start_here + len.max(previous_overlap + 1)).min(genome.len());
+            if end <= start {
+                break;
+            }
+            let piece = Seq::<Nuc5>::try_from(&genome[start_here..end]).unwrap();
+            let (stored, strand) = if reversed {
+                (piece.reverse_complement(), Strand::Reverse)
+            } else {
+                (piece, Strand::Forward)
+            };
+            let id = gfa.add_segment(Segment::new(&format!("s{i}"), stored)).unwrap();
+            let step = Handle::new(id, strand);
+            if let Some(&last) = steps.last() {
+                gfa.add_link(Link::new(last, step, &format!("{previous_overlap}M")));
+                overlaps.push(format!("{previous_overlap}M"));
+            }
+            steps.push(step);
+            start = end;
+            previous_overlap = overlap.min(end - start_here - 1);
+        }
+        let spelled = gfa.spell(&steps).unwrap().to_string();
+        prop_assert_eq!(spelled, &genome[..start]);
+
+        // The reverse walk spells the reverse complement
+        let back: Vec<Handle> = steps.iter().rev().map(|h| h.flip()).collect();
+        let reverse = gfa.spell(&back).unwrap();
+        prop_assert_eq!(reverse.reverse_complement().to_string(), &genome[..start]);
+
+        gfa.add_path(Path { name: "p".to_string(), steps, overlaps });
+        prop_assert_eq!(parse(&to_text(&gfa, GfaVersion::V1)), gfa.clone());
+        let reread = parse(&to_text(&gfa, GfaVersion::V2));
+        prop_assert_eq!(reread.links(), gfa.links());
+    }
+}
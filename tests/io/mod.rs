@@ -1,2 +1,8 @@
-// pub mod fasta_test;
+pub mod archive_test;
+pub mod bam_test;
+pub mod fasta_test;
 // pub mod fastq_test;
+pub mod gfa_test;
+pub mod msa_test;
+pub mod sam_test;
+pub mod twobit_test;
@@ -0,0 +1,147 @@
+use std::io::Cursor;
+
+use nuc::{
+    alphabet::{Nuc5, AA20},
+    io::msa::{read, write, MsaFormat},
+    msa::{Msa, MsaError},
+};
+
+const CLUSTAL: &str = "\
+CLUSTAL W (1.83) multiple sequence alignment
+
+
+seq1      ACGT-ACGTA 10
+seq2      ACGTTACG-A 10
+          ****.*** *
+
+seq1      CCGG
+seq2      CC-G
+          ** *
+";
+
+const STOCKHOLM: &str = "\
+# STOCKHOLM 1.0
+#=GF ID   example
+
+#=GS seq1 AC P00001
+seq1   MKV-LA
+seq2   MRVAL.
+#=GC SS_cons ......
+
+seq1   WW
+seq2   W-
+//
+";
+
+const PHYLIP_INTERLEAVED: &str = "\
+ 2 14
+seq1  ACGT-ACGTA
+seq2  ACGTTACG-A
+
+CCGG
+CC-G
+";
+
+fn parse<A: nuc::alphabet::Alphabet>(text: &str, format: MsaFormat) -> Result<Msa<A>, MsaError> {
+    read(Cursor::new(text), format)
+}
+
+fn dump<A: nuc::alphabet::Alphabet>(msa: &Msa<A>, format: MsaFormat) -> String {
+    let mut out = Vec::new();
+    write(&mut out, msa, format).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn reads_clustal_blocks() {
+    let msa = parse::<Nuc5>(CLUSTAL, MsaFormat::Clustal).unwrap();
+    assert_eq!(msa.names(), ["seq1", "seq2"]);
+    assert_eq!(msa.rows()[0].to_string(), "ACGT-ACGTACCGG");
+    assert_eq!(msa.rows()[1].to_string(), "ACGTTACG-ACC-G");
+}
+
+#[test]
+fn reads_stockholm_skipping_markup() {
+    let msa = parse::<AA20>(STOCKHOLM, MsaFormat::Stockholm).unwrap();
+    assert_eq!(msa.len(), 2);
+    assert_eq!(msa.rows()[1].to_string(), "MRVAL-W-");
+}
+
+#[test]
+fn reads_interleaved_phylip() {
+    let msa = parse::<Nuc5>(PHYLIP_INTERLEAVED, MsaFormat::Phylip).unwrap();
+    let clustal = parse::<Nuc5>(CLUSTAL, MsaFormat::Clustal).unwrap();
+    assert_eq!(msa, clustal);
+}
+
+#[test]
+fn reads_wrapped_sequential_phylip() {
+    let msa = parse::<Nuc5>("2 8\nseq1 ACGT\nACGT\nseq2 TTTT\nGGGG\n", MsaFormat::Phylip).unwrap();
+    assert_eq!(msa.names(), ["seq1", "seq2"]);
+    assert_eq!(msa.rows()[0].to_string(), "ACGTACGT");
+    assert_eq!(msa.rows()[1].to_string(), "TTTTGGGG");
+}
+
+#[test]
+fn reads_aligned_fasta() {
+    let msa = parse::<Nuc5>(">a desc\nAC-G\nT\n>b\nA--GT\n", MsaFormat::Fasta).unwrap();
+    assert_eq!(msa.names(), ["a desc", "b"]);
+    assert_eq!(msa.columns(), 5);
+}
+
+#[test]
+fn writes_clustal_with_conservation() {
+    let msa = parse::<Nuc5>(">a\nACGT\n>bb\nAC-A\n", MsaFormat::Fasta).unwrap();
+    assert_eq!(
+        dump(&msa, MsaFormat::Clustal),
+        "CLUSTAL W multiple sequence alignment\n\n\na     ACGT\nbb    AC-A\n      **  \n"
+    );
+    assert_eq!(
+        dump(&msa, MsaFormat::Stockholm),
+        "# STOCKHOLM 1.0\n\na   ACGT\nbb  AC-A\n//\n"
+    );
+    assert_eq!(
+        dump(&msa, MsaFormat::Phylip),
+        "2 4\na         ACGT\nbb        AC-A\n"
+    );
+}
+
+#[test]
+fn rejects_malformed_input() {
+    assert!(matches!(
+        parse::<Nuc5>("seq1 ACGT\n", MsaFormat::Clustal),
+        Err(MsaError::Parse { line: 1, .. })
+    ));
+    assert!(matches!(
+        parse::<Nuc5>(">a\nACGT\n>b\nAC\n", MsaFormat::Fasta),
+        Err(MsaError::LengthMismatch { .. })
+    ));
+    assert!(matches!(
+        parse::<Nuc5>("2 4\na ACGT\n", MsaFormat::Phylip),
+        Err(MsaError::Parse { .. })
+    ));
+    assert!(matches!(
+        parse::<Nuc5>("1 5\na ACGT\n", MsaFormat::Phylip),
+        Err(MsaError::LengthMismatch { .. })
+    ));
+    assert!(matches!(
+        parse::<Nuc5>("# STOCKHOLM 1.0\na ACXT\n//\n", MsaFormat::Stockholm),
+        Err(MsaError::Seq(_))
+    ));
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn formats_roundtrip(rows in proptest::collection::vec("[-ACDEFGHIKLMNPQRSTVWY]{150}", 1..6)) {
+        let mut msa = Msa::<AA20>::new();
+        for (i, row) in rows.iter().enumerate() {
+            msa.push(&format!("row_{i}"), row.parse().unwrap()).unwrap();
+        }
+        for format in [MsaFormat::Fasta, MsaFormat::Clustal, MsaFormat::Stockholm, MsaFormat::Phylip] {
+            let text = dump(&msa, format);
+            assert_eq!(parse::<AA20>(&text, format).unwrap(), msa, "{format:?}");
+        }
+    }
+
+}
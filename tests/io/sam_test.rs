@@ -0,0 +1,205 @@
+use std::io::Cursor;
+
+use proptest::prelude::*;
+
+use nuc::io::sam::{CigarOp, Flags, SamError, SamReader, SamRecord, TagArray, TagValue};
+
+const SAM: &str = "\
+@HD\tVN:1.6\tSO:coordinate
+@SQ\tSN:chr1\tLN:248956422\tAS:GRCh38
+@SQ\tSN:chrM\tLN:16569
+@RG\tID:rg1\tSM:NA12878\tPL:ILLUMINA
+@PG\tID:bwa\tPN:bwa\tVN:0.7.17\tCL:bwa mem ref.fa r1.fq
+@CO\tfree text\twith tabs
+r001\t99\tchr1\t7\t30\t8M2I4M1D3M\t=\t37\t39\tTTAGATAAAGGATACTG\t*\tNM:i:3\tRG:Z:rg1
+r002\t0\tchr1\t9\t255\t3S6M1P1I4M\t*\t0\t0\tAAAAGATAAGGRTA\t+,-./012345678\tXA:A:x\tXF:f:-1.5e2
+r003\t4\t*\t0\t0\t*\t*\t0\t0\t*\t*\tXB:B:c,-1,2,-3\tXH:H:1AE301\tXU:B:S,65535\tXG:B:f,0.5,2
+";
+
+fn records(text: &str) -> Vec<SamRecord> {
+    SamReader::new(Cursor::new(text))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn parses_header_lines() {
+    let reader = SamReader::new(Cursor::new(SAM)).unwrap();
+    let header = reader.header();
+    assert_eq!(header.version.as_deref(), Some("1.6"));
+    assert_eq!(header.sort_order.as_deref(), Some("coordinate"));
+
+    assert_eq!(header.references.len(), 2);
+    assert_eq!(header.references[0].name, "chr1");
+    assert_eq!(header.references[0].length, 248956422);
+    assert_eq!(
+        header.references[0].fields,
+        vec![("AS".to_string(), "GRCh38".to_string())]
+    );
+    assert_eq!(header.reference_id("chrM"), Some(1));
+
+    let rg = header.read_group("rg1").unwrap();
+    assert_eq!(rg.get("SM"), Some("NA12878"));
+    assert_eq!(rg.get("PL"), Some("ILLUMINA"));
+    assert_eq!(rg.get("LB"), None);
+
+    let pg = &header.programs[0];
+    assert_eq!(pg.id, "bwa");
+    assert_eq!(pg.get("VN"), Some("0.7.17"));
+    assert_eq!(pg.get("CL"), Some("bwa mem ref.fa r1.fq"));
+    assert_eq!(header.comments, vec!["free text\twith tabs"]);
+}
+
+#[test]
+fn parses_mandatory_fields() {
+    let records = records(SAM);
+    assert_eq!(records.len(), 3);
+
+    let r = &records[0];
+    assert_eq!(r.name, "r001");
+    assert_eq!(
+        r.flags,
+        Flags::PAIRED | Flags::PROPER_PAIR | Flags::MATE_REVERSE | Flags::FIRST_IN_PAIR
+    );
+    assert_eq!(r.reference.as_deref(), Some("chr1"));
+    assert_eq!(r.pos, Some(6));
+    assert_eq!(r.mapq, Some(30));
+    assert_eq!(
+        r.cigar.ops(),
+        [
+            (CigarOp::Match, 8),
+            (CigarOp::Insertion, 2),
+            (CigarOp::Match, 4),
+            (CigarOp::Deletion, 1),
+            (CigarOp::Match, 3),
+        ]
+    );
+    assert_eq!(r.cigar.to_string(), "8M2I4M1D3M");
+    assert_eq!(r.mate_reference.as_deref(), Some("chr1"));
+    assert_eq!(r.mate_pos, Some(36));
+    assert_eq!(r.template_len, 39);
+    assert_eq!(r.seq.to_string(), "TTAGATAAAGGATACTG");
+    assert_eq!(r.qual, None);
+
+    // Unavailable values
+    let r = &records[2];
+    assert!(r.flags.is_unmapped());
+    assert_eq!((r.reference.as_ref(), r.pos), (None, None));
+    assert_eq!((r.mate_reference.as_ref(), r.mate_pos), (None, None));
+    assert!(r.cigar.is_empty());
+    assert!(r.seq.is_empty());
+    assert_eq!(records[1].mapq, None);
+}
+
+#[test]
+fn decodes_sequences_and_qualities() {
+    let r = &records(SAM)[1];
+    // The ambiguity code R reads as N
+    assert_eq!(r.seq.to_string(), "AAAAGATAAGGNTA");
+    assert_eq!(r.qual, Some((10..24).collect::<Vec<u8>>()));
+    assert_eq!(r.cigar.ops()[0], (CigarOp::SoftClip, 3));
+    assert_eq!(r.cigar.ops()[2], (CigarOp::Padding, 1));
+}
+
+#[test]
+fn parses_typed_tags() {
+    let records = records(SAM);
+    assert_eq!(records[0].tag(b"NM"), Some(&TagValue::Int(3)));
+    assert_eq!(
+        records[0].tag(b"RG"),
+        Some(&TagValue::String("rg1".to_string()))
+    );
+    assert_eq!(records[0].tag(b"XX"), None);
+    assert_eq!(records[1].tag(b"XA"), Some(&TagValue::Char('x')));
+    assert_eq!(records[1].tag(b"XF"), Some(&TagValue::Float(-150.0)));
+
+    let r = &records[2];
+    assert_eq!(
+        r.tag(b"XB"),
+        Some(&TagValue::Array(TagArray::Int8(vec![-1, 2, -3])))
+    );
+    assert_eq!(r.tag(b"XH"), Some(&TagValue::Hex(vec![0x1A, 0xE3, 0x01])));
+    assert_eq!(
+        r.tag(b"XU"),
+        Some(&TagValue::Array(TagArray::UInt16(vec![65535])))
+    );
+    assert_eq!(
+        r.tag(b"XG"),
+        Some(&TagValue::Array(TagArray::Float(vec![0.5, 2.0])))
+    );
+}
+
+#[test]
+fn flags_combine() {
+    let mut flags = Flags::from_bits(0x10);
+    assert!(flags.is_reverse() && flags.is_primary());
+    flags |= Flags::SECONDARY;
+    assert!(!flags.is_primary());
+    assert!(flags.contains(Flags::REVERSE | Flags::SECONDARY));
+    flags.remove(Flags::REVERSE);
+    assert_eq!(flags.bits(), 0x100);
+    assert_eq!(flags & Flags::REVERSE, Flags::default());
+}
+
+#[test]
+fn cigar_ops_round_trip_chars() {
+    for op in CigarOp::ALL {
+        assert_eq!(CigarOp::from_char(op.to_char()), Some(op));
+    }
+    assert_eq!(CigarOp::from_char('Q'), None);
+}
+
+#[test]
+fn reports_line_numbers() {
+    let error = |text: &str| match SamReader::new(Cursor::new(text)) {
+        Err(e) => e,
+        Ok(reader) => reader.filter_map(Result::err).next().unwrap(),
+    };
+    let line = |e: SamError| match e {
+        SamError::Parse { line, .. } => line,
+        other => panic!("unexpected error {other}"),
+    };
+
+    assert_eq!(line(error("@SQ\tLN:10\n")), 1);
+    assert_eq!(line(error("@HD\tVN\n")), 1);
+    let record = "r\t0\t*\t0\t0\t*\t*\t0\t0\tACGT\t*";
+    assert_eq!(
+        line(error(&format!("@HD\tVN:1.6\n\n{record}\tNM:x:1\n"))),
+        3
+    );
+    assert_eq!(line(error(&format!("{record}\n{record}\tNM:i\n"))), 2);
+    assert_eq!(line(error("r\t0\t*\t0\t0\t4Q\t*\t0\t0\t*\t*\n")), 1);
+    assert_eq!(line(error("r\t0\t*\t0\t0\t*\t*\t0\t0\tAC-T\t*\n")), 1);
+    assert_eq!(line(error("r\t0\t*\t0\t0\t*\t*\t0\n")), 1);
+    assert_eq!(line(error("r\t0\t*\t0\t0\t*\t*\t0\t0\tACGT\tIII\n")), 1);
+}
+
+proptest! {
+    #[test]
+    fn parses_generated_records(
+        flags in any::<u16>(),
+        pos in 0i64..1_000_000,
+        ops in prop::collection::vec((1u32..500, 0usize..9), 1..10),
+        seq in "[ACGTN]{1,50}",
+        value in any::<i32>(),
+    ) {
+        let cigar: String = ops
+            .iter()
+            .map(|&(n, op)| format!("{n}{}", CigarOp::ALL[op].to_char()))
+            .collect();
+        let qual: String = seq.chars().map(|_| 'I').collect();
+        let text = format!("q\t{flags}\tref\t{pos}\t60\t{cigar}\t=\t0\t-5\t{seq}\t{qual}\tXI:i:{value}\n");
+        let record = &records(&text)[0];
+
+        prop_assert_eq!(record.flags.bits(), flags);
+        prop_assert_eq!(record.pos, (pos > 0).then(|| pos as usize - 1));
+        let parsed: Vec<(CigarOp, u32)> =
+            ops.iter().map(|&(n, op)| (CigarOp::ALL[op], n)).collect();
+        prop_assert_eq!(record.cigar.ops(), &parsed[..]);
+        prop_assert_eq!(record.seq.to_string(), seq.clone());
+        prop_assert_eq!(record.qual.as_ref().map(Vec::len), Some(seq.len()));
+        prop_assert_eq!(record.template_len, -5);
+        prop_assert_eq!(record.tag(b"XI"), Some(&TagValue::Int(value as i64)));
+    }
+}
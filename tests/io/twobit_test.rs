@@ -0,0 +1,145 @@
+use std::io::Cursor;
+
+use nuc::{
+    alphabet::Nuc5,
+    io::{
+        fasta::FastaReader,
+        twobit::{TwoBitReader, TwoBitRecord, TwoBitWriter},
+    },
+    masked::MaskedSeq,
+    softmask::CaseMask,
+};
+
+fn record(name: &str, seq: &str) -> TwoBitRecord {
+    TwoBitRecord {
+        name: name.to_string(),
+        seq: seq.to_uppercase().parse::<MaskedSeq>().unwrap(),
+        mask: Some(CaseMask::from_ascii(seq.as_bytes())),
+    }
+}
+
+fn write(records: &[TwoBitRecord]) -> Vec<u8> {
+    TwoBitWriter::new(Vec::new()).write(records).unwrap()
+}
+
+#[test]
+fn writes_reference_layout() {
+    let bytes = write(&[record("s", "TCAG")]);
+    assert_eq!(
+        bytes,
+        vec![
+            0x43, 0x27, 0x41, 0x1A, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, // header
+            1, b's', 22, 0, 0, 0, // index
+            4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,    // sizes, blocks, reserved
+            0x1B, // TCAG
+        ]
+    );
+}
+
+#[test]
+fn reads_big_endian_files() {
+    let bytes = vec![
+        0x1A, 0x41, 0x27, 0x43, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, // header
+        1, b's', 0, 0, 0, 22, // index
+        0, 0, 0, 5, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 1, // size, N blocks
+        0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, // mask blocks, reserved
+        0x1B, 0x00, // TCAG T
+    ];
+    let mut reader = TwoBitReader::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(reader.names(), &["s".to_string()]);
+    let record = reader.read("s").unwrap();
+    assert_eq!(record.seq.to_string(), "TCAGN");
+    assert_eq!(
+        record.mask.unwrap().intervals().collect::<Vec<_>>(),
+        vec![0..2]
+    );
+}
+
+#[test]
+fn unknown_name_is_not_found() {
+    let bytes = write(&[record("chr1", "ACGT")]);
+    let mut reader = TwoBitReader::new(Cursor::new(bytes)).unwrap();
+    let err = reader.read("chr2").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn out_of_range_fetch_is_invalid_input() {
+    let bytes = write(&[record("chr1", "ACGT")]);
+    let mut reader = TwoBitReader::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(reader.fetch("chr1", 2..4).unwrap(), record("chr1", "GT"));
+    let (start, end) = (3, 2);
+    for err in [
+        reader.fetch("chr1", 2..5).unwrap_err(),
+        reader.fetch("chr1", start..end).unwrap_err(),
+        reader.fetch("chr1", ..=usize::MAX).unwrap_err(),
+    ] {
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
+
+#[test]
+fn huge_counts_do_not_preallocate() {
+    // Claims 2^32 - 1 sequences but the index ends after the header
+    let mut bytes = write(&[record("s", "TCAG")]);
+    bytes[8..12].copy_from_slice(&[0xFF; 4]);
+    bytes.truncate(16);
+    let err = TwoBitReader::new(Cursor::new(bytes)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    // Claims 2^32 - 1 N blocks
+    let mut bytes = write(&[record("s", "TCAG")]);
+    bytes[26..30].copy_from_slice(&[0xFF; 4]);
+    let mut reader = TwoBitReader::new(Cursor::new(bytes)).unwrap();
+    let err = reader.read("s").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn rejects_non_twobit_input() {
+    let err = TwoBitReader::new(Cursor::new(b">fasta\nACGT\n".to_vec())).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn converts_from_fasta() {
+    let input = ">chr1\nACGTnnNNacgt\n>chr2\nGGCC\n";
+    let records: Vec<TwoBitRecord> = FastaReader::<_, Nuc5>::new(input.as_bytes())
+        .with_soft_mask(true)
+        .map(|r| TwoBitRecord::from(r.unwrap()))
+        .collect();
+    let mut reader = TwoBitReader::new(Cursor::new(write(&records))).unwrap();
+
+    let chr1 = reader.read("chr1").unwrap();
+    assert_eq!(chr1.seq.to_string(), "ACGTNNNNACGT");
+    assert_eq!(chr1.seq.n_count(), 4);
+    assert!(chr1.seq.is_n(4) && chr1.seq.is_n(7));
+    assert_eq!(
+        chr1.mask.unwrap().intervals().collect::<Vec<_>>(),
+        vec![4..6, 8..12]
+    );
+    assert_eq!(reader.seq_len("chr2").unwrap(), 4);
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn roundtrip(seqs in proptest::collection::vec("[ACGTNacgtn]{0,100}", 1..5)) {
+        let records: Vec<TwoBitRecord> = seqs
+            .iter()
+            .enumerate()
+            .map(|(i, s)| record(&format!("seq{i}"), s))
+            .collect();
+        let mut reader = TwoBitReader::new(Cursor::new(write(&records))).unwrap();
+        assert_eq!(reader.records().unwrap(), records);
+    }
+
+    #[test]
+    fn fetch_matches_slice(s in "[ACGTNacgtn]{1,200}", a in 0usize..200, b in 0usize..200) {
+        let (start, end) = (a.min(b).min(s.len()), a.max(b).min(s.len()));
+        let mut reader = TwoBitReader::new(Cursor::new(write(&[record("chr", &s)]))).unwrap();
+        let fetched = reader.fetch("chr", start..end).unwrap();
+        assert_eq!(fetched, record("chr", &s[start..end]));
+    }
+
+}
@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+
+use nuc::{
+    alphabet::Nuc4,
+    kmer::{
+        canonical_kmer, kmer_from_seq, kmer_to_seq, reverse_complement_kmer, CountMinSketch,
+        HyperLogLog, KmerBloomFilter, KmerCounter, KmerCounts, Sketch, SketchError, SketchMode,
+    },
+    seq::Seq,
+};
+
+fn seq(text: &str) -> Seq<Nuc4> {
+    Seq::try_from(text).unwrap()
+}
+
+fn revcomp(text: &str) -> String {
+    seq(text).reverse_complement().to_string()
+}
+
+/// Counts canonical k-mers as strings.
+fn naive_counts(texts: &[String], k: usize) -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    for text in texts {
+        for i in 0..(text.len() + 1).saturating_sub(k) {
+            let kmer = &text[i..i + k];
+            let rc = revcomp(kmer);
+            *counts.entry(kmer.min(&rc).to_string()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+fn as_strings(counts: &KmerCounts) -> HashMap<String, u32> {
+    counts
+        .iter()
+        .map(|(word, count)| (kmer_to_seq(word, counts.k()).to_string(), count))
+        .collect()
+}
+
+#[test]
+fn packs_kmers_left_to_right() {
+    let words: Vec<u64> = seq("ACGTA").kmers(3).collect();
+    assert_eq!(words, vec![0b000110, 0b011011, 0b101100]);
+    assert_eq!(seq("ACGTA").kmers(3).len(), 3);
+    assert_eq!(seq("AC").kmers(3).count(), 0);
+    assert_eq!(kmer_from_seq(&seq("GTA")), 0b101100);
+    assert_eq!(kmer_to_seq(0b101100, 3).to_string(), "GTA");
+}
+
+#[test]
+fn canonical_kmers_pick_smaller_strand() {
+    // AAC / GTT and GTA / TAC
+    let words: Vec<u64> = seq("AACGTA").canonical_kmers(3).collect();
+    let expected: Vec<u64> = ["AAC", "ACG", "CGT", "GTA"]
+        .iter()
+        .map(|k| canonical_kmer(kmer_from_seq(&seq(k)), 3))
+        .collect();
+    assert_eq!(words, expected);
+    assert_eq!(kmer_to_seq(words[3], 3).to_string(), "GTA");
+    assert_eq!(words[1], words[2]);
+}
+
+#[test]
+fn counter_min_count_and_histogram() {
+    let seqs = [seq("ACGTACGTAC"), seq("TTTT")];
+    let counts = KmerCounter::new(2).count(&seqs);
+    // AC/GT: 5, CG: 2, TA: 2, AA/TT: 3
+    assert_eq!(counts.count_of(&seq("GT")), 5);
+    assert_eq!(counts.count_of(&seq("AA")), 3);
+    assert_eq!(counts.total(), 12);
+    assert_eq!(counts.histogram(), vec![0, 0, 2, 1, 0, 1]);
+
+    let mut text = Vec::new();
+    counts.write_histogram(&mut text).unwrap();
+    assert_eq!(String::from_utf8(text).unwrap(), "2\t2\n3\t1\n5\t1\n");
+
+    let filtered = KmerCounter::new(2).with_min_count(3).count(&seqs);
+    assert_eq!(filtered.len(), 2);
+    assert_eq!(filtered.count_of(&seq("CG")), 0);
+}
+
+#[test]
+fn table_round_trip() {
+    let seqs = [seq("ACGTTGCAAGGCTTAGC"), seq("GGGGCCCCAT")];
+    let counts = KmerCounter::new(5).count(&seqs);
+
+    let mut bytes = Vec::new();
+    counts.write_to(&mut bytes).unwrap();
+    assert_eq!(KmerCounts::read_from(bytes.as_slice()).unwrap(), counts);
+
+    let path = std::env::temp_dir().join(format!("nuc-kmers-{}.bin", std::process::id()));
+    counts.save(&path).unwrap();
+    let loaded = KmerCounts::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, counts);
+
+    bytes[0] = b'X';
+    let err = KmerCounts::read_from(bytes.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn bloom_filter_reports_hit_fraction() {
+    let reference = Seq::<Nuc4>::random(5000);
+    let mut filter = KmerBloomFilter::with_false_positive_rate(21, 5000, 0.001);
+    filter.insert(&reference);
+
+    assert_eq!(filter.query(&reference.slice(100..250)), 1.0);
+    assert_eq!(
+        filter.query(&reference.slice(100..250).reverse_complement()),
+        1.0
+    );
+    assert!(filter.contains(&reference.slice(7..28)));
+    assert_eq!(filter.query(&seq("ACGT")), 0.0);
+
+    // Half of the read comes from the reference
+    let read = reference.slice(0..60).concat(&Seq::<Nuc4>::random(60));
+    let fraction = filter.query(&read);
+    assert!((0.3..0.5).contains(&fraction), "{fraction}");
+    assert!(filter.query(&Seq::random(200)) < 0.05);
+    assert!(filter.false_positive_rate() < 0.01);
+}
+
+#[test]
+fn bloom_filter_union_and_round_trip() {
+    let (a, b) = (Seq::<Nuc4>::random(300), Seq::<Nuc4>::random(300));
+    let mut left = KmerBloomFilter::new(15, 1 << 14, 3);
+    let mut right = left.clone();
+    left.insert(&a);
+    right.insert(&b);
+    left.union(&right);
+    assert_eq!(left.query(&a), 1.0);
+    assert_eq!(left.query(&b), 1.0);
+
+    let mut bytes = Vec::new();
+    left.write_to(&mut bytes).unwrap();
+    assert_eq!(KmerBloomFilter::read_from(bytes.as_slice()).unwrap(), left);
+    assert!(KmerBloomFilter::read_from(&bytes[..bytes.len() - 1]).is_err());
+
+    let path = std::env::temp_dir().join(format!("nuc-bloom-{}.bin", std::process::id()));
+    left.save(&path).unwrap();
+    let loaded = KmerBloomFilter::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, left);
+}
+
+#[test]
+fn count_min_sketch_estimates() {
+    let mut sketch = CountMinSketch::with_error(4, 0.001, 0.01);
+    sketch.insert(&seq("ACGTACGTAC"));
+    sketch.insert(&seq("GTAC"));
+    // ACGT x2, CGTA/TACG x3, GTAC x3
+    assert_eq!(sketch.estimate(&seq("ACGT")), 2);
+    assert_eq!(sketch.estimate(&seq("TACG")), 3);
+    assert_eq!(sketch.estimate(&seq("GTAC")), 3);
+    assert_eq!(sketch.estimate(&seq("AAAA")), 0);
+    assert_eq!(sketch.estimates(&seq("ACGTAC")), vec![2, 3, 3]);
+    assert_eq!(sketch.query(&seq("ACGTAC"), 3), 2.0 / 3.0);
+
+    let mut other = CountMinSketch::new(4, sketch.width(), sketch.depth());
+    other.insert(&seq("GTAC"));
+    sketch.merge(&other);
+    assert_eq!(sketch.estimate(&seq("GTAC")), 4);
+
+    let mut bytes = Vec::new();
+    sketch.write_to(&mut bytes).unwrap();
+    assert_eq!(CountMinSketch::read_from(bytes.as_slice()).unwrap(), sketch);
+    bytes[3] = 0;
+    assert!(CountMinSketch::read_from(bytes.as_slice()).is_err());
+}
+
+/// Substitutes every `step`-th base with a different one.
+fn mutate(text: &str, step: usize) -> String {
+    text.bytes()
+        .enumerate()
+        .map(|(i, b)| {
+            if i % step == step / 2 {
+                match b {
+                    b'A' => 'C',
+                    b'C' => 'G',
+                    b'G' => 'T',
+                    _ => 'A',
+                }
+            } else {
+                b as char
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn minhash_identical_and_unrelated() {
+    let genome = Seq::<Nuc4>::random(20_000);
+    let a = Sketch::from_seqs(std::slice::from_ref(&genome), 21, 1000);
+    let rc = Sketch::from_seqs(&[genome.reverse_complement()], 21, 1000);
+    assert_eq!(a.len(), 1000);
+    assert_eq!(a, rc);
+    assert_eq!(a.jaccard(&rc), Ok(1.0));
+    assert_eq!(a.mash_distance(&rc), Ok(0.0));
+    assert!(a.p_value(&rc).unwrap() < 1e-100);
+
+    let other = Sketch::from_seqs(&[Seq::random(20_000)], 21, 1000);
+    assert_eq!(a.jaccard(&other), Ok(0.0));
+    assert_eq!(a.mash_distance(&other), Ok(1.0));
+    assert_eq!(a.p_value(&other), Ok(1.0));
+}
+
+#[test]
+fn mash_distance_tracks_mutation_rate() {
+    let text = Seq::<Nuc4>::random(50_000).to_string();
+    let mutated = mutate(&text, 100);
+    let a = Sketch::from_seqs(&[seq(&text)], 21, 2000);
+    let b = Sketch::from_seqs(&[seq(&mutated)], 21, 2000);
+    let distance = a.mash_distance(&b).unwrap();
+    assert!((0.007..0.013).contains(&distance), "{distance}");
+    assert!(a.p_value(&b).unwrap() < 1e-10);
+}
+
+#[test]
+fn scaled_containment() {
+    let genome = Seq::<Nuc4>::random(40_000);
+    let part = genome.slice(10_000..20_000);
+    let whole = Sketch::scaled_from_seqs(std::slice::from_ref(&genome), 21, 20);
+    let piece = Sketch::scaled_from_seqs(&[part], 21, 20);
+    assert_eq!(piece.containment(&whole), Ok(1.0));
+    let reverse = whole.containment(&piece).unwrap();
+    assert!((0.15..0.35).contains(&reverse), "{reverse}");
+    let jaccard = piece.jaccard(&whole).unwrap();
+    assert!((0.15..0.35).contains(&jaccard), "{jaccard}");
+
+    // A coarser scale is compared on the common, larger scale
+    let coarse = Sketch::scaled_from_seqs(&[genome], 21, 40);
+    assert_eq!(piece.containment(&coarse), Ok(1.0));
+}
+
+#[test]
+fn sketch_compatibility_and_merge() {
+    let (x, y) = (Seq::<Nuc4>::random(5000), Seq::<Nuc4>::random(5000));
+    let both = Sketch::from_seqs(&[x.clone(), y.clone()], 15, 500);
+    let mut merged = Sketch::from_seqs(&[x], 15, 500);
+    merged.merge(&Sketch::from_seqs(&[y], 15, 500)).unwrap();
+    assert_eq!(merged, both);
+    assert_eq!(merged.bases(), 10_000);
+
+    assert_eq!(
+        both.jaccard(&Sketch::bottom_k(21, 500)),
+        Err(SketchError::KMismatch {
+            left: 15,
+            right: 21
+        })
+    );
+    assert_eq!(
+        both.jaccard(&Sketch::scaled(15, 10)),
+        Err(SketchError::ModeMismatch)
+    );
+}
+
+#[test]
+fn sketch_file_round_trip() {
+    let sketch = Sketch::scaled_from_seqs(&[Seq::random(3000)], 17, 8);
+    assert_eq!(sketch.mode(), SketchMode::Scaled(8));
+    let mut bytes = Vec::new();
+    sketch.write_to(&mut bytes).unwrap();
+    assert_eq!(Sketch::read_from(bytes.as_slice()).unwrap(), sketch);
+
+    let path = std::env::temp_dir().join(format!("nuc-sketch-{}.msh", std::process::id()));
+    let bottom = Sketch::from_seqs(&[Seq::random(3000)], 21, 100);
+    bottom.save(&path).unwrap();
+    let loaded = Sketch::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.jaccard(&bottom), Ok(1.0));
+
+    bytes[16] = 7;
+    assert!(Sketch::read_from(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn sketch_files_must_match_their_mode() {
+    let error = |bytes: &[u8]| Sketch::read_from(bytes).unwrap_err().to_string();
+    let set_parameter = |bytes: &mut Vec<u8>, value: u64| {
+        bytes[17..25].copy_from_slice(&value.to_le_bytes());
+    };
+
+    // A bottom-k sketch holding more hashes than its size
+    let mut bytes = Vec::new();
+    Sketch::from_seqs(&[Seq::random(3000)], 21, 100)
+        .write_to(&mut bytes)
+        .unwrap();
+    set_parameter(&mut bytes, 50);
+    assert!(error(&bytes).contains("more hashes than its size"));
+
+    // A scaled sketch holding hashes its scale would drop
+    let mut bytes = Vec::new();
+    Sketch::scaled_from_seqs(&[Seq::random(3000)], 17, 8)
+        .write_to(&mut bytes)
+        .unwrap();
+    set_parameter(&mut bytes, 1000);
+    assert!(error(&bytes).contains("above its scale"));
+}
+
+#[test]
+fn hyperloglog_estimates_distinct_kmers() {
+    let seqs: Vec<Seq<Nuc4>> = (0..20).map(|_| Seq::random(5000)).collect();
+    let exact = KmerCounter::new(21).count(&seqs).len() as f64;
+
+    let mut hll = HyperLogLog::new(21, 14);
+    for s in &seqs {
+        hll.insert(s);
+    }
+    // Duplicates and reverse complements do not change the estimate
+    hll.insert(&seqs[0].reverse_complement());
+    let error = (hll.estimate() - exact).abs() / exact;
+    assert!(error < 4.0 * hll.relative_error(), "{error}");
+
+    let mut small = HyperLogLog::new(21, 12);
+    small.insert(&seq("AAAAACCCCCGATTACAGGGGGTTAT"));
+    assert!((small.estimate() - 6.0).abs() < 0.5);
+    assert_eq!(HyperLogLog::new(21, 10).estimate(), 0.0);
+}
+
+#[test]
+fn hyperloglog_merges_across_threads() {
+    let seqs: Vec<Seq<Nuc4>> = (0..8).map(|_| Seq::random(4000)).collect();
+    let mut single = HyperLogLog::new(25, 12);
+    for s in &seqs {
+        single.insert(s);
+    }
+
+    let parts: Vec<HyperLogLog> = std::thread::scope(|scope| {
+        let workers: Vec<_> = seqs
+            .chunks(3)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut hll = HyperLogLog::new(25, 12);
+                    for s in chunk {
+                        hll.insert(s);
+                    }
+                    hll
+                })
+            })
+            .collect();
+        workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+    let mut merged = HyperLogLog::new(25, 12);
+    for part in &parts {
+        merged.merge(part);
+    }
+    assert_eq!(merged, single);
+
+    let mut bytes = Vec::new();
+    merged.write_to(&mut bytes).unwrap();
+    let loaded = HyperLogLog::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(loaded.estimate(), single.estimate());
+    assert!(HyperLogLog::read_from(&bytes[..100]).is_err());
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn bottom_k_keeps_smallest_hashes(texts in proptest::collection::vec("[ACGT]{0,200}", 1..5), size in 1usize..50) {
+        let seqs: Vec<Seq<Nuc4>> = texts.iter().map(|t| seq(t)).collect();
+        let sketch = Sketch::from_seqs(&seqs, 5, size);
+        let mut all: Vec<u64> = seqs.iter().flat_map(|s| nuc::hash::NtHash::new(s, 5)).collect();
+        all.sort_unstable();
+        all.dedup();
+        all.truncate(size);
+        assert_eq!(sketch.hashes(), all.as_slice());
+    }
+
+    #[test]
+    fn scaled_keeps_hashes_below_the_threshold(texts in proptest::collection::vec("[ACGT]{0,600}", 1..5), scale in 1u64..8) {
+        let seqs: Vec<Seq<Nuc4>> = texts.iter().map(|t| seq(t)).collect();
+        let sketch = Sketch::scaled_from_seqs(&seqs, 5, scale);
+        let mut all: Vec<u64> = seqs
+            .iter()
+            .flat_map(|s| nuc::hash::NtHash::new(s, 5))
+            .filter(|&hash| hash <= u64::MAX / scale)
+            .collect();
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(sketch.hashes(), all.as_slice());
+
+        let mut one_by_one = Sketch::scaled(5, scale);
+        for s in &seqs {
+            one_by_one.insert(s);
+        }
+        assert_eq!(one_by_one, sketch);
+    }
+
+    #[test]
+    fn count_min_never_undercounts(texts in proptest::collection::vec("[ACGT]{0,60}", 1..6), k in 1usize..8) {
+        let mut sketch = CountMinSketch::new(k, 64, 3);
+        for text in &texts {
+            sketch.insert(&seq(text));
+        }
+        for (kmer, count) in naive_counts(&texts, k) {
+            assert!(sketch.estimate(&seq(&kmer)) >= count);
+        }
+    }
+
+    #[test]
+    fn bloom_has_no_false_negatives(texts in proptest::collection::vec("[ACGT]{0,80}", 1..6), k in 1usize..40) {
+        let mut filter = KmerBloomFilter::new(k, 512, 4);
+        for text in &texts {
+            filter.insert(&seq(text));
+        }
+        for text in &texts {
+            let s = seq(text);
+            let expected = if text.len() >= k { 1.0 } else { 0.0 };
+            assert_eq!(filter.query(&s), expected);
+        }
+    }
+
+    #[test]
+    fn reverse_complement_matches_seq(text in "[ACGT]{1,32}") {
+        let k = text.len();
+        let word = kmer_from_seq(&seq(&text));
+        let rc = reverse_complement_kmer(word, k);
+        assert_eq!(kmer_to_seq(rc, k).to_string(), revcomp(&text));
+        assert_eq!(reverse_complement_kmer(rc, k), word);
+    }
+
+    #[test]
+    fn kmers_match_windows(text in "[ACGT]{0,100}", k in 1usize..=32) {
+        let s = seq(&text);
+        let words: Vec<u64> = s.kmers(k).collect();
+        let expected: Vec<u64> = (0..(text.len() + 1).saturating_sub(k))
+            .map(|i| kmer_from_seq(&seq(&text[i..i + k])))
+            .collect();
+        assert_eq!(words, expected);
+        let canonical: Vec<u64> = s.canonical_kmers(k).collect();
+        let expected: Vec<u64> = expected.iter().map(|&w| canonical_kmer(w, k)).collect();
+        assert_eq!(canonical, expected);
+    }
+
+    #[test]
+    fn counter_matches_naive(
+        texts in proptest::collection::vec("[ACGT]{0,80}", 0..8),
+        k in 1usize..12,
+        threads in 1usize..4,
+    ) {
+        let seqs: Vec<Seq<Nuc4>> = texts.iter().map(|t| seq(t)).collect();
+        let counts = KmerCounter::new(k).with_threads(threads).count(&seqs);
+        assert_eq!(as_strings(&counts), naive_counts(&texts, k));
+        assert!(counts.entries().windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn threaded_equals_single(texts in proptest::collection::vec("[ACG]{10,60}", 2..10), min in 1u32..4) {
+        let seqs: Vec<Seq<Nuc4>> = texts.iter().map(|t| seq(t)).collect();
+        let single = KmerCounter::new(4).with_min_count(min).count(&seqs);
+        let threaded = KmerCounter::new(4).with_min_count(min).with_threads(3).count(&seqs);
+        assert_eq!(single, threaded);
+    }
+}
@@ -0,0 +1,94 @@
+use nuc::{
+    alphabet::{Nuc4, Nuc5, Nucleotide},
+    masked::MaskedSeq,
+    seq::Seq,
+};
+
+#[test]
+fn empty_masked() {
+    let masked = "".parse::<MaskedSeq>().unwrap();
+    assert!(masked.is_empty());
+    assert_eq!(masked.to_string(), "");
+    assert!(masked.n_runs().is_empty());
+}
+
+#[test]
+fn n_runs_are_collected() {
+    let masked = "NNACGTNNNNACNA".parse::<MaskedSeq>().unwrap();
+    assert_eq!(masked.n_runs(), &[0..2, 6..10, 12..13]);
+    assert_eq!(masked.n_count(), 7);
+    assert_eq!(masked.as_nuc4().to_string(), "AAACGTAAAAACAA");
+}
+
+#[test]
+fn new_merges_overlapping_runs() {
+    let seq = Seq::<Nuc4>::try_from("ACGTACGTAC").unwrap();
+    let masked = MaskedSeq::new(seq, vec![6..8, 1..3, 2..4, 4..5, 9..9]);
+    assert_eq!(masked.n_runs(), &[1..5, 6..8]);
+    assert_eq!(masked.to_string(), "ANNNNCNNAC");
+}
+
+#[test]
+#[should_panic]
+fn new_rejects_out_of_bounds_run() {
+    let seq = Seq::<Nuc4>::try_from("ACGT").unwrap();
+    MaskedSeq::new(seq, vec![0..1, 2..5]);
+}
+
+#[test]
+fn get_reports_n() {
+    let masked = "ANNC".parse::<MaskedSeq>().unwrap();
+    assert_eq!(masked.get(0), Nucleotide::A);
+    assert_eq!(masked.get(1), Nucleotide::N);
+    assert_eq!(masked.get(2), Nucleotide::N);
+    assert_eq!(masked.get(3), Nucleotide::C);
+    assert!(masked.is_n(1));
+    assert!(!masked.is_n(3));
+}
+
+proptest::proptest! {
+
+    // -- Conversion roundtrips --
+
+    #[test]
+    fn nuc5_roundtrip(s in "[ATGCN]{0,200}") {
+        let seq = Seq::<Nuc5>::try_from(s.as_str()).unwrap();
+        let masked = MaskedSeq::from(&seq);
+        assert_eq!(masked.to_string(), s);
+        assert_eq!(masked.len(), s.len());
+        assert_eq!(Seq::<Nuc5>::from(&masked), seq);
+    }
+
+    #[test]
+    fn n_count_matches_string(s in "[ATGCN]{0,200}") {
+        let masked = s.parse::<MaskedSeq>().unwrap();
+        assert_eq!(masked.n_count(), s.bytes().filter(|&b| b == b'N').count());
+        for pair in masked.n_runs().windows(2) {
+            assert!(pair[0].end < pair[1].start);
+        }
+    }
+
+    // -- get / iter agree with Seq<Nuc5> --
+
+    #[test]
+    fn get_and_iter_match_nuc5(s in "[ATGCN]{1,200}") {
+        let seq = Seq::<Nuc5>::try_from(s.as_str()).unwrap();
+        let masked = MaskedSeq::from(&seq);
+        for i in 0..s.len() {
+            assert_eq!(masked.get(i), seq.get(i));
+        }
+        assert!(masked.iter().eq(seq.iter()));
+    }
+
+    // -- slice --
+
+    #[test]
+    fn slice_matches_string(s in "[ATGCN]{0,200}", a in 0usize..200, b in 0usize..200) {
+        let (start, end) = (a.min(b).min(s.len()), a.max(b).min(s.len()));
+        let masked = s.parse::<MaskedSeq>().unwrap();
+        let sliced = masked.slice(start..end);
+        assert_eq!(sliced.to_string(), &s[start..end]);
+        assert_eq!(sliced, s[start..end].parse::<MaskedSeq>().unwrap());
+    }
+
+}
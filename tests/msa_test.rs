@@ -0,0 +1,113 @@
+use nuc::{
+    alphabet::{Nuc5, Nucleotide, AA20},
+    msa::{GappedSeq, Msa, MsaError},
+};
+
+fn row(s: &str) -> GappedSeq<Nuc5> {
+    s.parse().unwrap()
+}
+
+fn msa(rows: &[&str]) -> Msa<Nuc5> {
+    let mut msa = Msa::new();
+    for (i, r) in rows.iter().enumerate() {
+        msa.push(&format!("s{i}"), row(r)).unwrap();
+    }
+    msa
+}
+
+#[test]
+fn gapped_seq_keeps_residues_and_gap_runs() {
+    let r = row("--AC-GT..N");
+    assert_eq!(r.len(), 10);
+    assert_eq!(r.residues().to_string(), "ACGTN");
+    assert_eq!(r.gaps(), [0..2, 4..5, 7..9]);
+    assert_eq!(r.gap_count(), 5);
+    assert_eq!(r.residue_index(3), Some(1));
+    assert_eq!(r.residue_index(5), Some(2));
+    assert_eq!(r.residue_index(9), Some(4));
+    assert!(r.is_gap(8));
+    assert_eq!(r.get(2), Some(Nucleotide::A));
+    assert_eq!(r.to_string(), "--AC-GT--N");
+}
+
+#[test]
+fn gapped_seq_merges_gap_runs() {
+    let residues = row("ACGT").residues().clone();
+    let r = GappedSeq::new(residues, vec![3..5, 0..1, 4..6]);
+    assert_eq!(r.gaps(), [0..1, 3..6]);
+    assert_eq!(r.to_string(), "-AC---GT");
+    assert!("AC-X".parse::<GappedSeq<Nuc5>>().is_err());
+}
+
+#[test]
+fn msa_rejects_ragged_rows() {
+    let mut m = msa(&["AC-GT"]);
+    assert!(matches!(
+        m.push("bad", row("ACGT")),
+        Err(MsaError::LengthMismatch {
+            expected: 5,
+            found: 4,
+            ..
+        })
+    ));
+    assert_eq!(m.len(), 1);
+    assert_eq!(m.row("s0").unwrap().to_string(), "AC-GT");
+}
+
+#[test]
+fn iterates_columns() {
+    let m = msa(&["AC-", "A-G"]);
+    let columns: Vec<_> = m.iter_columns().collect();
+    assert_eq!(columns.len(), 3);
+    assert_eq!(columns[1], vec![Some(Nucleotide::C), None]);
+    assert_eq!(m.column(2), vec![None, Some(Nucleotide::G)]);
+    assert_eq!(Msa::<Nuc5>::new().iter_columns().count(), 0);
+}
+
+#[test]
+fn consensus_and_statistics() {
+    let m = msa(&["ACGT-A", "ACGA-C", "TCG--G", "ACCA-T"]);
+    assert_eq!(m.consensus().to_string(), "ACGA-A");
+
+    let conservation = m.conservation();
+    assert_eq!(conservation[0], 0.75);
+    assert_eq!(conservation[1], 1.0);
+    assert_eq!(conservation[4], 0.0);
+
+    let entropy = m.entropy();
+    assert_eq!(entropy[1], 0.0);
+    // All-gap columns have no residues, and no negative zero
+    assert!(entropy[4] == 0.0 && entropy[4].is_sign_positive());
+    assert!(entropy[1].is_sign_positive());
+    assert_eq!(entropy[5], 2.0);
+    assert!((entropy[0] - 0.811278).abs() < 1e-6);
+    assert_eq!(m.gap_fraction()[3], 0.25);
+    assert_eq!(m.gap_fraction()[4], 1.0);
+}
+
+#[test]
+fn filters_columns() {
+    let m = msa(&["ACGT-A", "ACGA-C", "TCG--G", "ACCA-T"]);
+    let filtered = m.remove_gappy_columns(0.5);
+    assert_eq!(filtered.columns(), 5);
+    assert_eq!(filtered.rows()[2].to_string(), "TCG-G");
+    assert_eq!(filtered.names(), m.names());
+
+    let conserved = m.filter_columns(|_, column| column.iter().all(|s| *s == column[0]));
+    assert_eq!(conserved.rows()[0].to_string(), "C-");
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn gapped_roundtrip(text in "[-ACDEFGHIKLMNPQRSTVWY]{0,200}") {
+        let r = text.parse::<GappedSeq<AA20>>().unwrap();
+        assert_eq!(r.to_string(), text);
+        let collected: GappedSeq<AA20> = r.iter().collect();
+        assert_eq!(&collected, &r);
+        for (column, symbol) in r.iter().enumerate() {
+            assert_eq!(r.get(column), symbol);
+        }
+    }
+
+}
@@ -0,0 +1,232 @@
+use nuc::{
+    alphabet::{Nuc4, Nuc5, Strand, AA20},
+    search::ApproxMatch,
+    seq::Seq,
+};
+
+fn naive(text: &str, pattern: &str) -> Vec<usize> {
+    (0..=text.len().saturating_sub(pattern.len()))
+        .filter(|&i| text.len() >= pattern.len() && &text[i..i + pattern.len()] == pattern)
+        .collect()
+}
+
+fn find<A: nuc::alphabet::Alphabet>(text: &str, pattern: &str) -> Vec<usize> {
+    let text = Seq::<A>::try_from(text).unwrap();
+    let pattern = Seq::<A>::try_from(pattern).unwrap();
+    text.find_iter(&pattern).collect()
+}
+
+#[test]
+fn finds_overlapping_occurrences() {
+    assert_eq!(find::<Nuc4>("AAAAA", "AA"), vec![0, 1, 2, 3]);
+    assert_eq!(find::<Nuc4>("ACGACGACG", "ACGACG"), vec![0, 3]);
+}
+
+#[test]
+fn empty_pattern_matches_everywhere() {
+    assert_eq!(find::<Nuc4>("ACG", ""), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn pattern_longer_than_text() {
+    assert!(find::<Nuc4>("ACG", "ACGT").is_empty());
+    assert!(find::<Nuc4>("", "A").is_empty());
+}
+
+#[test]
+fn long_patterns_use_packed_comparison() {
+    let unit = "ACGTTGCAAC".repeat(8);
+    let text = format!("GG{unit}T{unit}{unit}");
+    assert_eq!(find::<Nuc4>(&text, &unit), naive(&text, &unit));
+    assert_eq!(find::<Nuc4>(&text, &unit).len(), 10);
+}
+
+#[test]
+fn both_strands_reports_reverse_complement_hits() {
+    let text = Seq::<Nuc5>::try_from("AACCGNTTCGGTT").unwrap();
+    let pattern = Seq::<Nuc5>::try_from("CCG").unwrap();
+    let hits: Vec<_> = text.find_iter_both_strands(&pattern).collect();
+    assert_eq!(hits, vec![(2, Strand::Forward), (8, Strand::Reverse)]);
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn nuc4_matches_naive(text in "[ACGT]{0,300}", pattern in "[ACGT]{1,8}") {
+        assert_eq!(find::<Nuc4>(&text, &pattern), naive(&text, &pattern));
+    }
+
+    #[test]
+    fn nuc4_planted_patterns(prefix in "[ACGT]{0,50}", pattern in "[ACGT]{1,100}", suffix in "[ACGT]{0,50}") {
+        let text = format!("{prefix}{pattern}{suffix}{pattern}");
+        let hits = find::<Nuc4>(&text, &pattern);
+        assert_eq!(&hits, &naive(&text, &pattern));
+        assert!(hits.contains(&prefix.len()));
+    }
+
+    #[test]
+    fn nuc5_matches_naive(text in "[ACN]{0,300}", pattern in "[ACN]{1,70}") {
+        assert_eq!(find::<Nuc5>(&text, &pattern), naive(&text, &pattern));
+    }
+
+    #[test]
+    fn aa20_planted_patterns(prefix in "[ACDEFGHIKLMNPQRSTVWY]{0,50}", pattern in "[AC]{1,80}") {
+        let text = format!("{prefix}{pattern}{pattern}");
+        assert_eq!(find::<AA20>(&text, &pattern), naive(&text, &pattern));
+    }
+
+    #[test]
+    fn both_strands_matches_naive(text in "[ACGT]{0,200}", pattern in "[ACGT]{1,6}") {
+        let seq = Seq::<Nuc4>::try_from(text.as_str()).unwrap();
+        let pat = Seq::<Nuc4>::try_from(pattern.as_str()).unwrap();
+        let rc = pat.reverse_complement().to_string();
+        let mut expected: Vec<_> = naive(&text, &pattern).into_iter().map(|p| (p, Strand::Forward))
+            .chain(naive(&text, &rc).into_iter().map(|p| (p, Strand::Reverse)))
+            .collect();
+        expected.sort_by_key(|&(p, s)| (p, s == Strand::Reverse));
+        assert_eq!(seq.find_iter_both_strands(&pat).collect::<Vec<_>>(), expected);
+    }
+
+}
+
+// -- Approximate matching --
+
+/// Semi-global edit distance of `pattern` against every prefix end of `text`.
+fn naive_approx(
+    text: &[u8],
+    pattern: &[u8],
+    k: usize,
+    eq: impl Fn(u8, u8) -> bool,
+) -> Vec<ApproxMatch> {
+    let m = pattern.len();
+    let mut col: Vec<usize> = (0..=m).collect();
+    let mut hits = Vec::new();
+    if m <= k {
+        hits.push(ApproxMatch {
+            end: 0,
+            distance: m,
+        });
+    }
+    for (j, &t) in text.iter().enumerate() {
+        let mut diag = col[0];
+        col[0] = 0;
+        for i in 1..=m {
+            let up = col[i];
+            col[i] = (diag + !eq(pattern[i - 1], t) as usize)
+                .min(col[i - 1] + 1)
+                .min(up + 1);
+            diag = up;
+        }
+        if col[m] <= k {
+            hits.push(ApproxMatch {
+                end: j + 1,
+                distance: col[m],
+            });
+        }
+    }
+    hits.retain(|h| h.end > 0 || m == 0);
+    hits
+}
+
+fn approx<A: nuc::alphabet::Alphabet>(text: &str, pattern: &str, k: usize) -> Vec<ApproxMatch> {
+    let text = Seq::<A>::try_from(text).unwrap();
+    let pattern = Seq::<A>::try_from(pattern).unwrap();
+    text.find_approx(&pattern, k).collect()
+}
+
+#[test]
+fn approx_reports_end_and_distance() {
+    let hits = approx::<Nuc4>("TTTACGTTTT", "ACTT", 1);
+    assert_eq!(
+        hits,
+        vec![
+            ApproxMatch {
+                end: 7,
+                distance: 1
+            },
+            ApproxMatch {
+                end: 8,
+                distance: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn approx_with_zero_edits_is_exact() {
+    let hits: Vec<usize> = approx::<Nuc4>("ACGTACGT", "GTA", 0)
+        .iter()
+        .map(|h| h.end - 3)
+        .collect();
+    assert_eq!(hits, find::<Nuc4>("ACGTACGT", "GTA"));
+}
+
+#[test]
+fn iupac_codes_match_their_bases() {
+    let text = Seq::<Nuc5>::try_from("GGACGTTAGCTTNNN").unwrap();
+    let hits: Vec<_> = text.find_approx_iupac("RSY", 0).unwrap().collect();
+    assert_eq!(
+        hits,
+        vec![
+            ApproxMatch {
+                end: 10,
+                distance: 0
+            },
+            ApproxMatch {
+                end: 11,
+                distance: 0
+            }
+        ]
+    );
+
+    // A text N is only matched by a pattern N
+    let ends = |pattern| -> Vec<usize> {
+        text.find_approx_iupac(pattern, 0)
+            .unwrap()
+            .map(|h| h.end)
+            .collect()
+    };
+    assert_eq!(ends("TTR"), vec![8]);
+    assert_eq!(ends("TNN"), vec![8, 9, 13, 14]);
+    assert!(text.find_approx_iupac("AXG", 0).is_err());
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn approx_matches_naive_dp(text in "[ACGT]{0,150}", pattern in "[ACGT]{1,20}", k in 0usize..4) {
+        let expected = naive_approx(text.as_bytes(), pattern.as_bytes(), k, |a, b| a == b);
+        assert_eq!(approx::<Nuc4>(&text, &pattern, k), expected);
+    }
+
+    #[test]
+    fn approx_long_patterns_use_blocks(text in "[ACGN]{0,300}", pattern in "[ACGN]{60,150}", k in 0usize..40) {
+        let expected = naive_approx(text.as_bytes(), pattern.as_bytes(), k, |a, b| a == b);
+        assert_eq!(approx::<Nuc5>(&text, &pattern, k), expected);
+    }
+
+    #[test]
+    fn approx_planted_with_edits(prefix in "[ACDEFGHIKLMNPQRSTVWY]{0,40}", pattern in "[ACDEFGHIKLMNPQRSTVWY]{70,100}", cut in 0usize..70) {
+        // Delete one symbol from the pattern before planting it
+        let planted = format!("{}{}", &pattern[..cut], &pattern[cut + 1..]);
+        let text = format!("{prefix}{planted}");
+        let hits = approx::<AA20>(&text, &pattern, 1);
+        // The prefix may restore the deleted symbol, giving an exact hit
+        assert!(hits.iter().any(|h| h.end == text.len()));
+    }
+
+    #[test]
+    fn iupac_matches_naive_dp(text in "[ACGTN]{0,150}", pattern in "[ACGTRYSWKMBDHVN]{1,80}", k in 0usize..3) {
+        let seq = Seq::<Nuc5>::try_from(text.as_str()).unwrap();
+        let iupac = |p: u8, t: u8| {
+            let mask = nuc::alphabet::IUPAC_TO_MASK[p as usize];
+            match t {
+                b'N' => mask == 0b1111,
+                _ => mask & nuc::alphabet::IUPAC_TO_MASK[t as usize] != 0,
+            }
+        };
+        let expected = naive_approx(text.as_bytes(), pattern.as_bytes(), k, iupac);
+        assert_eq!(seq.find_approx_iupac(&pattern, k).unwrap().collect::<Vec<_>>(), expected);
+    }
+
+}
@@ -137,6 +137,26 @@ proptest::proptest! {
         assert_eq!(seq.to_string(), &s[..trim_to]);
     }
 
+    // -- slice --
+
+    #[test]
+    fn nuc4_slice_matches_string(s in "[ATGC]{0,100}", a in 0usize..100, b in 0usize..100) {
+        let (start, end) = (a.min(b).min(s.len()), a.max(b).min(s.len()));
+        let seq = Seq::<Nuc4>::try_from(s.as_str()).unwrap();
+        let sliced = seq.slice(start..end);
+        assert_eq!(sliced.to_string(), &s[start..end]);
+        assert_eq!(sliced, Seq::<Nuc4>::try_from(&s[start..end]).unwrap());
+    }
+
+    #[test]
+    fn nuc5_slice_matches_string(s in "[ATGCN]{0,100}", a in 0usize..100, b in 0usize..100) {
+        let (start, end) = (a.min(b).min(s.len()), a.max(b).min(s.len()));
+        let seq = Seq::<Nuc5>::try_from(s.as_str()).unwrap();
+        let sliced = seq.slice(start..end);
+        assert_eq!(sliced.to_string(), &s[start..end]);
+        assert_eq!(sliced, Seq::<Nuc5>::try_from(&s[start..end]).unwrap());
+    }
+
     // -- ordering --
 
     #[test]
@@ -163,3 +183,24 @@ proptest::proptest! {
     }
 
 }
+
+#[test]
+fn reverse_complement_nuc5() {
+    let seq = Seq::<Nuc5>::try_from("AACGTN").unwrap();
+    assert_eq!(seq.reverse_complement().to_string(), "NACGTT");
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn reverse_complement_is_involution(s in "[ATGC]{0,100}") {
+        let seq = Seq::<Nuc4>::try_from(s.as_str()).unwrap();
+        let rc = seq.reverse_complement();
+        let expected: String = s.chars().rev().map(|c| match c {
+            'A' => 'T', 'C' => 'G', 'G' => 'C', _ => 'A',
+        }).collect();
+        assert_eq!(rc.to_string(), expected);
+        assert_eq!(rc.reverse_complement(), seq);
+    }
+
+}
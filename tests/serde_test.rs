@@ -0,0 +1,114 @@
+#![cfg(feature = "serde")]
+
+use nuc::{
+    alphabet::{Alphabet, AminoAcid, Nuc4, Nuc5, Nucleotide, AA20},
+    seq::Seq,
+};
+
+#[test]
+fn json_uses_ascii_string() {
+    let seq = Seq::<Nuc5>::try_from("ACGTN").unwrap();
+    assert_eq!(serde_json::to_string(&seq).unwrap(), "\"ACGTN\"");
+    let back: Seq<Nuc5> = serde_json::from_str("\"acgtn\"").unwrap();
+    assert_eq!(back, seq);
+}
+
+#[test]
+fn json_rejects_invalid_symbols() {
+    assert!(serde_json::from_str::<Seq<Nuc4>>("\"ACGN\"").is_err());
+    assert!(serde_json::from_str::<Seq<Nuc4>>("42").is_err());
+}
+
+#[test]
+fn binary_uses_packed_bytes() {
+    let seq = Seq::<Nuc4>::try_from("ACGTACGTA").unwrap();
+    let bytes = bincode::serialize(&seq).unwrap();
+    // u64 length, u64 byte count, then the packed bytes
+    assert_eq!(bytes.len(), 8 + 8 + 3);
+    assert_eq!(&bytes[16..], seq.as_bytes());
+    assert_eq!(bincode::deserialize::<Seq<Nuc4>>(&bytes).unwrap(), seq);
+}
+
+#[test]
+fn binary_rejects_inconsistent_data() {
+    let seq = Seq::<Nuc5>::try_from("ACGT").unwrap();
+    let mut bytes = bincode::serialize(&seq).unwrap();
+    bytes[0] = 7;
+    assert!(bincode::deserialize::<Seq<Nuc5>>(&bytes).is_err());
+
+    // 0b111 is not a Nuc5 symbol
+    let mut bytes = bincode::serialize(&seq).unwrap();
+    bytes[16] = 0xFF;
+    assert!(bincode::deserialize::<Seq<Nuc5>>(&bytes).is_err());
+}
+
+#[test]
+fn binary_rejects_padding_bits() {
+    // Nine Nuc4 symbols leave six unused bits in the last byte
+    let seq = Seq::<Nuc4>::try_from("ACGTACGTA").unwrap();
+    let mut bytes = bincode::serialize(&seq).unwrap();
+    *bytes.last_mut().unwrap() |= 0b0011_1111;
+    assert!(bincode::deserialize::<Seq<Nuc4>>(&bytes).is_err());
+
+    // Nuc5 packs two symbols per byte, leaving the top two bits unused
+    let seq = Seq::<Nuc5>::try_from("ACGT").unwrap();
+    let mut bytes = bincode::serialize(&seq).unwrap();
+    bytes[16] |= 0b1100_0000;
+    assert!(bincode::deserialize::<Seq<Nuc5>>(&bytes).is_err());
+    bytes[16] &= 0b0011_1111;
+    assert_eq!(bincode::deserialize::<Seq<Nuc5>>(&bytes).unwrap(), seq);
+}
+
+#[test]
+fn elements_roundtrip() {
+    assert_eq!(serde_json::to_string(&Nucleotide::G).unwrap(), "\"G\"");
+    assert_eq!(
+        serde_json::from_str::<AminoAcid>("\"w\"").unwrap(),
+        AminoAcid::W
+    );
+    assert!(serde_json::from_str::<Nucleotide>("\"X\"").is_err());
+
+    for &elem in AA20::ELEMENTS {
+        let bytes = bincode::serialize(&elem).unwrap();
+        assert_eq!(bytes, vec![elem as u8]);
+        assert_eq!(bincode::deserialize::<AminoAcid>(&bytes).unwrap(), elem);
+    }
+    assert!(bincode::deserialize::<Nucleotide>(&[5]).is_err());
+}
+
+#[test]
+fn embeds_in_structs() {
+    #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+    struct Primer {
+        name: String,
+        seq: Seq<Nuc4>,
+    }
+
+    let primer = Primer {
+        name: "fwd".to_string(),
+        seq: Seq::try_from("ACGTTGCA").unwrap(),
+    };
+    let json = serde_json::to_string(&primer).unwrap();
+    assert_eq!(json, r#"{"name":"fwd","seq":"ACGTTGCA"}"#);
+    assert_eq!(serde_json::from_str::<Primer>(&json).unwrap(), primer);
+    let bytes = bincode::serialize(&primer).unwrap();
+    assert_eq!(bincode::deserialize::<Primer>(&bytes).unwrap(), primer);
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn json_roundtrip(s in "[ACDEFGHIKLMNPQRSTVWY]{0,100}") {
+        let seq = Seq::<AA20>::try_from(s.as_str()).unwrap();
+        let json = serde_json::to_string(&seq).unwrap();
+        assert_eq!(serde_json::from_str::<Seq<AA20>>(&json).unwrap(), seq);
+    }
+
+    #[test]
+    fn binary_roundtrip(s in "[ACGTN]{0,100}") {
+        let seq = Seq::<Nuc5>::try_from(s.as_str()).unwrap();
+        let bytes = bincode::serialize(&seq).unwrap();
+        assert_eq!(bincode::deserialize::<Seq<Nuc5>>(&bytes).unwrap(), seq);
+    }
+
+}
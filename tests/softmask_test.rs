@@ -0,0 +1,65 @@
+use nuc::softmask::CaseMask;
+
+#[test]
+fn empty_mask() {
+    let mask = CaseMask::from_ascii(b"");
+    assert!(mask.is_empty());
+    assert_eq!(mask.intervals().count(), 0);
+}
+
+#[test]
+fn intervals_span_word_boundaries() {
+    let mask = CaseMask::from_intervals(200, [10..20, 60..130, 199..200]);
+    assert_eq!(
+        mask.intervals().collect::<Vec<_>>(),
+        vec![10..20, 60..130, 199..200]
+    );
+    assert_eq!(mask.count_masked(), 81);
+    assert!(mask.is_masked(64));
+    assert!(!mask.is_masked(130));
+}
+
+#[test]
+fn apply_lowercases_masked_positions() {
+    let mask = CaseMask::from_intervals(6, std::iter::once(1..3));
+    let mut ascii = b"ACGTAC".to_vec();
+    mask.apply(&mut ascii);
+    assert_eq!(ascii, b"AcgTAC");
+}
+
+#[test]
+#[should_panic(expected = "mask of length 6 applied to 5 bytes")]
+fn apply_rejects_length_mismatch() {
+    let mask = CaseMask::from_intervals(6, std::iter::once(1..3));
+    mask.apply(&mut b"ACGTA".to_vec());
+}
+
+proptest::proptest! {
+
+    #[test]
+    fn from_ascii_matches_case(s in "[ACGTacgt]{0,300}") {
+        let mask = CaseMask::from_ascii(s.as_bytes());
+        assert_eq!(mask.len(), s.len());
+        for (i, b) in s.bytes().enumerate() {
+            assert_eq!(mask.is_masked(i), b.is_ascii_lowercase());
+        }
+    }
+
+    #[test]
+    fn intervals_roundtrip(s in "[ACGTacgt]{0,300}") {
+        let mask = CaseMask::from_ascii(s.as_bytes());
+        let rebuilt = CaseMask::from_intervals(s.len(), mask.intervals());
+        assert_eq!(rebuilt, mask);
+        let mut ascii = s.to_uppercase().into_bytes();
+        mask.apply(&mut ascii);
+        assert_eq!(ascii, s.as_bytes());
+    }
+
+    #[test]
+    fn slice_matches_string(s in "[ACGTacgt]{0,300}", a in 0usize..300, b in 0usize..300) {
+        let (start, end) = (a.min(b).min(s.len()), a.max(b).min(s.len()));
+        let mask = CaseMask::from_ascii(s.as_bytes());
+        assert_eq!(mask.slice(start..end), CaseMask::from_ascii(&s.as_bytes()[start..end]));
+    }
+
+}